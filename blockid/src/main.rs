@@ -1,16 +1,15 @@
 use std::{
+    fs,
     io::{self, stdout},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 use clap::{Parser, Subcommand, ValueEnum};
 use libblockid::{
-    AlignmentOffset, Probe, ProbeFlags,
+    Probe, ProbeAttempt, ProbeFlags, ProbeOutcome, ProbeTree, SignatureType, WipedRegion, Wiper,
     error::Error,
     filesystem::{FS_DETECT_ORDER, FsFilter, FsInfo, FsType},
-    partition::{
-        PT_DETECT_ORDER, PtFilter, PtType, PtInfo, PtTag, Partition, PartitionType,
-    },
+    partition::{PT_DETECT_ORDER, PtFilter, PtType},
 };
 use serde::Serialize;
 use serde_dotenv::to_writer as to_dotenv_writer;
@@ -57,8 +56,54 @@ enum Commands {
         filesystem: Option<Vec<FsType>>,
 
         /// Set filter for what partition table type to parse for.
-        #[arg(short = 't', long = "type-filter", value_enum)]
+        #[arg(short = 'p', long = "pt-type-filter", value_enum)]
         part_table: Option<Vec<PtType>>,
+
+        /// Probe the partition table and the filesystem of every partition
+        #[arg(short = 'a', long = "all")]
+        all: bool,
+
+        /// Fail if signatures of more than one filesystem type are found
+        #[arg(short = 's', long = "safe")]
+        safe: bool,
+
+        /// Explain why each filesystem and partition table type did or did not match
+        #[arg(short = 'e', long = "explain")]
+        explain: bool,
+    },
+
+    /// Erase filesystem and partition table signatures from a device, listing
+    /// them when no signatures are selected
+    Wipe {
+        /// Block device path to wipe (e.g. /dev/sda)
+        #[arg(short = 'd', long = "device", value_name = "PATH")]
+        device: PathBuf,
+
+        /// Set the start offset in bytes to begin probing at
+        #[arg(short = 'o', long = "offset", value_name = "BYTES")]
+        offset: Option<u64>,
+
+        /// Erase every signature found
+        #[arg(short = 'a', long = "all")]
+        all: bool,
+
+        /// Only erase signatures of the given types (e.g. ext4,gpt)
+        #[arg(
+            short = 't',
+            long = "types",
+            value_name = "TYPES",
+            value_delimiter = ',',
+            value_parser = parse_signature_type
+        )]
+        types: Option<Vec<SignatureType>>,
+
+        /// Save the erased bytes of each signature to a file in DIR
+        #[arg(short = 'b', long = "backup", value_name = "DIR")]
+        backup: Option<PathBuf>,
+
+        /// Show what would be erased without writing to the device
+        #[arg(short = 'n', long = "dry-run")]
+        dry_run: bool,
     },
 
     /// Display I/O topology of a device
@@ -82,6 +127,16 @@ enum Format {
     Json,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+struct PartEntry<'a> {
+    part_entry_number: u64,
+    part_entry_offset: u64,
+    part_entry_size: u64,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    filesystem: Option<&'a FsInfo>,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "UPPERCASE")]
 struct Topology {
@@ -97,6 +152,82 @@ struct Topology {
     alignment_offset: Option<u64>,
 }
 
+fn write_export<T: Serialize>(value: &T) {
+    to_dotenv_writer(stdout(), value).expect("ahh, making dotenv failed.");
+    println!();
+}
+
+fn write_export_tree(tree: &ProbeTree<io::Error>, root: bool) {
+    for e in tree.fs_error.iter().chain(&tree.pt_error) {
+        eprintln!("0x{:x}: {}", tree.offset, e);
+    }
+
+    if root && let Some(info) = &tree.filesystem {
+        write_export(info);
+    }
+
+    if let Some(info) = &tree.part_table {
+        write_export(info);
+    }
+
+    for part in &tree.partitions {
+        let Some(contents) = &part.contents else {
+            continue;
+        };
+
+        write_export(&PartEntry {
+            part_entry_number: part.partition.part_no,
+            part_entry_offset: contents.offset,
+            part_entry_size: contents.size,
+            filesystem: contents.filesystem.as_ref(),
+        });
+
+        write_export_tree(contents, false);
+    }
+}
+
+fn parse_signature_type(name: &str) -> Result<SignatureType, String> {
+    FS_DETECT_ORDER
+        .iter()
+        .map(|(_, t)| SignatureType::Filesystem(*t))
+        .chain(
+            PT_DETECT_ORDER
+                .iter()
+                .map(|(_, t)| SignatureType::PartTable(*t)),
+        )
+        .find(|t| t.to_string() == name)
+        .ok_or_else(|| format!("unknown signature type `{name}`"))
+}
+
+fn write_backup(dir: &Path, device: &Path, region: &WipedRegion) -> Result<(), Error<io::Error>> {
+    let name = device
+        .file_name()
+        .map_or("device".into(), |n| n.to_string_lossy());
+    let path = dir.join(format!("blockid-{}-0x{:08x}.bak", name, region.offset));
+
+    fs::write(path, &region.backup)?;
+
+    Ok(())
+}
+
+fn write_attempt(attempt: &ProbeAttempt<io::Error>) {
+    let magic = attempt
+        .magic
+        .map(|m| format!("magic at 0x{:x}", m.b_offset));
+
+    match (&attempt.outcome, magic) {
+        (ProbeOutcome::Filtered, _) => println!("{}: skipped by filter", attempt.kind),
+        (ProbeOutcome::DeviceTooSmall, _) => println!("{}: device too small", attempt.kind),
+        (ProbeOutcome::MagicNotFound, _) => println!("{}: magic not found", attempt.kind),
+        (ProbeOutcome::Found(_), Some(magic)) => println!("{}: found, {}", attempt.kind, magic),
+        (ProbeOutcome::Found(_), None) => println!("{}: found", attempt.kind),
+        (ProbeOutcome::Rejected(e), Some(magic)) => {
+            println!("{}: {}, rejected: {}", attempt.kind, magic, e)
+        }
+        (ProbeOutcome::Rejected(e), None) => println!("{}: rejected: {}", attempt.kind, e),
+    }
+}
+
 fn main() {
     if let Err(e) = _main() {
         eprintln!("{}", e)
@@ -127,11 +258,40 @@ fn _main() -> Result<(), Error<io::Error>> {
                 device,
                 offset,
                 format,
-                filesystem,
-                part_table,
+                filesystem: _,
+                part_table: _,
+                all,
+                safe,
+                explain,
             } => {
-                let mut probe =
-                    Probe::open(device, ProbeFlags::empty(), offset.unwrap_or_default())?;
+                let mut flags = ProbeFlags::empty();
+                flags.set(ProbeFlags::FailOnAmbivalent, safe);
+
+                let mut probe = Probe::open(device, flags, offset.unwrap_or_default())?;
+
+                if explain {
+                    let report = probe.probe_report(FsFilter::empty(), PtFilter::empty());
+
+                    for attempt in report.attempts {
+                        write_attempt(&attempt);
+                    }
+
+                    return Ok(());
+                }
+
+                if all {
+                    let tree = probe.probe_all()?;
+
+                    match format.unwrap_or_default() {
+                        Format::Export => write_export_tree(&tree, true),
+                        Format::Json => {
+                            to_json_writer(stdout(), &tree).unwrap();
+                            println!();
+                        }
+                    }
+
+                    return Ok(());
+                }
 
                 match probe.probe_part_table(PtFilter::empty()) {
                     Ok(info) => {
@@ -175,6 +335,63 @@ fn _main() -> Result<(), Error<io::Error>> {
 
                 return Err(Error::ProbesExhausted);
             }
+            Commands::Wipe {
+                device,
+                offset,
+                all,
+                types,
+                backup,
+                dry_run,
+            } => {
+                let (mut fs_filter, mut pt_filter) = (FsFilter::empty(), PtFilter::empty());
+
+                if let Some(types) = &types {
+                    fs_filter = FsFilter::all();
+                    pt_filter = PtFilter::all();
+
+                    for (flag, fs_type) in FS_DETECT_ORDER {
+                        if types.contains(&SignatureType::Filesystem(*fs_type)) {
+                            fs_filter.remove(*flag);
+                        }
+                    }
+                    for (flag, pt_type) in PT_DETECT_ORDER {
+                        if types.contains(&SignatureType::PartTable(*pt_type)) {
+                            pt_filter.remove(*flag);
+                        }
+                    }
+                }
+
+                let mut wiper =
+                    Wiper::open(&device, ProbeFlags::empty(), offset.unwrap_or_default())?;
+                wiper.set_dry_run(dry_run || (!all && types.is_none()));
+
+                let dry_run = wiper.dry_run();
+
+                let regions = wiper.wipe_all_with(fs_filter, pt_filter, |region| {
+                    if let Some(dir) = &backup
+                        && !dry_run
+                    {
+                        write_backup(dir, &device, region)?;
+                    }
+
+                    Ok(())
+                })?;
+
+                for region in regions {
+                    let magic: Vec<String> =
+                        region.backup.iter().map(|b| format!("{b:02x}")).collect();
+
+                    println!(
+                        "{}: {} bytes {} erased at offset 0x{:08x} ({}): {}",
+                        device.display(),
+                        region.backup.len(),
+                        if wiper.dry_run() { "can be" } else { "were" },
+                        region.offset,
+                        region.kind,
+                        magic.join(" ")
+                    );
+                }
+            }
             Commands::Topology { device, format } => {
                 let probe = Probe::open(device, ProbeFlags::empty(), 0)?;
 
@@ -1,10 +1,16 @@
-use crate::std::fmt;
+use crate::{filesystem::FsType, std::fmt};
 pub use crate::{
     filesystem::{
-        apfs::ApfsError, cramfs::CramfsError, exfat::ExFatError, ext::ExtError, luks::LuksError,
-        ntfs::NtfsError, vfat::VFatError, vxfs::VxfsError, xfs::XfsError,
+        apfs::ApfsError, bcachefs::BcachefsError, bitlocker::BitlockerError, btrfs::BtrfsError,
+        cramfs::CramfsError, erofs::ErofsError, exfat::ExFatError, ext::ExtError, f2fs::F2fsError,
+        fw_raid::FwRaidError, hfs::HfsError, integrity::IntegrityError, iso9660::Iso9660Error,
+        jfs::JfsError, linux_raid::LinuxRaidError, luks::LuksError, lvm::LvmError,
+        minix::MinixError, nilfs::NilfsError, ntfs::NtfsError, reiserfs::ReiserfsError,
+        squashfs::SquashfsError, swap::SwapError, udf::UdfError, ufs::UfsError,
+        verity::VerityError, vfat::VFatError, vxfs::VxfsError, xfs::XfsError, zfs::ZfsError,
     },
-    partition::{aix::AixError, gpt::GptError, mbr::MbrError},
+    nvlist::NvListError,
+    partition::{aix::AixError, bsd::BsdError, gpt::GptError, mbr::MbrError},
 };
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -34,12 +40,56 @@ pub enum Error<E: fmt::Debug> {
     Xfs(XfsError),
     /// Errors returned from cramfs probeing logic
     Cramfs(CramfsError),
+    /// Errors returned from btrfs probing logic.
+    Btrfs(BtrfsError),
+    /// Errors returned from squashfs probing logic.
+    Squashfs(SquashfsError),
+    /// Errors returned from EROFS (Enhanced Read-Only File System) probing logic.
+    Erofs(ErofsError),
+    /// Errors returned from ISO9660 probing logic.
+    Iso9660(Iso9660Error),
+    /// Errors returned from UDF (Universal Disk Format) probing logic.
+    Udf(UdfError),
+    /// Errors returned from F2FS (Flash-Friendly File System) probing logic.
+    F2fs(F2fsError),
+    /// Errors returned from bcachefs probing logic.
+    Bcachefs(BcachefsError),
+    /// Errors returned from swap and suspend image probing logic.
+    Swap(SwapError),
+    /// Errors returned from HFS, HFS+ and HFSX probing logic.
+    Hfs(HfsError),
+    /// Errors returned from ZFS probing logic.
+    Zfs(ZfsError),
+    /// Errors returned from reiserfs probing logic.
+    Reiserfs(ReiserfsError),
+    /// Errors returned from JFS probing logic.
+    Jfs(JfsError),
+    /// Errors returned from NILFS2 probing logic.
+    Nilfs(NilfsError),
+    /// Errors returned from Minix probing logic.
+    Minix(MinixError),
+    /// Errors returned from UFS probing logic.
+    Ufs(UfsError),
+    /// Errors returned from Linux MD RAID probing logic.
+    LinuxRaid(LinuxRaidError),
+    /// Errors returned from LVM2 probing logic.
+    Lvm(LvmError),
+    /// Errors returned from firmware RAID probing logic.
+    FwRaid(FwRaidError),
+    /// Errors returned from dm-verity probing logic.
+    Verity(VerityError),
+    /// Errors returned from dm-integrity probing logic.
+    Integrity(IntegrityError),
+    /// Errors returned from BitLocker probing logic.
+    Bitlocker(BitlockerError),
     /// Errors returned from AIX probing logic.
     Aix(AixError),
     /// Errors returned from MBR (Master Boot Record) probing logic.
     Mbr(MbrError),
     /// Errors returned from GPT (GUID Partition Table) probing logic.
     Gpt(GptError),
+    /// Errors returned from BSD disklabel probing logic.
+    Bsd(BsdError),
     /// No magic signature was found at any expected offset.
     UnableToLocateMagicSignature,
     /// The device is smaller than the minimum required to hold
@@ -51,6 +101,9 @@ pub enum Error<E: fmt::Debug> {
     OffsetExceedsDeviceSize,
     /// All available probes were attempted and none succeeded.
     ProbesExhausted,
+    /// Signatures of more than one incompatible filesystem type were found,
+    /// listed in detection order.
+    Ambivalent(Vec<FsType>),
 }
 
 impl<E: fmt::Debug> fmt::Display for Error<E> {
@@ -63,12 +116,34 @@ impl<E: fmt::Debug> fmt::Display for Error<E> {
             Self::Ext(e) => write!(f, "Ext Error: {}", e),
             Self::Ntfs(e) => write!(f, "NTFS Error: {}", e),
             Self::VFat(e) => write!(f, "VFAT Error: {}", e),
-            Self::Vxfs(_) => write!(f, "VXFS Error"),
+            Self::Vxfs(e) => write!(f, "VXFS Error: {}", e),
             Self::Xfs(e) => write!(f, "XFS Error: {}", e),
             Self::Cramfs(e) => write!(f, "cramfs error: {}", e),
+            Self::Btrfs(e) => write!(f, "btrfs Error: {}", e),
+            Self::Squashfs(e) => write!(f, "squashfs Error: {}", e),
+            Self::Erofs(e) => write!(f, "EROFS Error: {}", e),
+            Self::Iso9660(e) => write!(f, "ISO9660 Error: {}", e),
+            Self::Udf(e) => write!(f, "UDF Error: {}", e),
+            Self::F2fs(e) => write!(f, "F2FS Error: {}", e),
+            Self::Bcachefs(e) => write!(f, "bcachefs Error: {}", e),
+            Self::Swap(e) => write!(f, "swap Error: {}", e),
+            Self::Hfs(e) => write!(f, "HFS Error: {}", e),
+            Self::Zfs(e) => write!(f, "ZFS Error: {}", e),
+            Self::Reiserfs(e) => write!(f, "reiserfs Error: {}", e),
+            Self::Jfs(e) => write!(f, "JFS Error: {}", e),
+            Self::Nilfs(e) => write!(f, "NILFS2 Error: {}", e),
+            Self::Minix(e) => write!(f, "Minix Error: {}", e),
+            Self::Ufs(e) => write!(f, "UFS Error: {}", e),
+            Self::LinuxRaid(e) => write!(f, "Linux RAID Error: {}", e),
+            Self::Lvm(e) => write!(f, "LVM Error: {}", e),
+            Self::FwRaid(e) => write!(f, "Firmware RAID Error: {}", e),
+            Self::Verity(e) => write!(f, "dm-verity Error: {}", e),
+            Self::Integrity(e) => write!(f, "dm-integrity Error: {}", e),
+            Self::Bitlocker(e) => write!(f, "BitLocker Error: {}", e),
             Self::Aix(_) => write!(f, "AIX Error"),
             Self::Mbr(e) => write!(f, "MBR Error: {}", e),
             Self::Gpt(e) => write!(f, "GPT Error: {}", e),
+            Self::Bsd(e) => write!(f, "BSD Error: {}", e),
             Self::UnableToLocateMagicSignature => write!(f, "unable to locate magic signature"),
             Self::DeviceTooSmall => write!(
                 f,
@@ -83,6 +158,13 @@ impl<E: fmt::Debug> fmt::Display for Error<E> {
             Self::ProbesExhausted => {
                 write!(f, "all available probes were attempted and none succeeded")
             }
+            Self::Ambivalent(types) => {
+                write!(f, "ambivalent result, found signatures of:")?;
+                for fs_type in types {
+                    write!(f, " {}", fs_type)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -1,12 +1,33 @@
 pub(crate) mod apfs;
+pub(crate) mod bcachefs;
+pub(crate) mod bitlocker;
+pub(crate) mod btrfs;
 pub(crate) mod cramfs;
+pub(crate) mod erofs;
 pub(crate) mod exfat;
 pub(crate) mod ext;
+pub(crate) mod f2fs;
+pub(crate) mod fw_raid;
+pub(crate) mod hfs;
+pub(crate) mod integrity;
+pub(crate) mod iso9660;
+pub(crate) mod jfs;
+pub(crate) mod linux_raid;
 pub(crate) mod luks;
+pub(crate) mod lvm;
+pub(crate) mod minix;
+pub(crate) mod nilfs;
 pub(crate) mod ntfs;
+pub(crate) mod reiserfs;
+pub(crate) mod squashfs;
+pub(crate) mod swap;
+pub(crate) mod udf;
+pub(crate) mod ufs;
+pub(crate) mod verity;
 pub(crate) mod vfat;
 pub(crate) mod vxfs;
 pub(crate) mod xfs;
+pub(crate) mod zfs;
 
 use bitflags::bitflags;
 use fat_volume_id::{id32::VolumeId32, id64::VolumeId64};
@@ -16,16 +37,47 @@ use crate::{
     error::Error,
     filesystem::{
         apfs::{APFS_MAGICS, APFS_MINSZ, probe_apfs},
+        bcachefs::{BCACHEFS_MAGICS, BCACHEFS_MINSZ, probe_bcachefs},
+        bitlocker::{BITLOCKER_MAGICS, BITLOCKER_MINSZ, probe_bitlocker},
+        btrfs::{BTRFS_MAGICS, BTRFS_MINSZ, probe_btrfs},
+        cramfs::{CRAMFS_MAGICS, CRAMFS_MINSZ, probe_cramfs},
+        erofs::{EROFS_MAGICS, EROFS_MINSZ, probe_erofs},
         exfat::{EXFAT_MAGICS, EXFAT_MINSZ, probe_exfat},
         ext::{EXT_MAGICS, EXT_MINSZ, probe_ext2, probe_ext3, probe_ext4, probe_jbd},
+        f2fs::{F2FS_MAGICS, F2FS_MINSZ, probe_f2fs},
+        fw_raid::{
+            DDF_MINSZ, FW_RAID_MAGICS, FW_RAID_MINSZ, PROMISE_MINSZ, probe_adaptec, probe_ddf,
+            probe_isw, probe_jmicron, probe_lsi, probe_nvidia, probe_promise, probe_silicon,
+            probe_via,
+        },
+        hfs::{
+            HFS_MAGICS, HFS_MINSZ, HFSPLUS_MAGICS, HFSPLUS_MINSZ, HFSX_MAGICS, HFSX_MINSZ,
+            probe_hfs, probe_hfsplus,
+        },
+        integrity::{INTEGRITY_MAGICS, INTEGRITY_MINSZ, probe_integrity},
+        iso9660::{ISO9660_MAGICS, ISO9660_MINSZ, probe_iso9660},
+        jfs::{JFS_MAGICS, JFS_MINSZ, probe_jfs},
+        linux_raid::{LINUX_RAID_MAGICS, LINUX_RAID_MINSZ, probe_linux_raid},
         luks::{
             LUKS1_MAGICS, LUKS1_MINSZ, LUKS2_MAGICS, LUKS2_MINSZ, LUKSOPAL_MAGICS, probe_luks_opal,
             probe_luks1, probe_luks2,
         },
+        lvm::{LVM2_MAGICS, LVM2_MINSZ, probe_lvm2},
+        minix::{MINIX_MAGICS, MINIX_MINSZ, probe_minix},
+        nilfs::{NILFS_MAGICS, NILFS_MINSZ, probe_nilfs},
         ntfs::{NTFS_MAGICS, NTFS_MINSZ, probe_ntfs},
+        reiserfs::{REISERFS_MAGICS, REISERFS_MINSZ, probe_reiserfs},
+        squashfs::{SQUASHFS_MAGICS, SQUASHFS_MINSZ, probe_squashfs},
+        swap::{
+            SWAP_MAGICS, SWAP_MINSZ, SWSUSPEND_MAGICS, SWSUSPEND_MINSZ, probe_swap, probe_swsuspend,
+        },
+        udf::{UDF_MAGICS, UDF_MINSZ, probe_udf},
+        ufs::{UFS_MAGICS, UFS_MINSZ, probe_ufs},
+        verity::{VERITY_MAGICS, VERITY_MINSZ, probe_verity},
         vfat::{VFAT_MAGICS, VFAT_MINSZ, probe_vfat},
         vxfs::{VXFS_MAGICS, VXFS_MINSZ, probe_vxfs},
-        xfs::{XFS_MAGICS, XFS_MINSZ, probe_xfs},
+        xfs::{XFS_LOG_MAGICS, XFS_LOG_MINSZ, XFS_MAGICS, XFS_MINSZ, probe_xfs, probe_xfs_log},
+        zfs::{ZFS_MAGICS, ZFS_MINSZ, probe_zfs},
     },
     io::{BlockIo, Reader},
     probe::{Endianness, Magic, ProbeFlags, Usage},
@@ -35,19 +87,73 @@ use crate::{
 /// Order used to detect filesystems
 #[rustfmt::skip]
 pub const FS_DETECT_ORDER: &[(FsFilter, FsType)] = &[
+    // A member of a mirror also shows the filesystem of the array, RAID
+    // members come first so they are reported instead.
+    (FsFilter::SKIP_LINUX_RAID_MEMBER, FsType::LinuxRaidMember),
+    (FsFilter::SKIP_DDF_RAID_MEMBER, FsType::DdfRaidMember),
+    (FsFilter::SKIP_ISW_RAID_MEMBER, FsType::IswRaidMember),
+    (FsFilter::SKIP_LSI_MEGA_RAID_MEMBER, FsType::LsiMegaRaidMember),
+    (FsFilter::SKIP_VIA_RAID_MEMBER, FsType::ViaRaidMember),
+    (FsFilter::SKIP_SILICON_MEDLEY_RAID_MEMBER, FsType::SiliconMedleyRaidMember),
+    (FsFilter::SKIP_NVIDIA_RAID_MEMBER, FsType::NvidiaRaidMember),
+    (FsFilter::SKIP_PROMISE_FASTTRACK_RAID_MEMBER, FsType::PromiseFasttrackRaidMember),
+    (FsFilter::SKIP_ADAPTEC_RAID_MEMBER, FsType::AdaptecRaidMember),
+    (FsFilter::SKIP_JMICRON_RAID_MEMBER, FsType::JmicronRaidMember),
+    (FsFilter::SKIP_LVM2_MEMBER, FsType::Lvm2Member),
     (FsFilter::SKIP_APFS, FsType::Apfs),
+    (FsFilter::SKIP_BCACHEFS, FsType::Bcachefs),
+    (FsFilter::SKIP_BTRFS, FsType::Btrfs),
+    (FsFilter::SKIP_CRAMFS, FsType::Cramfs),
+    (FsFilter::SKIP_EROFS, FsType::Erofs),
     (FsFilter::SKIP_EXFAT, FsType::Exfat),
     (FsFilter::SKIP_JBD, FsType::Jbd),
     (FsFilter::SKIP_EXT2, FsType::Ext2),
     (FsFilter::SKIP_EXT3, FsType::Ext3),
     (FsFilter::SKIP_EXT4, FsType::Ext4),
+    (FsFilter::SKIP_F2FS, FsType::F2fs),
+    (FsFilter::SKIP_HFS, FsType::Hfs),
+    (FsFilter::SKIP_HFSPLUS, FsType::HfsPlus),
+    (FsFilter::SKIP_HFSX, FsType::Hfsx),
+    // UDF bridge discs also carry ISO9660 descriptors, UDF is preferred.
+    (FsFilter::SKIP_UDF, FsType::Udf),
+    (FsFilter::SKIP_ISO9660, FsType::Iso9660),
+    (FsFilter::SKIP_JFS, FsType::Jfs),
     (FsFilter::SKIP_LUKS1, FsType::LUKS1),
     (FsFilter::SKIP_LUKS2, FsType::LUKS2),
     (FsFilter::SKIP_LUKS_OPAL, FsType::LUKSOpal),
+    (FsFilter::SKIP_DM_VERITY_HASH, FsType::DmVerityHash),
+    (FsFilter::SKIP_DM_INTEGRITY, FsType::DmIntegrity),
+    (FsFilter::SKIP_BITLOCKER, FsType::Bitlocker),
+    (FsFilter::SKIP_NILFS2, FsType::Nilfs2),
     (FsFilter::SKIP_NTFS, FsType::Ntfs),
+    (FsFilter::SKIP_REISERFS, FsType::Reiserfs),
+    (FsFilter::SKIP_SQUASHFS, FsType::Squashfs),
+    (FsFilter::SKIP_SWAP, FsType::Swap),
+    (FsFilter::SKIP_SWSUSPEND, FsType::Swsuspend),
+    (FsFilter::SKIP_UFS, FsType::Ufs),
     (FsFilter::SKIP_VFAT, FsType::Vfat),
     (FsFilter::SKIP_VXFS, FsType::Vxfs),
     (FsFilter::SKIP_XFS, FsType::Xfs),
+    (FsFilter::SKIP_XFS_EXTERNAL_LOG, FsType::XfsExternalLog),
+    (FsFilter::SKIP_ZFS, FsType::Zfs),
+    // Minix only has a two byte magic, give every other filesystem a chance
+    // first.
+    (FsFilter::SKIP_MINIX, FsType::Minix),
+];
+
+/// Pairs of filesystem types that are expected to be found together on one
+/// device, as they are detected from the same superblock. These never make a
+/// probe ambivalent, see [`ProbeFlags::FailOnAmbivalent`].
+#[rustfmt::skip]
+pub const FS_COMPATIBLE: &[(FsType, FsType)] = &[
+    (FsType::Jbd, FsType::Ext2),
+    (FsType::Jbd, FsType::Ext3),
+    (FsType::Jbd, FsType::Ext4),
+    (FsType::Ext2, FsType::Ext3),
+    (FsType::Ext2, FsType::Ext4),
+    (FsType::Ext3, FsType::Ext4),
+    (FsType::LUKS2, FsType::LUKSOpal),
+    (FsType::Iso9660, FsType::Udf),
 ];
 
 /// A generic handler for probing a filesystem type.
@@ -72,42 +178,119 @@ pub(crate) struct FsHandler<IO: BlockIo> {
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum FsType {
+    AdaptecRaidMember,
     Apfs,
+    Bcachefs,
+    Bitlocker,
+    Btrfs,
+    Cramfs,
+    DdfRaidMember,
+    DmIntegrity,
+    DmVerityHash,
+    Erofs,
     Exfat,
     Jbd,
     Ext2,
     Ext3,
     Ext4,
+    F2fs,
+    Hfs,
+    HfsPlus,
+    Hfsx,
+    Iso9660,
+    IswRaidMember,
+    Jfs,
+    JmicronRaidMember,
+    LinuxRaidMember,
+    LsiMegaRaidMember,
     LUKS1,
     LUKS2,
     LUKSOpal,
+    Lvm2Member,
+    Minix,
+    Nilfs2,
     Ntfs,
+    NvidiaRaidMember,
+    PromiseFasttrackRaidMember,
+    Reiserfs,
+    SiliconMedleyRaidMember,
+    Squashfs,
+    Swap,
+    Swsuspend,
+    Udf,
+    Ufs,
     Vfat,
+    ViaRaidMember,
     Vxfs,
     Xfs,
+    XfsExternalLog,
+    Zfs,
 }
 
 impl fmt::Display for FsType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            FsType::AdaptecRaidMember => write!(f, "adaptec_raid_member"),
             FsType::Apfs => write!(f, "apfs"),
+            FsType::Bcachefs => write!(f, "bcachefs"),
+            FsType::Bitlocker => write!(f, "BitLocker"),
+            FsType::Btrfs => write!(f, "btrfs"),
+            FsType::Cramfs => write!(f, "cramfs"),
+            FsType::DdfRaidMember => write!(f, "ddf_raid_member"),
+            FsType::DmIntegrity => write!(f, "DM_integrity"),
+            FsType::DmVerityHash => write!(f, "DM_verity_hash"),
+            FsType::Erofs => write!(f, "erofs"),
             FsType::Exfat => write!(f, "exfat"),
             FsType::Jbd => write!(f, "jbd"),
             FsType::Ext2 => write!(f, "ext2"),
             FsType::Ext3 => write!(f, "ext3"),
             FsType::Ext4 => write!(f, "ext4"),
+            FsType::F2fs => write!(f, "f2fs"),
+            FsType::Hfs => write!(f, "hfs"),
+            FsType::HfsPlus => write!(f, "hfsplus"),
+            FsType::Hfsx => write!(f, "hfsx"),
+            FsType::Iso9660 => write!(f, "iso9660"),
+            FsType::IswRaidMember => write!(f, "isw_raid_member"),
+            FsType::Jfs => write!(f, "jfs"),
+            FsType::JmicronRaidMember => write!(f, "jmicron_raid_member"),
+            FsType::LinuxRaidMember => write!(f, "linux_raid_member"),
+            FsType::LsiMegaRaidMember => write!(f, "lsi_mega_raid_member"),
             FsType::LUKS1 => write!(f, "luks1"),
             FsType::LUKS2 => write!(f, "luks2"),
             FsType::LUKSOpal => write!(f, "luks_opal"),
+            FsType::Lvm2Member => write!(f, "LVM2_member"),
+            FsType::Minix => write!(f, "minix"),
+            FsType::Nilfs2 => write!(f, "nilfs2"),
             FsType::Ntfs => write!(f, "ntfs"),
+            FsType::NvidiaRaidMember => write!(f, "nvidia_raid_member"),
+            FsType::PromiseFasttrackRaidMember => write!(f, "promise_fasttrack_raid_member"),
+            FsType::Reiserfs => write!(f, "reiserfs"),
+            FsType::SiliconMedleyRaidMember => write!(f, "silicon_medley_raid_member"),
+            FsType::Squashfs => write!(f, "squashfs"),
+            FsType::Swap => write!(f, "swap"),
+            FsType::Swsuspend => write!(f, "swsuspend"),
+            FsType::Udf => write!(f, "udf"),
+            FsType::Ufs => write!(f, "ufs"),
             FsType::Vfat => write!(f, "vfat"),
+            FsType::ViaRaidMember => write!(f, "via_raid_member"),
             FsType::Vxfs => write!(f, "vxfs"),
             FsType::Xfs => write!(f, "xfs"),
+            FsType::XfsExternalLog => write!(f, "xfs_external_log"),
+            FsType::Zfs => write!(f, "zfs_member"),
         }
     }
 }
 
 impl FsType {
+    /// Returns whether `self` and `other` may both match the same device
+    /// without the result being ambivalent.
+    pub fn is_compatible(&self, other: FsType) -> bool {
+        *self == other
+            || FS_COMPATIBLE
+                .iter()
+                .any(|&(a, b)| (a, b) == (*self, other) || (b, a) == (*self, other))
+    }
+
     pub(crate) const fn fs_handler<IO: BlockIo>(&self) -> FsHandler<IO> {
         match self {
             FsType::LUKS1 => FsHandler {
@@ -127,6 +310,21 @@ impl FsType {
                 magics: LUKSOPAL_MAGICS,
                 probe: probe_luks_opal,
             },
+            FsType::DmVerityHash => FsHandler {
+                minsz: VERITY_MINSZ,
+                magics: VERITY_MAGICS,
+                probe: probe_verity,
+            },
+            FsType::DmIntegrity => FsHandler {
+                minsz: INTEGRITY_MINSZ,
+                magics: INTEGRITY_MAGICS,
+                probe: probe_integrity,
+            },
+            FsType::Bitlocker => FsHandler {
+                minsz: BITLOCKER_MINSZ,
+                magics: BITLOCKER_MAGICS,
+                probe: probe_bitlocker,
+            },
             FsType::Exfat => FsHandler {
                 minsz: EXFAT_MINSZ,
                 magics: EXFAT_MAGICS,
@@ -137,11 +335,41 @@ impl FsType {
                 magics: EXT_MAGICS,
                 probe: probe_jbd,
             },
+            FsType::AdaptecRaidMember => FsHandler {
+                minsz: FW_RAID_MINSZ,
+                magics: FW_RAID_MAGICS,
+                probe: probe_adaptec,
+            },
             FsType::Apfs => FsHandler {
                 minsz: APFS_MINSZ,
                 magics: APFS_MAGICS,
                 probe: probe_apfs,
             },
+            FsType::Bcachefs => FsHandler {
+                minsz: BCACHEFS_MINSZ,
+                magics: BCACHEFS_MAGICS,
+                probe: probe_bcachefs,
+            },
+            FsType::Btrfs => FsHandler {
+                minsz: BTRFS_MINSZ,
+                magics: BTRFS_MAGICS,
+                probe: probe_btrfs,
+            },
+            FsType::Cramfs => FsHandler {
+                minsz: CRAMFS_MINSZ,
+                magics: CRAMFS_MAGICS,
+                probe: probe_cramfs,
+            },
+            FsType::DdfRaidMember => FsHandler {
+                minsz: DDF_MINSZ,
+                magics: FW_RAID_MAGICS,
+                probe: probe_ddf,
+            },
+            FsType::Erofs => FsHandler {
+                minsz: EROFS_MINSZ,
+                magics: EROFS_MAGICS,
+                probe: probe_erofs,
+            },
             FsType::Ext2 => FsHandler {
                 minsz: EXT_MINSZ,
                 magics: EXT_MAGICS,
@@ -157,16 +385,131 @@ impl FsType {
                 magics: EXT_MAGICS,
                 probe: probe_ext4,
             },
+            FsType::F2fs => FsHandler {
+                minsz: F2FS_MINSZ,
+                magics: F2FS_MAGICS,
+                probe: probe_f2fs,
+            },
+            FsType::Hfs => FsHandler {
+                minsz: HFS_MINSZ,
+                magics: HFS_MAGICS,
+                probe: probe_hfs,
+            },
+            FsType::HfsPlus => FsHandler {
+                minsz: HFSPLUS_MINSZ,
+                magics: HFSPLUS_MAGICS,
+                probe: probe_hfsplus,
+            },
+            FsType::Hfsx => FsHandler {
+                minsz: HFSX_MINSZ,
+                magics: HFSX_MAGICS,
+                probe: probe_hfsplus,
+            },
+            FsType::Iso9660 => FsHandler {
+                minsz: ISO9660_MINSZ,
+                magics: ISO9660_MAGICS,
+                probe: probe_iso9660,
+            },
+            FsType::IswRaidMember => FsHandler {
+                minsz: FW_RAID_MINSZ,
+                magics: FW_RAID_MAGICS,
+                probe: probe_isw,
+            },
+            FsType::Jfs => FsHandler {
+                minsz: JFS_MINSZ,
+                magics: JFS_MAGICS,
+                probe: probe_jfs,
+            },
+            FsType::JmicronRaidMember => FsHandler {
+                minsz: FW_RAID_MINSZ,
+                magics: FW_RAID_MAGICS,
+                probe: probe_jmicron,
+            },
+            FsType::LinuxRaidMember => FsHandler {
+                minsz: LINUX_RAID_MINSZ,
+                magics: LINUX_RAID_MAGICS,
+                probe: probe_linux_raid,
+            },
+            FsType::LsiMegaRaidMember => FsHandler {
+                minsz: FW_RAID_MINSZ,
+                magics: FW_RAID_MAGICS,
+                probe: probe_lsi,
+            },
+            FsType::Lvm2Member => FsHandler {
+                minsz: LVM2_MINSZ,
+                magics: LVM2_MAGICS,
+                probe: probe_lvm2,
+            },
+            FsType::Minix => FsHandler {
+                minsz: MINIX_MINSZ,
+                magics: MINIX_MAGICS,
+                probe: probe_minix,
+            },
+            FsType::Nilfs2 => FsHandler {
+                minsz: NILFS_MINSZ,
+                magics: NILFS_MAGICS,
+                probe: probe_nilfs,
+            },
             FsType::Ntfs => FsHandler {
                 minsz: NTFS_MINSZ,
                 magics: NTFS_MAGICS,
                 probe: probe_ntfs,
             },
+            FsType::NvidiaRaidMember => FsHandler {
+                minsz: FW_RAID_MINSZ,
+                magics: FW_RAID_MAGICS,
+                probe: probe_nvidia,
+            },
+            FsType::PromiseFasttrackRaidMember => FsHandler {
+                minsz: PROMISE_MINSZ,
+                magics: FW_RAID_MAGICS,
+                probe: probe_promise,
+            },
+            FsType::Reiserfs => FsHandler {
+                minsz: REISERFS_MINSZ,
+                magics: REISERFS_MAGICS,
+                probe: probe_reiserfs,
+            },
+            FsType::SiliconMedleyRaidMember => FsHandler {
+                minsz: FW_RAID_MINSZ,
+                magics: FW_RAID_MAGICS,
+                probe: probe_silicon,
+            },
+            FsType::Squashfs => FsHandler {
+                minsz: SQUASHFS_MINSZ,
+                magics: SQUASHFS_MAGICS,
+                probe: probe_squashfs,
+            },
+            FsType::Swap => FsHandler {
+                minsz: SWAP_MINSZ,
+                magics: SWAP_MAGICS,
+                probe: probe_swap,
+            },
+            FsType::Swsuspend => FsHandler {
+                minsz: SWSUSPEND_MINSZ,
+                magics: SWSUSPEND_MAGICS,
+                probe: probe_swsuspend,
+            },
+            FsType::Udf => FsHandler {
+                minsz: UDF_MINSZ,
+                magics: UDF_MAGICS,
+                probe: probe_udf,
+            },
+            FsType::Ufs => FsHandler {
+                minsz: UFS_MINSZ,
+                magics: UFS_MAGICS,
+                probe: probe_ufs,
+            },
             FsType::Vfat => FsHandler {
                 minsz: VFAT_MINSZ,
                 magics: VFAT_MAGICS,
                 probe: probe_vfat,
             },
+            FsType::ViaRaidMember => FsHandler {
+                minsz: FW_RAID_MINSZ,
+                magics: FW_RAID_MAGICS,
+                probe: probe_via,
+            },
             FsType::Vxfs => FsHandler {
                 minsz: VXFS_MINSZ,
                 magics: VXFS_MAGICS,
@@ -177,6 +520,16 @@ impl FsType {
                 magics: XFS_MAGICS,
                 probe: probe_xfs,
             },
+            FsType::XfsExternalLog => FsHandler {
+                minsz: XFS_LOG_MINSZ,
+                magics: XFS_LOG_MAGICS,
+                probe: probe_xfs_log,
+            },
+            FsType::Zfs => FsHandler {
+                minsz: ZFS_MINSZ,
+                magics: ZFS_MAGICS,
+                probe: probe_zfs,
+            },
         }
     }
 }
@@ -190,6 +543,13 @@ pub enum FsId {
     VolumeId32(VolumeId32),
     /// A 64-bit volume serial number.
     VolumeId64(VolumeId64),
+    /// An identifier derived from other metadata, for formats without one
+    /// of their own.
+    PseudoUuid(PseudoUuid),
+    /// The 32 character identifier LVM gives its objects.
+    Lvm(LvmUuid),
+    /// The 24 byte GUID of a SNIA DDF configuration.
+    Ddf(DdfGuid),
 }
 
 impl FsId {
@@ -213,6 +573,134 @@ impl FsId {
             _ => None,
         }
     }
+
+    pub fn as_pseudo_uuid(&self) -> Option<PseudoUuid> {
+        match self {
+            FsId::PseudoUuid(t) => Some(*t),
+            _ => None,
+        }
+    }
+
+    pub fn as_lvm(&self) -> Option<LvmUuid> {
+        match self {
+            FsId::Lvm(t) => Some(*t),
+            _ => None,
+        }
+    }
+
+    pub fn as_ddf(&self) -> Option<DdfGuid> {
+        match self {
+            FsId::Ddf(t) => Some(*t),
+            _ => None,
+        }
+    }
+}
+
+/// Identifier of up to 32 ASCII characters built from filesystem metadata,
+/// such as the volume creation date of ISO9660, the same as libblkid reports
+/// as the UUID of these formats.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct PseudoUuid {
+    len: u8,
+    bytes: [u8; 32],
+}
+
+impl PseudoUuid {
+    /// Returns `None` if `id` is longer than 32 bytes or not ASCII.
+    pub(crate) fn new(id: &str) -> Option<PseudoUuid> {
+        if id.len() > 32 || !id.is_ascii() {
+            return None;
+        }
+
+        let mut bytes = [0u8; 32];
+        bytes[..id.len()].copy_from_slice(id.as_bytes());
+
+        return Some(PseudoUuid {
+            len: id.len() as u8,
+            bytes,
+        });
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Only ever built from ASCII.
+        crate::std::str::from_utf8(&self.bytes[..usize::from(self.len)]).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for PseudoUuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Identifier of an LVM physical volume, volume group or logical volume, 32
+/// characters from `[0-9a-zA-Z!#]` shown in groups of 6-4-4-4-4-4-6.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct LvmUuid([u8; 32]);
+
+impl LvmUuid {
+    /// Returns `None` if `id` has characters LVM never uses in one.
+    pub(crate) fn from_bytes(id: [u8; 32]) -> Option<LvmUuid> {
+        if !id
+            .iter()
+            .all(|&c| c.is_ascii_alphanumeric() || c == b'!' || c == b'#')
+        {
+            return None;
+        }
+
+        return Some(LvmUuid(id));
+    }
+
+    /// The identifier as stored on disk, without dashes.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl fmt::Display for LvmUuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut start = 0;
+
+        for (i, len) in [6, 4, 4, 4, 4, 4, 6].into_iter().enumerate() {
+            if i != 0 {
+                write!(f, "-")?;
+            }
+            for &c in &self.0[start..start + len] {
+                write!(f, "{}", char::from(c))?;
+            }
+            start += len;
+        }
+
+        Ok(())
+    }
+}
+
+/// GUID of a SNIA DDF configuration, starting with the T10 vendor ID of the
+/// controller that created it and otherwise binary, shown as hex.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct DdfGuid([u8; 24]);
+
+impl DdfGuid {
+    pub(crate) fn from_bytes(guid: [u8; 24]) -> DdfGuid {
+        DdfGuid(guid)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 24] {
+        &self.0
+    }
+}
+
+impl fmt::Display for DdfGuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in self.0 {
+            write!(f, "{b:02x}")?;
+        }
+
+        Ok(())
+    }
 }
 
 impl From<Uuid> for FsId {
@@ -262,20 +750,26 @@ pub enum FsTag {
     ///     VolumeId32: `2a9d-b913`
     ///     VolumeId64: `17acf19235bcde78`
     FsId(FsId),
-    /// Sub member identifier.
-    SubMemberId(Uuid),
+    /// Identifier of this device within a multi-device filesystem or pool.
+    SubMemberId(FsId),
     /// External log identifier.
     ExtLogId(Uuid),
+    /// Whether the filesystem keeps its log on a separate device.
+    ExternalLog(bool),
     /// External journal identifier.
     ExtJournalId(Uuid),
+    /// Identifier the filesystem tags its metadata with, when it differs from
+    /// the one reported as [`FsTag::FsId`].
+    InternalId(Uuid),
     /// Usage string, Eg: `raid`, `filesystem`.
     Usage(Usage),
     /// Filesystem version.
     Version(String),
     /// Superblock magic string.
     Magic(Vec<u8>),
-    /// Superblock magic string offset.
-    MagicOffset(u64),
+    /// Superblock magic string offset, both relative to the probe offset and
+    /// absolute from the start of the device.
+    MagicOffset { relative: u64, absolute: u64 },
     /// Filesystem size.
     FsSize(u64),
     /// Last fsblock/total number of fsblocks.
@@ -288,6 +782,24 @@ pub enum FsTag {
     Endianness(Endianness),
     /// OS used to create filesystem.
     Creator(String),
+    /// Compression algorithm identifier, as numbered by the filesystem.
+    CompressionId(u16),
+    /// Application the filesystem was written by.
+    ApplicationId(String),
+    /// Publisher of the filesystem contents.
+    PublisherId(String),
+    /// System able to use the system area of the filesystem.
+    SystemId(String),
+    /// Name of the LVM volume group a physical volume belongs to.
+    VolumeGroup(String),
+    /// Size of the blocks of data a dm-verity hash device covers.
+    DataBlockSize(u64),
+    /// Size of the blocks of a dm-verity hash device.
+    HashBlockSize(u64),
+    /// Salt mixed into the hashes of a dm-verity hash device.
+    Salt(Vec<u8>),
+    /// Size in bytes of the tag dm-integrity keeps for each sector.
+    TagSize(u16),
 }
 
 #[derive(Debug)]
@@ -340,7 +852,7 @@ impl FsInfo {
         })
     }
 
-    pub fn sub_member_id(&self) -> Option<Uuid> {
+    pub fn sub_member_id(&self) -> Option<FsId> {
         self.tags.iter().find_map(|t| match t {
             FsTag::SubMemberId(t) => Some(*t),
             _ => None,
@@ -354,6 +866,13 @@ impl FsInfo {
         })
     }
 
+    pub fn external_log(&self) -> Option<bool> {
+        self.tags.iter().find_map(|t| match t {
+            FsTag::ExternalLog(t) => Some(*t),
+            _ => None,
+        })
+    }
+
     pub fn ext_journal_id(&self) -> Option<Uuid> {
         self.tags.iter().find_map(|t| match t {
             FsTag::ExtJournalId(t) => Some(*t),
@@ -361,6 +880,13 @@ impl FsInfo {
         })
     }
 
+    pub fn internal_id(&self) -> Option<Uuid> {
+        self.tags.iter().find_map(|t| match t {
+            FsTag::InternalId(t) => Some(*t),
+            _ => None,
+        })
+    }
+
     pub fn usage(&self) -> Option<Usage> {
         self.tags.iter().find_map(|t| match t {
             FsTag::Usage(t) => Some(*t),
@@ -384,7 +910,14 @@ impl FsInfo {
 
     pub fn magic_offset(&self) -> Option<u64> {
         self.tags.iter().find_map(|t| match t {
-            FsTag::MagicOffset(t) => Some(*t),
+            FsTag::MagicOffset { relative, .. } => Some(*relative),
+            _ => None,
+        })
+    }
+
+    pub fn magic_absolute_offset(&self) -> Option<u64> {
+        self.tags.iter().find_map(|t| match t {
+            FsTag::MagicOffset { absolute, .. } => Some(*absolute),
             _ => None,
         })
     }
@@ -430,6 +963,69 @@ impl FsInfo {
             _ => None,
         })
     }
+
+    pub fn compression_id(&self) -> Option<u16> {
+        self.tags.iter().find_map(|t| match t {
+            FsTag::CompressionId(t) => Some(*t),
+            _ => None,
+        })
+    }
+
+    pub fn application_id(&self) -> Option<&String> {
+        self.tags.iter().find_map(|t| match t {
+            FsTag::ApplicationId(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    pub fn publisher_id(&self) -> Option<&String> {
+        self.tags.iter().find_map(|t| match t {
+            FsTag::PublisherId(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    pub fn system_id(&self) -> Option<&String> {
+        self.tags.iter().find_map(|t| match t {
+            FsTag::SystemId(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    pub fn volume_group(&self) -> Option<&String> {
+        self.tags.iter().find_map(|t| match t {
+            FsTag::VolumeGroup(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    pub fn data_block_size(&self) -> Option<u64> {
+        self.tags.iter().find_map(|t| match t {
+            FsTag::DataBlockSize(t) => Some(*t),
+            _ => None,
+        })
+    }
+
+    pub fn hash_block_size(&self) -> Option<u64> {
+        self.tags.iter().find_map(|t| match t {
+            FsTag::HashBlockSize(t) => Some(*t),
+            _ => None,
+        })
+    }
+
+    pub fn salt(&self) -> Option<&[u8]> {
+        self.tags.iter().find_map(|t| match t {
+            FsTag::Salt(t) => Some(t.as_slice()),
+            _ => None,
+        })
+    }
+
+    pub fn tag_size(&self) -> Option<u16> {
+        self.tags.iter().find_map(|t| match t {
+            FsTag::TagSize(t) => Some(*t),
+            _ => None,
+        })
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -463,16 +1059,48 @@ impl serde::Serialize for FsInfo {
                     FsId::VolumeId64(id64) => {
                         map.serialize_entry("FS_ID", id64)?;
                     }
+                    FsId::PseudoUuid(id) => {
+                        map.serialize_entry("FS_ID", id.as_str())?;
+                    }
+                    FsId::Lvm(id) => {
+                        map.serialize_entry("FS_ID", &id.to_string())?;
+                    }
+                    FsId::Ddf(id) => {
+                        map.serialize_entry("FS_ID", &id.to_string())?;
+                    }
+                },
+                FsTag::SubMemberId(id) => match id {
+                    FsId::Uuid(uuid) => {
+                        map.serialize_entry("SUB_MEMBER_ID", uuid)?;
+                    }
+                    FsId::VolumeId32(id32) => {
+                        map.serialize_entry("SUB_MEMBER_ID", id32)?;
+                    }
+                    FsId::VolumeId64(id64) => {
+                        map.serialize_entry("SUB_MEMBER_ID", id64)?;
+                    }
+                    FsId::PseudoUuid(id) => {
+                        map.serialize_entry("SUB_MEMBER_ID", id.as_str())?;
+                    }
+                    FsId::Lvm(id) => {
+                        map.serialize_entry("SUB_MEMBER_ID", &id.to_string())?;
+                    }
+                    FsId::Ddf(id) => {
+                        map.serialize_entry("SUB_MEMBER_ID", &id.to_string())?;
+                    }
                 },
-                FsTag::SubMemberId(id) => {
-                    map.serialize_entry("SUB_MEMBER_ID", id)?;
-                }
                 FsTag::ExtLogId(id) => {
                     map.serialize_entry("EXT_LOG_ID", id)?;
                 }
+                FsTag::ExternalLog(external) => {
+                    map.serialize_entry("EXTERNAL_LOG", external)?;
+                }
                 FsTag::ExtJournalId(id) => {
                     map.serialize_entry("EXT_JOURNAL_ID", id)?;
                 }
+                FsTag::InternalId(id) => {
+                    map.serialize_entry("INTERNAL_ID", id)?;
+                }
                 FsTag::Usage(usage) => {
                     map.serialize_entry("USAGE", usage)?;
                 }
@@ -482,8 +1110,9 @@ impl serde::Serialize for FsInfo {
                 FsTag::Magic(mag) => {
                     map.serialize_entry("MAGIC", mag)?;
                 }
-                FsTag::MagicOffset(off) => {
-                    map.serialize_entry("MAGIC_OFFSET", off)?;
+                FsTag::MagicOffset { relative, absolute } => {
+                    map.serialize_entry("MAGIC_OFFSET", relative)?;
+                    map.serialize_entry("MAGIC_ABSOLUTE_OFFSET", absolute)?;
                 }
                 FsTag::FsSize(sz) => {
                     map.serialize_entry("FS_SIZE", sz)?;
@@ -503,6 +1132,33 @@ impl serde::Serialize for FsInfo {
                 FsTag::Creator(creator) => {
                     map.serialize_entry("CREATOR", creator)?;
                 }
+                FsTag::CompressionId(id) => {
+                    map.serialize_entry("COMPRESSION_ID", id)?;
+                }
+                FsTag::ApplicationId(id) => {
+                    map.serialize_entry("APPLICATION_ID", id)?;
+                }
+                FsTag::PublisherId(id) => {
+                    map.serialize_entry("PUBLISHER_ID", id)?;
+                }
+                FsTag::SystemId(id) => {
+                    map.serialize_entry("SYSTEM_ID", id)?;
+                }
+                FsTag::VolumeGroup(vg) => {
+                    map.serialize_entry("VOLUME_GROUP", vg)?;
+                }
+                FsTag::DataBlockSize(blk_sz) => {
+                    map.serialize_entry("DATA_BLOCK_SIZE", blk_sz)?;
+                }
+                FsTag::HashBlockSize(blk_sz) => {
+                    map.serialize_entry("HASH_BLOCK_SIZE", blk_sz)?;
+                }
+                FsTag::Salt(salt) => {
+                    map.serialize_entry("SALT", salt)?;
+                }
+                FsTag::TagSize(tag_sz) => {
+                    map.serialize_entry("TAG_SIZE", tag_sz)?;
+                }
             }
         }
 
@@ -527,5 +1183,39 @@ bitflags! {
         const SKIP_VFAT = 1 << 10;
         const SKIP_VXFS = 1 << 11;
         const SKIP_XFS = 1 << 12;
+        const SKIP_CRAMFS = 1 << 13;
+        const SKIP_BTRFS = 1 << 14;
+        const SKIP_XFS_EXTERNAL_LOG = 1 << 15;
+        const SKIP_SQUASHFS = 1 << 16;
+        const SKIP_EROFS = 1 << 17;
+        const SKIP_ISO9660 = 1 << 18;
+        const SKIP_UDF = 1 << 19;
+        const SKIP_F2FS = 1 << 20;
+        const SKIP_BCACHEFS = 1 << 21;
+        const SKIP_SWAP = 1 << 22;
+        const SKIP_SWSUSPEND = 1 << 23;
+        const SKIP_HFS = 1 << 24;
+        const SKIP_HFSPLUS = 1 << 25;
+        const SKIP_HFSX = 1 << 26;
+        const SKIP_ZFS = 1 << 27;
+        const SKIP_JFS = 1 << 28;
+        const SKIP_MINIX = 1 << 29;
+        const SKIP_NILFS2 = 1 << 30;
+        const SKIP_REISERFS = 1 << 31;
+        const SKIP_UFS = 1 << 32;
+        const SKIP_LINUX_RAID_MEMBER = 1 << 33;
+        const SKIP_LVM2_MEMBER = 1 << 34;
+        const SKIP_ISW_RAID_MEMBER = 1 << 35;
+        const SKIP_DDF_RAID_MEMBER = 1 << 36;
+        const SKIP_LSI_MEGA_RAID_MEMBER = 1 << 37;
+        const SKIP_PROMISE_FASTTRACK_RAID_MEMBER = 1 << 38;
+        const SKIP_NVIDIA_RAID_MEMBER = 1 << 39;
+        const SKIP_SILICON_MEDLEY_RAID_MEMBER = 1 << 40;
+        const SKIP_VIA_RAID_MEMBER = 1 << 41;
+        const SKIP_JMICRON_RAID_MEMBER = 1 << 42;
+        const SKIP_ADAPTEC_RAID_MEMBER = 1 << 43;
+        const SKIP_DM_VERITY_HASH = 1 << 44;
+        const SKIP_DM_INTEGRITY = 1 << 45;
+        const SKIP_BITLOCKER = 1 << 46;
     }
 }
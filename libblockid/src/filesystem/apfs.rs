@@ -148,7 +148,10 @@ pub fn probe_apfs<IO: BlockIo>(
     info.set(FsTag::BlockSize(u64::from(sb.block_size)));
     info.set(FsTag::Usage(Usage::Filesystem));
     info.set(FsTag::Magic(ApfsSuperBlock::MAGIC.to_vec()));
-    info.set(FsTag::MagicOffset(ApfsSuperBlock::MAGIC_OFFSET));
+    info.set(FsTag::MagicOffset {
+        relative: ApfsSuperBlock::MAGIC_OFFSET,
+        absolute: offset + ApfsSuperBlock::MAGIC_OFFSET,
+    });
 
     return Ok(info);
 }
@@ -0,0 +1,302 @@
+use crc::{Algorithm, CRC_32_ISCSI, CRC_64_ECMA_182, CRC_64_WE, Crc};
+use uuid::Uuid;
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    byteorder::{LittleEndian, U16, U32, U64},
+    transmute_ref,
+};
+
+use crate::{
+    error::Error,
+    filesystem::{FsId, FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader},
+    probe::{Magic, ProbeFlags, Usage},
+    std::{fmt, mem::offset_of, str::Utf8Error},
+    util::{decode_utf8_from, decode_utf8_lossy_from},
+};
+
+/*
+ * https://github.com/koverstreet/bcachefs/blob/master/fs/bcachefs/bcachefs_format.h
+ */
+
+#[derive(Debug, Clone)]
+pub enum BcachefsError {
+    Utf8Error(Utf8Error),
+    UnsupportedVersion(u16),
+    InvalidSize(u32),
+    UnknownChecksumType(u8),
+    InvalidChecksum,
+}
+
+impl fmt::Display for BcachefsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BcachefsError::Utf8Error(e) => {
+                write!(f, "Filesystem label contains invalid UTF-8: {e}")
+            }
+            BcachefsError::UnsupportedVersion(v) => write!(f, "Unsupported version: {v}"),
+            BcachefsError::InvalidSize(u64s) => {
+                write!(f, "Superblock larger than its layout allows: {u64s} u64s")
+            }
+            BcachefsError::UnknownChecksumType(t) => write!(f, "Unknown checksum type: {t}"),
+            BcachefsError::InvalidChecksum => write!(f, "Invalid superblock checksum"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<BcachefsError> for Error<E> {
+    fn from(e: BcachefsError) -> Self {
+        Error::Bcachefs(e)
+    }
+}
+
+const SUPER_OFFSET: u64 = 4096;
+const MAGIC_OFFSET: u64 = SUPER_OFFSET + offset_of!(BcachefsSuperBlock, magic) as u64;
+
+pub const BCACHEFS_MINSZ: Option<u64> = None;
+pub const BCACHEFS_MAGICS: Option<&'static [Magic]> = Some(&[
+    // Shared with bcache, told apart by the version.
+    Magic {
+        magic: b"\xc6\x85\x73\xf6\x4e\x1a\x45\xca\x82\x65\xf5\x7f\x48\xba\x6d\x81",
+        b_offset: MAGIC_OFFSET,
+    },
+    Magic {
+        magic: b"\xc6\x85\x73\xf6\x66\xce\x90\xa9\xd9\x6a\x60\xcf\x80\x3d\xf7\xef",
+        b_offset: MAGIC_OFFSET,
+    },
+]);
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct BcachefsSbLayout {
+    magic: [u8; 16],
+    layout_type: u8,
+    sb_max_size_bits: u8,
+    nr_superblocks: u8,
+    pad: [u8; 5],
+    sb_offset: [U64<LittleEndian>; 61],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct BcachefsSuperBlock {
+    csum: [u8; 16],
+    version: U16<LittleEndian>,
+    version_min: U16<LittleEndian>,
+    pad: [U16<LittleEndian>; 2],
+    magic: [u8; 16],
+    uuid: [u8; 16],
+    user_uuid: [u8; 16],
+    label: [u8; 32],
+    offset: U64<LittleEndian>,
+    seq: U64<LittleEndian>,
+    block_size: U16<LittleEndian>,
+    dev_idx: u8,
+    nr_devices: u8,
+    u64s: U32<LittleEndian>,
+    time_base_lo: U64<LittleEndian>,
+    time_base_hi: U32<LittleEndian>,
+    time_precision: U32<LittleEndian>,
+    flags: [U64<LittleEndian>; 7],
+    write_time: U64<LittleEndian>,
+    features: [U64<LittleEndian>; 2],
+    compat: [U64<LittleEndian>; 2],
+    layout: BcachefsSbLayout,
+}
+
+impl BcachefsSuperBlock {
+    /// Oldest on-disk version bcachefs still reads, bcache superblocks have
+    /// versions below it.
+    const MIN_VERSION: u16 = 9;
+
+    const SECTOR_SIZE: u64 = 512;
+
+    const CSUM_TYPE_NONE: u8 = 0;
+    const CSUM_TYPE_CRC32C_NONZERO: u8 = 1;
+    const CSUM_TYPE_CRC64_NONZERO: u8 = 2;
+    const CSUM_TYPE_CRC32C: u8 = 5;
+    const CSUM_TYPE_CRC64: u8 = 6;
+    const CSUM_TYPE_XXHASH: u8 = 7;
+
+    const FIELD_MEMBERS_V1: u32 = 1;
+    const FIELD_MEMBERS_V2: u32 = 11;
+    const MEMBER_V1_BYTES: usize = 56;
+
+    /// Checksum type, bits 2 to 7 of the first flags word.
+    fn csum_type(&self) -> u8 {
+        return ((self.flags[0].get() >> 2) & 0x3f) as u8;
+    }
+
+    /// Looks up the UUID of member `dev_idx` in the members field, along with
+    /// the member size in bytes.
+    fn member(&self, fields: &[u8]) -> Option<(Uuid, u64)> {
+        let mut fields = fields;
+
+        while fields.len() >= 8 {
+            let u64s = u32::from_le_bytes(fields[0..4].try_into().ok()?) as usize;
+            let field_type = u32::from_le_bytes(fields[4..8].try_into().ok()?);
+            let field_len = u64s.checked_mul(8)?;
+
+            if field_len < 8 || field_len > fields.len() {
+                return None;
+            }
+
+            let (start, stride) = match field_type {
+                BcachefsSuperBlock::FIELD_MEMBERS_V1 => (8, BcachefsSuperBlock::MEMBER_V1_BYTES),
+                BcachefsSuperBlock::FIELD_MEMBERS_V2 => (
+                    16,
+                    usize::from(u16::from_le_bytes(fields.get(8..10)?.try_into().ok()?)),
+                ),
+                _ => {
+                    fields = &fields[field_len..];
+                    continue;
+                }
+            };
+
+            let at = start + usize::from(self.dev_idx) * stride;
+            let member = fields[..field_len].get(at..at + 28)?;
+
+            let uuid = Uuid::from_bytes(member[..16].try_into().ok()?);
+            let nbuckets = u64::from_le_bytes(member[16..24].try_into().ok()?);
+            let bucket_size = u16::from_le_bytes(member[26..28].try_into().ok()?);
+
+            let size =
+                nbuckets.checked_mul(u64::from(bucket_size) * BcachefsSuperBlock::SECTOR_SIZE)?;
+
+            return Some((uuid, size));
+        }
+
+        return None;
+    }
+}
+
+/// Verifies the superblock checksum, calculated from the version to the end
+/// of the variable length fields.
+///
+/// Only crc32c and crc64 checksums are verified, superblocks using xxhash or
+/// an encryption checksum are accepted on their other fields alone. The
+/// nonzero variants are seeded with and inverted by all ones, the others
+/// are neither.
+fn verify_csum(sb: &BcachefsSuperBlock, raw: &[u8]) -> Result<(), BcachefsError> {
+    const CRC_32C_ZERO: Algorithm<u32> = Algorithm {
+        width: 32,
+        poly: 0x1edc6f41,
+        init: 0,
+        refin: true,
+        refout: true,
+        xorout: 0,
+        check: 0x58e3fa20,
+        residue: 0,
+    };
+
+    let data = &raw[offset_of!(BcachefsSuperBlock, version)..];
+
+    match sb.csum_type() {
+        BcachefsSuperBlock::CSUM_TYPE_NONE => {}
+        t @ (BcachefsSuperBlock::CSUM_TYPE_CRC32C_NONZERO
+        | BcachefsSuperBlock::CSUM_TYPE_CRC32C) => {
+            let alg = if t == BcachefsSuperBlock::CSUM_TYPE_CRC32C {
+                &CRC_32C_ZERO
+            } else {
+                &CRC_32_ISCSI
+            };
+            let calc_sum = Crc::<u32>::new(alg).checksum(data);
+
+            if sb.csum[..4] != calc_sum.to_le_bytes() {
+                return Err(BcachefsError::InvalidChecksum);
+            }
+        }
+        t @ (BcachefsSuperBlock::CSUM_TYPE_CRC64_NONZERO | BcachefsSuperBlock::CSUM_TYPE_CRC64) => {
+            let alg = if t == BcachefsSuperBlock::CSUM_TYPE_CRC64 {
+                &CRC_64_ECMA_182
+            } else {
+                &CRC_64_WE
+            };
+            let calc_sum = Crc::<u64>::new(alg).checksum(data);
+
+            if sb.csum[..8] != calc_sum.to_le_bytes() {
+                return Err(BcachefsError::InvalidChecksum);
+            }
+        }
+        t if t <= BcachefsSuperBlock::CSUM_TYPE_XXHASH => {}
+        t => return Err(BcachefsError::UnknownChecksumType(t)),
+    }
+
+    return Ok(());
+}
+
+/// Probes a bcachefs superblock. As with libblkid, the member is identified
+/// by its device UUID rather than by `dev_idx`, which is only used to find
+/// that UUID in the members field.
+pub fn probe_bcachefs<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    magic: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let buf: [u8; size_of::<BcachefsSuperBlock>()] = reader.read_exact_at(offset + SUPER_OFFSET)?;
+    let sb: &BcachefsSuperBlock = transmute_ref!(&buf);
+
+    let version = sb.version.get();
+    if version < BcachefsSuperBlock::MIN_VERSION {
+        return Err(BcachefsError::UnsupportedVersion(version).into());
+    }
+
+    let u64s = sb.u64s.get();
+    let sb_size = size_of::<BcachefsSuperBlock>() as u64 + u64::from(u64s) * 8;
+    let max_size = BcachefsSuperBlock::SECTOR_SIZE << sb.layout.sb_max_size_bits.min(16);
+
+    if sb_size > max_size {
+        return Err(BcachefsError::InvalidSize(u64s).into());
+    }
+
+    let raw = reader.read_vec_at(offset + SUPER_OFFSET, sb_size as usize)?;
+
+    verify_csum(sb, &raw)?;
+
+    let label = if sb.label[0] != 0 {
+        if flags.contains(ProbeFlags::FailOnInvalidUTF) {
+            Some(decode_utf8_from(&sb.label).map_err(BcachefsError::Utf8Error)?)
+        } else {
+            Some(decode_utf8_lossy_from(&sb.label))
+        }
+    } else {
+        None
+    };
+
+    let member = sb.member(&raw[size_of::<BcachefsSuperBlock>()..]);
+
+    let block_size = u64::from(sb.block_size.get()) * BcachefsSuperBlock::SECTOR_SIZE;
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Bcachefs));
+    if let Some(l) = label {
+        info.set(FsTag::Label(l));
+    }
+    // The internal UUID is only used to tag metadata, users and mount see
+    // the user UUID.
+    info.set(FsTag::FsId(FsId::Uuid(Uuid::from_bytes(sb.user_uuid))));
+    info.set(FsTag::InternalId(Uuid::from_bytes(sb.uuid)));
+    if let Some((uuid, _)) = member {
+        info.set(FsTag::SubMemberId(FsId::Uuid(uuid)));
+    }
+    info.set(FsTag::Usage(Usage::Filesystem));
+    info.set(FsTag::Version(format!(
+        "{}.{}",
+        version >> 10,
+        version & 0x3ff
+    )));
+    info.set(FsTag::Magic(magic.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    if let Some((_, size)) = member {
+        info.set(FsTag::FsSize(size));
+    }
+    info.set(FsTag::FsBlockSize(block_size));
+    info.set(FsTag::BlockSize(block_size));
+
+    return Ok(info);
+}
@@ -0,0 +1,300 @@
+use uuid::Uuid;
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    byteorder::{LittleEndian, U16, U32, U64},
+};
+
+use crate::{
+    error::Error,
+    filesystem::{FsId, FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader, SeekFrom},
+    probe::{Magic, ProbeFlags, Usage},
+    std::fmt,
+};
+
+/*
+ * https://github.com/libyal/libbde/blob/main/documentation/BitLocker%20Drive%20Encryption%20(BDE)%20format.asciidoc
+ * https://github.com/util-linux/util-linux/blob/master/libblkid/src/superblocks/bitlocker.c
+ */
+
+#[derive(Debug, Clone)]
+pub enum BitlockerError {
+    UnknownHeader,
+    MissingMetadata,
+}
+
+impl fmt::Display for BitlockerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BitlockerError::UnknownHeader => write!(f, "Unknown BitLocker volume header"),
+            BitlockerError::MissingMetadata => {
+                write!(f, "No valid FVE metadata block was found")
+            }
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<BitlockerError> for Error<E> {
+    fn from(e: BitlockerError) -> Self {
+        Error::Bitlocker(e)
+    }
+}
+
+const BDE_MAGIC_VISTA: [u8; 11] = *b"\xeb\x52\x90-FVE-FS-";
+const BDE_MAGIC_WIN7: [u8; 11] = *b"\xeb\x58\x90-FVE-FS-";
+/// BitLocker To Go keeps a FAT32 boot sector so older systems can read the
+/// unlock tool, the OEM name is the one a plain FAT32 volume has.
+const BDE_MAGIC_TOGO: [u8; 11] = *b"\xeb\x58\x90MSWIN4.1";
+const FVE_MAGIC: [u8; 8] = *b"-FVE-FS-";
+/// `4967d63b-2e29-4ad8-8399-f6a339e3d001` as stored, in the Windows 7 and
+/// To Go volume headers.
+const BDE_IDENTIFIER: [u8; 16] = [
+    0x3b, 0xd6, 0x67, 0x49, 0x29, 0x2e, 0xd8, 0x4a, 0x83, 0x99, 0xf6, 0xa3, 0x39, 0xe3, 0xd0, 0x01,
+];
+/// FVE metadata blocks are aligned to 64 bytes.
+const FVE_ALIGN: u64 = 64;
+
+pub const BITLOCKER_MINSZ: Option<u64> = Some(512);
+pub const BITLOCKER_MAGICS: Option<&'static [Magic]> = Some(&[
+    Magic {
+        magic: &BDE_MAGIC_VISTA,
+        b_offset: 0,
+    },
+    Magic {
+        magic: &BDE_MAGIC_WIN7,
+        b_offset: 0,
+    },
+    Magic {
+        magic: &BDE_MAGIC_TOGO,
+        b_offset: 0,
+    },
+]);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BitlockerKind {
+    Vista,
+    Win7,
+    ToGo,
+}
+
+impl BitlockerKind {
+    fn from_header(buf: &[u8]) -> Option<BitlockerKind> {
+        match buf.get(..11)? {
+            b if b == BDE_MAGIC_VISTA => return Some(BitlockerKind::Vista),
+            b if b == BDE_MAGIC_WIN7 => return Some(BitlockerKind::Win7),
+            b if b == BDE_MAGIC_TOGO => return Some(BitlockerKind::ToGo),
+            _ => return None,
+        }
+    }
+}
+
+/// Vista reuses the NTFS boot sector, the MFT mirror cluster points at the
+/// first FVE metadata block.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct BdeVistaHeader {
+    boot_entry_point: [u8; 3],
+    oem_id: [u8; 8],
+    bytes_per_sector: U16<LittleEndian>,
+    sectors_per_cluster: u8,
+    reserved: [u8; 42],
+    fve_metadata_cluster: U64<LittleEndian>,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct BdeWin7Header {
+    boot_entry_point: [u8; 3],
+    oem_id: [u8; 8],
+    reserved: [u8; 149],
+    guid: [u8; 16],
+    fve_metadata_offsets: [U64<LittleEndian>; 3],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct BdeToGoHeader {
+    boot_entry_point: [u8; 3],
+    oem_id: [u8; 8],
+    reserved: [u8; 413],
+    guid: [u8; 16],
+    fve_metadata_offsets: [U64<LittleEndian>; 3],
+}
+
+/// Start of an FVE metadata block, followed by the metadata header.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct FveBlockHeader {
+    signature: [u8; 8],
+    size: U16<LittleEndian>,
+    version: U16<LittleEndian>,
+    reserved: [u8; 4],
+    /// Only set from version 2.
+    volume_size: U64<LittleEndian>,
+    reserved2: [u8; 4],
+    volume_header_sectors: U32<LittleEndian>,
+    fve_metadata_offsets: [U64<LittleEndian>; 3],
+    volume_header_offset: U64<LittleEndian>,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct FveMetadataHeader {
+    size: U32<LittleEndian>,
+    version: U32<LittleEndian>,
+    header_size: U32<LittleEndian>,
+    size_copy: U32<LittleEndian>,
+    volume_guid: [u8; 16],
+    next_nonce: U32<LittleEndian>,
+    encryption_method: U32<LittleEndian>,
+    creation_time: U64<LittleEndian>,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct FveMetadata {
+    block: FveBlockHeader,
+    header: FveMetadataHeader,
+}
+
+/// Returns whether the volume header carries the BitLocker identifier, which
+/// Vista headers have none of.
+fn has_identifier(kind: BitlockerKind, buf: &[u8; 512]) -> bool {
+    match kind {
+        BitlockerKind::Vista => return true,
+        BitlockerKind::Win7 => {
+            return BdeWin7Header::ref_from_prefix(buf)
+                .is_ok_and(|(hdr, _)| hdr.guid == BDE_IDENTIFIER);
+        }
+        BitlockerKind::ToGo => {
+            return BdeToGoHeader::ref_from_prefix(buf)
+                .is_ok_and(|(hdr, _)| hdr.guid == BDE_IDENTIFIER);
+        }
+    }
+}
+
+/// Returns the offsets, relative to the start of the volume, the volume
+/// header says the FVE metadata blocks are at.
+fn fve_offsets(kind: BitlockerKind, buf: &[u8; 512]) -> [u64; 3] {
+    match kind {
+        BitlockerKind::Vista => {
+            let Ok((hdr, _)) = BdeVistaHeader::ref_from_prefix(buf) else {
+                return [0; 3];
+            };
+            let cluster_size =
+                u64::from(hdr.bytes_per_sector.get()) * u64::from(hdr.sectors_per_cluster);
+
+            return [
+                hdr.fve_metadata_cluster.get().saturating_mul(cluster_size),
+                0,
+                0,
+            ];
+        }
+        BitlockerKind::Win7 => {
+            let Ok((hdr, _)) = BdeWin7Header::ref_from_prefix(buf) else {
+                return [0; 3];
+            };
+            return hdr.fve_metadata_offsets.map(|o| o.get());
+        }
+        BitlockerKind::ToGo => {
+            let Ok((hdr, _)) = BdeToGoHeader::ref_from_prefix(buf) else {
+                return [0; 3];
+            };
+            return hdr.fve_metadata_offsets.map(|o| o.get());
+        }
+    }
+}
+
+/// Reads the first valid FVE metadata block the volume header points at.
+/// Windows keeps three copies, later ones are used when the first has been
+/// damaged.
+fn read_fve_metadata<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    offset: u64,
+    kind: BitlockerKind,
+    buf: &[u8; 512],
+) -> Result<Option<FveMetadata>, Error<IO::Error>> {
+    let end = reader.seek(SeekFrom::End(0))?;
+
+    for fve_offset in fve_offsets(kind, buf) {
+        if fve_offset == 0
+            || fve_offset % FVE_ALIGN != 0
+            || offset
+                .checked_add(fve_offset)
+                .and_then(|o| o.checked_add(size_of::<FveMetadata>() as u64))
+                .is_none_or(|e| e > end)
+        {
+            continue;
+        }
+
+        let fve_buf: [u8; size_of::<FveMetadata>()] = reader.read_exact_at(offset + fve_offset)?;
+        let Ok(fve) = FveMetadata::read_from_bytes(&fve_buf) else {
+            continue;
+        };
+
+        if fve.block.signature == FVE_MAGIC {
+            return Ok(Some(fve));
+        }
+    }
+
+    return Ok(None);
+}
+
+/// Every format needs a valid FVE metadata block, the 11 byte magic alone
+/// matches too much. The BitLocker To Go header starts like FAT32, so one
+/// without the identifier is not taken for BitLocker at all.
+fn find_bitlocker<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    offset: u64,
+) -> Result<FveMetadata, Error<IO::Error>> {
+    let buf: [u8; 512] = reader.read_exact_at(offset)?;
+    let kind = BitlockerKind::from_header(&buf).ok_or(BitlockerError::UnknownHeader)?;
+
+    if !has_identifier(kind, &buf) {
+        return Err(Error::UnableToLocateMagicSignature);
+    }
+
+    return read_fve_metadata(reader, offset, kind, &buf)?
+        .ok_or(BitlockerError::MissingMetadata.into());
+}
+
+pub fn probe_is_bitlocker<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    offset: u64,
+) -> Result<bool, Error<IO::Error>> {
+    match find_bitlocker(reader, offset) {
+        Ok(_) => return Ok(true),
+        Err(Error::Bitlocker(_) | Error::UnableToLocateMagicSignature) => return Ok(false),
+        Err(e) => return Err(e),
+    }
+}
+
+pub fn probe_bitlocker<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    _: ProbeFlags,
+    offset: u64,
+    magic: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let fve = find_bitlocker(reader, offset)?;
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Bitlocker));
+    info.set(FsTag::Usage(Usage::Crypto));
+    info.set(FsTag::Magic(magic.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    info.set(FsTag::FsId(FsId::Uuid(Uuid::from_bytes_le(
+        fve.header.volume_guid,
+    ))));
+    info.set(FsTag::Version(fve.block.version.get().to_string()));
+
+    let volume_size = fve.block.volume_size.get();
+    if volume_size != 0 {
+        info.set(FsTag::FsSize(volume_size));
+    }
+
+    return Ok(info);
+}
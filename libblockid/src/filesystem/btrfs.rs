@@ -0,0 +1,276 @@
+use crc::{CRC_32_ISCSI, Crc};
+use uuid::Uuid;
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    byteorder::{LittleEndian, U16, U32, U64},
+    transmute_ref,
+};
+
+use crate::{
+    error::Error,
+    filesystem::{FsId, FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader, SeekFrom},
+    probe::{Magic, ProbeFlags, Usage},
+    std::{fmt, str::Utf8Error},
+    util::{decode_utf8_from, decode_utf8_lossy_from},
+};
+
+/*
+ * https://github.com/torvalds/linux/blob/master/include/uapi/linux/btrfs_tree.h
+ */
+
+#[derive(Debug, Clone)]
+pub enum BtrfsError {
+    Utf8Error(Utf8Error),
+    InvalidBytenr,
+    InvalidSectorSize(u32),
+    InvalidNodeSize(u32),
+    UnknownChecksumType(u16),
+    InvalidChecksum,
+}
+
+impl fmt::Display for BtrfsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BtrfsError::Utf8Error(e) => write!(f, "Filesystem label contains invalid UTF-8: {e}"),
+            BtrfsError::InvalidBytenr => {
+                write!(f, "Superblock location does not match its offset")
+            }
+            BtrfsError::InvalidSectorSize(s) => write!(f, "Invalid sector size: {s}"),
+            BtrfsError::InvalidNodeSize(s) => write!(f, "Invalid node size: {s}"),
+            BtrfsError::UnknownChecksumType(t) => write!(f, "Unknown checksum type: {t}"),
+            BtrfsError::InvalidChecksum => write!(f, "Invalid superblock checksum"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<BtrfsError> for Error<E> {
+    fn from(e: BtrfsError) -> Self {
+        Error::Btrfs(e)
+    }
+}
+
+pub const BTRFS_MAGIC: [u8; 8] = *b"_BHRfS_M";
+/// Offset of the magic within a superblock.
+pub const MAGIC_OFFSET: u64 = 0x40;
+
+/// Offset of the primary superblock, followed by its mirror copies.
+pub const SUPER_OFFSETS: [u64; 3] = [0x10000, 0x4000000, 0x4000000000];
+
+pub const BTRFS_MINSZ: Option<u64> = Some(SUPER_OFFSETS[0] + BtrfsSuperBlock::INFO_SIZE as u64);
+pub const BTRFS_MAGICS: Option<&'static [Magic]> = Some(&[
+    Magic {
+        magic: &BTRFS_MAGIC,
+        b_offset: SUPER_OFFSETS[0] + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &BTRFS_MAGIC,
+        b_offset: SUPER_OFFSETS[1] + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &BTRFS_MAGIC,
+        b_offset: SUPER_OFFSETS[2] + MAGIC_OFFSET,
+    },
+]);
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct BtrfsDevItem {
+    devid: U64<LittleEndian>,
+    total_bytes: U64<LittleEndian>,
+    bytes_used: U64<LittleEndian>,
+    io_align: U32<LittleEndian>,
+    io_width: U32<LittleEndian>,
+    sector_size: U32<LittleEndian>,
+    dev_type: U64<LittleEndian>,
+    generation: U64<LittleEndian>,
+    start_offset: U64<LittleEndian>,
+    dev_group: U32<LittleEndian>,
+    seek_speed: u8,
+    bandwidth: u8,
+    uuid: [u8; 16],
+    fsid: [u8; 16],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct BtrfsSuperBlock {
+    csum: [u8; 32],
+    fsid: [u8; 16],
+    bytenr: U64<LittleEndian>,
+    flags: U64<LittleEndian>,
+    magic: [u8; 8],
+    generation: U64<LittleEndian>,
+    root: U64<LittleEndian>,
+    chunk_root: U64<LittleEndian>,
+    log_root: U64<LittleEndian>,
+    log_root_transid: U64<LittleEndian>,
+    total_bytes: U64<LittleEndian>,
+    bytes_used: U64<LittleEndian>,
+    root_dir_objectid: U64<LittleEndian>,
+    num_devices: U64<LittleEndian>,
+    sectorsize: U32<LittleEndian>,
+    nodesize: U32<LittleEndian>,
+    leafsize: U32<LittleEndian>,
+    stripesize: U32<LittleEndian>,
+    sys_chunk_array_size: U32<LittleEndian>,
+    chunk_root_generation: U64<LittleEndian>,
+    compat_flags: U64<LittleEndian>,
+    compat_ro_flags: U64<LittleEndian>,
+    incompat_flags: U64<LittleEndian>,
+    csum_type: U16<LittleEndian>,
+    root_level: u8,
+    chunk_root_level: u8,
+    log_root_level: u8,
+    dev_item: BtrfsDevItem,
+    label: [u8; 256],
+}
+
+impl BtrfsSuperBlock {
+    /// Size of the superblock area covered by the checksum.
+    const INFO_SIZE: usize = 4096;
+    const CSUM_SIZE: usize = 32;
+
+    const CSUM_TYPE_CRC32C: u16 = 0;
+    const CSUM_TYPE_BLAKE2: u16 = 3;
+
+    const MIN_SECTORSIZE: u32 = 4096;
+    const MAX_NODESIZE: u32 = 65536;
+
+    /// Checks the superblock found at `sb_offset` from the start of the
+    /// filesystem.
+    ///
+    /// Only crc32c checksums are verified, superblocks using xxhash64,
+    /// sha256 or blake2b are accepted on their other fields alone.
+    fn verify(&self, sb_offset: u64, raw: &[u8]) -> Result<(), BtrfsError> {
+        if self.bytenr.get() != sb_offset {
+            return Err(BtrfsError::InvalidBytenr);
+        }
+
+        let sectorsize = self.sectorsize.get();
+        if !sectorsize.is_power_of_two()
+            || !(BtrfsSuperBlock::MIN_SECTORSIZE..=BtrfsSuperBlock::MAX_NODESIZE)
+                .contains(&sectorsize)
+        {
+            return Err(BtrfsError::InvalidSectorSize(sectorsize));
+        }
+
+        let nodesize = self.nodesize.get();
+        if !nodesize.is_power_of_two()
+            || !(sectorsize..=BtrfsSuperBlock::MAX_NODESIZE).contains(&nodesize)
+        {
+            return Err(BtrfsError::InvalidNodeSize(nodesize));
+        }
+
+        match self.csum_type.get() {
+            BtrfsSuperBlock::CSUM_TYPE_CRC32C => {
+                let calc_sum = Crc::<u32>::new(&CRC_32_ISCSI)
+                    .checksum(&raw[BtrfsSuperBlock::CSUM_SIZE..BtrfsSuperBlock::INFO_SIZE]);
+
+                if self.csum[..4] != calc_sum.to_le_bytes() {
+                    return Err(BtrfsError::InvalidChecksum);
+                }
+            }
+            t if t <= BtrfsSuperBlock::CSUM_TYPE_BLAKE2 => {}
+            t => return Err(BtrfsError::UnknownChecksumType(t)),
+        }
+
+        return Ok(());
+    }
+}
+
+/// Reads and verifies the superblock at `sb_offset` from the start of the
+/// filesystem.
+fn read_super<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    offset: u64,
+    sb_offset: u64,
+) -> Result<BtrfsSuperBlock, Error<IO::Error>> {
+    let raw = reader.read_vec_at(offset + sb_offset, BtrfsSuperBlock::INFO_SIZE)?;
+
+    let buf: &[u8; size_of::<BtrfsSuperBlock>()] = raw[..size_of::<BtrfsSuperBlock>()]
+        .try_into()
+        .expect("superblock is smaller than INFO_SIZE");
+    let sb: &BtrfsSuperBlock = transmute_ref!(buf);
+
+    if sb.magic != BTRFS_MAGIC {
+        return Err(Error::UnableToLocateMagicSignature);
+    }
+
+    sb.verify(sb_offset, &raw)?;
+
+    return Ok(*sb);
+}
+
+pub fn probe_btrfs<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    _: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let end = reader.seek(SeekFrom::End(0))?;
+
+    // A damaged primary superblock is replaced by the first intact mirror,
+    // otherwise the error from the primary is returned.
+    let mut found = None;
+    let mut primary_err = None;
+
+    for sb_offset in SUPER_OFFSETS {
+        if offset + sb_offset + BtrfsSuperBlock::INFO_SIZE as u64 > end {
+            break;
+        }
+
+        match read_super(reader, offset, sb_offset) {
+            Ok(sb) => {
+                found = Some((sb_offset, sb));
+                break;
+            }
+            Err(Error::Io(e)) => return Err(Error::Io(e)),
+            Err(e) => {
+                primary_err.get_or_insert(e);
+            }
+        }
+    }
+
+    let Some((sb_offset, sb)) = found else {
+        return Err(primary_err.unwrap_or(Error::UnableToLocateMagicSignature));
+    };
+
+    let label = if sb.label[0] != 0 {
+        if flags.contains(ProbeFlags::FailOnInvalidUTF) {
+            Some(decode_utf8_from(&sb.label).map_err(BtrfsError::Utf8Error)?)
+        } else {
+            Some(decode_utf8_lossy_from(&sb.label))
+        }
+    } else {
+        None
+    };
+
+    let mag_offset = sb_offset + MAGIC_OFFSET;
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Btrfs));
+    if let Some(l) = label {
+        info.set(FsTag::Label(l));
+    }
+    info.set(FsTag::FsId(FsId::Uuid(Uuid::from_bytes(sb.fsid))));
+    info.set(FsTag::SubMemberId(FsId::Uuid(Uuid::from_bytes(
+        sb.dev_item.uuid,
+    ))));
+    info.set(FsTag::Usage(Usage::Filesystem));
+    info.set(FsTag::Version(sb.generation.get().to_string()));
+    info.set(FsTag::Magic(BTRFS_MAGIC.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: mag_offset,
+        absolute: offset + mag_offset,
+    });
+    info.set(FsTag::FsSize(sb.total_bytes.get()));
+    info.set(FsTag::FsLastBlock(
+        sb.total_bytes.get() / u64::from(sb.sectorsize.get()),
+    ));
+    info.set(FsTag::FsBlockSize(u64::from(sb.nodesize.get())));
+    info.set(FsTag::BlockSize(u64::from(sb.sectorsize.get())));
+
+    return Ok(info);
+}
@@ -1,22 +1,38 @@
-use std::mem::offset_of;
-
 use crc::{CRC_32_ISO_HDLC, Crc};
 use zerocopy::{FromBytes, Immutable, IntoBytes, Unaligned, transmute_ref};
 
 use crate::{
     error::Error,
-    filesystem::FsInfo,
-    io::{BlockIo, Reader},
-    probe::{Magic, ProbeFlags},
-    std::fmt,
+    filesystem::{FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader, SeekFrom},
+    probe::{Endianness, Magic, ProbeFlags, Usage},
+    std::{fmt, mem::offset_of, str::Utf8Error},
+    util::{decode_utf8_from, decode_utf8_lossy_from},
 };
 
+/*
+ * https://github.com/torvalds/linux/blob/master/include/uapi/linux/cramfs_fs.h
+ */
+
 #[derive(Debug, Clone)]
-pub enum CramfsError {}
+pub enum CramfsError {
+    Utf8Error(Utf8Error),
+    InvalidSize,
+    InvalidChecksum,
+}
 
 impl fmt::Display for CramfsError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        todo!()
+        match self {
+            CramfsError::Utf8Error(e) => write!(f, "Filesystem label contains invalid UTF-8: {e}"),
+            CramfsError::InvalidSize => {
+                write!(
+                    f,
+                    "Filesystem size is smaller than its superblock or past the end of the device"
+                )
+            }
+            CramfsError::InvalidChecksum => write!(f, "Invalid filesystem checksum"),
+        }
     }
 }
 
@@ -49,7 +65,7 @@ struct CramfsSuperBlock {
     flags: [u8; 4],
     future: [u8; 4],
     signature: [u8; 16],
-    crc: [u8; 2],
+    crc: [u8; 4],
     edition: [u8; 4],
     blocks: [u8; 4],
     files: [u8; 4],
@@ -58,31 +74,61 @@ struct CramfsSuperBlock {
 
 impl CramfsSuperBlock {
     const FLAG_FSID_VERSION_2: u32 = 0x00000001;
+    /// Size of the chunks the checksummed area is read in.
+    const CSUM_CHUNK: u64 = 1 << 16;
+}
+
+fn cfs32_to_cpu(le: bool, raw: [u8; 4]) -> u32 {
+    if le {
+        u32::from_le_bytes(raw)
+    } else {
+        u32::from_be_bytes(raw)
+    }
 }
 
+/// Verifies the CRC32 of the whole filesystem, calculated with the `crc`
+/// field zeroed. Sizes running past the end of the device are rejected
+/// before anything is read.
 fn verify_csum<IO: BlockIo>(
     reader: &mut Reader<IO>,
-    mag: Magic,
-    sb: CramfsSuperBlock,
+    offset: u64,
+    sb: &CramfsSuperBlock,
     le: bool,
 ) -> Result<(), Error<IO::Error>> {
-    let expected = if le {
-        u16::from_le_bytes(sb.crc)
-    } else {
-        u16::from_be_bytes(sb.crc)
-    };
+    let expected = cfs32_to_cpu(le, sb.crc);
+    let csummed_size = u64::from(cfs32_to_cpu(le, sb.size));
 
-    let csummed_size = if le {
-        u32::from_le_bytes(sb.size)
-    } else {
-        u32::from_be_bytes(sb.size)
-    };
+    let end = reader.seek(SeekFrom::End(0))?;
+
+    if csummed_size < size_of::<CramfsSuperBlock>() as u64
+        || csummed_size > end.saturating_sub(offset)
+    {
+        return Err(CramfsError::InvalidSize.into());
+    }
+
+    let crc = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+    let mut digest = crc.digest();
+
+    let mut pos = 0;
+    while pos < csummed_size {
+        let len = (csummed_size - pos).min(CramfsSuperBlock::CSUM_CHUNK) as usize;
+
+        let buf = if pos == 0 {
+            let crc_offset = offset_of!(CramfsSuperBlock, crc);
+            reader.read_at_exclude(offset, len, crc_offset..crc_offset + 4)?
+        } else {
+            reader.read_vec_at(offset + pos, len)?
+        };
 
-    if csummed_size > (1 << 16) || csummed_size < (size_of::<CramfsSuperBlock>() as u32) {
-        todo!()
+        digest.update(&buf);
+        pos += len as u64;
     }
 
-    todo!()
+    if digest.finalize() != expected {
+        return Err(CramfsError::InvalidChecksum.into());
+    }
+
+    return Ok(());
 }
 
 pub fn probe_cramfs<IO: BlockIo>(
@@ -97,12 +143,41 @@ pub fn probe_cramfs<IO: BlockIo>(
 
     let le = mag.magic == LITTLE_ENDIAN_MAGIC;
 
-    let v2 = (if le {
-        u32::from_le_bytes(sb.flags)
+    let v2 = cfs32_to_cpu(le, sb.flags) & CramfsSuperBlock::FLAG_FSID_VERSION_2 != 0;
+
+    if v2 {
+        verify_csum(reader, offset, sb, le)?;
+    }
+
+    let label = if sb.name[0] != 0 {
+        if flags.contains(ProbeFlags::FailOnInvalidUTF) {
+            Some(decode_utf8_from(&sb.name).map_err(CramfsError::Utf8Error)?)
+        } else {
+            Some(decode_utf8_lossy_from(&sb.name))
+        }
+    } else {
+        None
+    };
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Cramfs));
+    if let Some(l) = label {
+        info.set(FsTag::Label(l));
+    }
+    info.set(FsTag::Usage(Usage::Filesystem));
+    info.set(FsTag::Version(if v2 { "2" } else { "1" }.to_string()));
+    info.set(FsTag::Magic(mag.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: mag.b_offset,
+        absolute: offset + mag.b_offset,
+    });
+    info.set(FsTag::FsSize(u64::from(cfs32_to_cpu(le, sb.size))));
+    info.set(FsTag::Endianness(if le {
+        Endianness::Little
     } else {
-        u32::from_be_bytes(sb.flags)
-    }) & CramfsSuperBlock::FLAG_FSID_VERSION_2
-        != 0;
+        Endianness::Big
+    }));
 
-    todo!()
+    return Ok(info);
 }
@@ -0,0 +1,155 @@
+use crc::{CRC_32_ISCSI, Crc};
+use uuid::Uuid;
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    byteorder::{LittleEndian, U16, U32, U64},
+    transmute_ref,
+};
+
+use crate::{
+    error::Error,
+    filesystem::{FsId, FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader},
+    probe::{Magic, ProbeFlags, Usage},
+    std::{fmt, mem::offset_of, str::Utf8Error},
+    util::{decode_utf8_from, decode_utf8_lossy_from},
+};
+
+/*
+ * https://github.com/torvalds/linux/blob/master/fs/erofs/erofs_fs.h
+ */
+
+#[derive(Debug, Clone)]
+pub enum ErofsError {
+    Utf8Error(Utf8Error),
+    InvalidBlockSize(u8),
+    InvalidChecksum,
+}
+
+impl fmt::Display for ErofsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErofsError::Utf8Error(e) => write!(f, "Filesystem label contains invalid UTF-8: {e}"),
+            ErofsError::InvalidBlockSize(bits) => {
+                write!(f, "Invalid block size: 2^{bits}")
+            }
+            ErofsError::InvalidChecksum => write!(f, "Invalid superblock checksum"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<ErofsError> for Error<E> {
+    fn from(e: ErofsError) -> Self {
+        Error::Erofs(e)
+    }
+}
+
+const SUPER_OFFSET: u64 = 1024;
+
+pub const EROFS_MINSZ: Option<u64> = None;
+pub const EROFS_MAGICS: Option<&'static [Magic]> = Some(&[Magic {
+    magic: b"\xe2\xe1\xf5\xe0",
+    b_offset: SUPER_OFFSET,
+}]);
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct ErofsSuperBlock {
+    magic: U32<LittleEndian>,
+    checksum: U32<LittleEndian>,
+    feature_compat: U32<LittleEndian>,
+    blkszbits: u8,
+    sb_extslots: u8,
+    root_nid: U16<LittleEndian>,
+    inos: U64<LittleEndian>,
+    build_time: U64<LittleEndian>,
+    build_time_nsec: U32<LittleEndian>,
+    blocks: U32<LittleEndian>,
+    meta_blkaddr: U32<LittleEndian>,
+    xattr_blkaddr: U32<LittleEndian>,
+    uuid: [u8; 16],
+    volume_name: [u8; 16],
+    feature_incompat: U32<LittleEndian>,
+}
+
+impl ErofsSuperBlock {
+    const FEATURE_COMPAT_SB_CHKSUM: u32 = 0x00000001;
+
+    const MIN_BLKSZBITS: u8 = 9;
+    const MAX_BLKSZBITS: u8 = 16;
+}
+
+/// Verifies the crc32c of the superblock, calculated over the rest of its
+/// block with the `checksum` field zeroed.
+fn verify_csum<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    offset: u64,
+    sb: &ErofsSuperBlock,
+) -> Result<(), Error<IO::Error>> {
+    let mut len = 1usize << sb.blkszbits;
+    if len > SUPER_OFFSET as usize {
+        len -= SUPER_OFFSET as usize;
+    }
+
+    let csum_offset = offset_of!(ErofsSuperBlock, checksum);
+    let buf = reader.read_at_exclude(offset + SUPER_OFFSET, len, csum_offset..csum_offset + 4)?;
+
+    // The kernel does not invert the final value.
+    let calc_sum = !Crc::<u32>::new(&CRC_32_ISCSI).checksum(&buf);
+
+    if calc_sum != sb.checksum.get() {
+        return Err(ErofsError::InvalidChecksum.into());
+    }
+
+    return Ok(());
+}
+
+pub fn probe_erofs<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    magic: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let buf: [u8; size_of::<ErofsSuperBlock>()] = reader.read_exact_at(offset + SUPER_OFFSET)?;
+    let sb: &ErofsSuperBlock = transmute_ref!(&buf);
+
+    if !(ErofsSuperBlock::MIN_BLKSZBITS..=ErofsSuperBlock::MAX_BLKSZBITS).contains(&sb.blkszbits) {
+        return Err(ErofsError::InvalidBlockSize(sb.blkszbits).into());
+    }
+
+    if sb.feature_compat.get() & ErofsSuperBlock::FEATURE_COMPAT_SB_CHKSUM != 0 {
+        verify_csum(reader, offset, sb)?;
+    }
+
+    let label = if sb.volume_name[0] != 0 {
+        if flags.contains(ProbeFlags::FailOnInvalidUTF) {
+            Some(decode_utf8_from(&sb.volume_name).map_err(ErofsError::Utf8Error)?)
+        } else {
+            Some(decode_utf8_lossy_from(&sb.volume_name))
+        }
+    } else {
+        None
+    };
+
+    let block_size = 1u64 << sb.blkszbits;
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Erofs));
+    if let Some(l) = label {
+        info.set(FsTag::Label(l));
+    }
+    info.set(FsTag::FsId(FsId::Uuid(Uuid::from_bytes(sb.uuid))));
+    info.set(FsTag::Usage(Usage::Filesystem));
+    info.set(FsTag::Magic(magic.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    info.set(FsTag::FsSize(u64::from(sb.blocks.get()) * block_size));
+    info.set(FsTag::FsLastBlock(u64::from(sb.blocks.get())));
+    info.set(FsTag::FsBlockSize(block_size));
+    info.set(FsTag::BlockSize(block_size));
+
+    return Ok(info);
+}
@@ -132,10 +132,11 @@ impl ExFatSuperBlock {
     fn next_cluster<IO: BlockIo>(
         &self,
         reader: &mut Reader<IO>,
+        offset: u64,
         cluster: u32,
     ) -> Result<u32, Error<IO::Error>> {
         let fat_offset = self.block_to_offset(u64::from(self.fat_offset)) + (cluster as u64 * 4);
-        let next: [u8; 4] = reader.read_exact_at(fat_offset)?;
+        let next: [u8; 4] = reader.read_exact_at(offset + fat_offset)?;
 
         return Ok(u32::from_le_bytes(next));
     }
@@ -292,7 +293,7 @@ pub fn probe_is_exfat<IO: BlockIo>(
     let sb: &ExFatSuperBlock = transmute_ref!(&buf);
 
     if reader
-        .get_magic(EXFAT_MAGICS.expect("EXFAT magics is not `None`"))?
+        .get_magic(offset, EXFAT_MAGICS.expect("EXFAT magics is not `None`"))?
         .is_none()
     {
         return Ok(false);
@@ -307,17 +308,18 @@ pub fn probe_is_exfat<IO: BlockIo>(
 fn find_label<IO: BlockIo>(
     reader: &mut Reader<IO>,
     flags: ProbeFlags,
+    offset: u64,
     sb: &ExFatSuperBlock,
 ) -> Result<Option<String>, Error<IO::Error>> {
     let mut cluster = u32::from(sb.first_clustor_of_root);
-    let mut offset = sb.cluster_to_offset(cluster);
+    let mut entry_off = sb.cluster_to_offset(cluster);
 
     let mut i = 0;
     let mut buf: [u8; EXFAT_ENTRY_SIZE] = [0u8; 32];
 
     while i < 8388608 {
         // EXFAT_MAX_DIR_SIZE / EXFAT_ENTRY_SIZE
-        reader.read_at(offset, &mut buf)?;
+        reader.read_at(offset + entry_off, &mut buf)?;
 
         let entry: &ExfatEntryLabel = transmute_ref!(&buf);
 
@@ -340,17 +342,17 @@ fn find_label<IO: BlockIo>(
             return Ok(Some(label));
         }
 
-        offset += EXFAT_ENTRY_SIZE as u64;
+        entry_off += EXFAT_ENTRY_SIZE as u64;
 
-        if sb.cluster_size() != 0 && offset.is_multiple_of(sb.cluster_size() as u64) {
-            cluster = sb.next_cluster(reader, cluster)?;
+        if sb.cluster_size() != 0 && entry_off.is_multiple_of(sb.cluster_size() as u64) {
+            cluster = sb.next_cluster(reader, offset, cluster)?;
             if cluster < EXFAT_FIRST_DATA_CLUSTER {
                 return Ok(None);
             }
             if cluster > EXFAT_LAST_DATA_CLUSTER {
                 return Ok(None);
             }
-            offset = sb.cluster_to_offset(cluster);
+            entry_off = sb.cluster_to_offset(cluster);
         }
         i += 1;
     }
@@ -370,7 +372,7 @@ pub fn probe_exfat<IO: BlockIo>(
 
     valid_exfat(reader, offset, sb)?;
 
-    let label = find_label(reader, flags, sb)?;
+    let label = find_label(reader, flags, offset, sb)?;
 
     let version = format!("{}.{}", sb.vermaj, sb.vermin);
 
@@ -392,7 +394,10 @@ pub fn probe_exfat<IO: BlockIo>(
     info.set(FsTag::Usage(Usage::Filesystem));
     info.set(FsTag::Version(version));
     info.set(FsTag::Magic(mag.magic.to_vec()));
-    info.set(FsTag::MagicOffset(mag.b_offset));
+    info.set(FsTag::MagicOffset {
+        relative: mag.b_offset,
+        absolute: offset + mag.b_offset,
+    });
 
     return Ok(info);
 }
@@ -486,7 +486,10 @@ pub fn probe_jbd<IO: BlockIo>(
     info.set(FsTag::Usage(Usage::Filesystem));
     info.set(FsTag::Version(version));
     info.set(FsTag::Magic(magic.magic.to_vec()));
-    info.set(FsTag::MagicOffset(magic.b_offset));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
     info.set(FsTag::FsSize(fs_size));
     info.set(FsTag::FsLastBlock(fs_last_block));
     info.set(FsTag::FsBlockSize(block_size));
@@ -539,7 +542,10 @@ pub fn probe_ext2<IO: BlockIo>(
     info.set(FsTag::Usage(Usage::Filesystem));
     info.set(FsTag::Version(version));
     info.set(FsTag::Magic(magic.magic.to_vec()));
-    info.set(FsTag::MagicOffset(magic.b_offset));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
     info.set(FsTag::FsSize(fs_size));
     info.set(FsTag::FsLastBlock(fs_last_block));
     info.set(FsTag::FsBlockSize(block_size));
@@ -592,7 +598,10 @@ pub fn probe_ext3<IO: BlockIo>(
     info.set(FsTag::Usage(Usage::Filesystem));
     info.set(FsTag::Version(version));
     info.set(FsTag::Magic(magic.magic.to_vec()));
-    info.set(FsTag::MagicOffset(magic.b_offset));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
     info.set(FsTag::FsSize(fs_size));
     info.set(FsTag::FsLastBlock(fs_last_block));
     info.set(FsTag::FsBlockSize(block_size));
@@ -649,7 +658,10 @@ pub fn probe_ext4<IO: BlockIo>(
     info.set(FsTag::Usage(Usage::Filesystem));
     info.set(FsTag::Version(version));
     info.set(FsTag::Magic(magic.magic.to_vec()));
-    info.set(FsTag::MagicOffset(magic.b_offset));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
     info.set(FsTag::FsSize(fs_size));
     info.set(FsTag::FsLastBlock(fs_last_block));
     info.set(FsTag::FsBlockSize(block_size));
@@ -0,0 +1,229 @@
+use crc::{Algorithm, Crc};
+use uuid::Uuid;
+use widestring::error::Utf16Error;
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    byteorder::{LittleEndian, U16, U32, U64},
+    transmute_ref,
+};
+
+use crate::{
+    error::Error,
+    filesystem::{FsId, FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader},
+    probe::{Endianness, Magic, ProbeFlags, Usage},
+    std::{fmt, mem::offset_of},
+    util::{decode_utf16_from, decode_utf16_lossy_from},
+};
+
+/*
+ * https://github.com/torvalds/linux/blob/master/include/linux/f2fs_fs.h
+ */
+
+#[derive(Debug, Clone)]
+pub enum F2fsError {
+    Utf16Error(Utf16Error),
+    InvalidSectorSize(u32),
+    InvalidBlockSize(u32),
+    InvalidSegmentSize(u32),
+    InvalidChecksumOffset(u32),
+    InvalidChecksum,
+}
+
+impl fmt::Display for F2fsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            F2fsError::Utf16Error(e) => write!(f, "Volume name contains invalid UTF-16: {e}"),
+            F2fsError::InvalidSectorSize(bits) => write!(f, "Invalid sector size: 2^{bits}"),
+            F2fsError::InvalidBlockSize(bits) => write!(f, "Invalid block size: 2^{bits}"),
+            F2fsError::InvalidSegmentSize(bits) => {
+                write!(f, "Invalid blocks per segment: 2^{bits}")
+            }
+            F2fsError::InvalidChecksumOffset(o) => write!(f, "Invalid checksum offset: {o}"),
+            F2fsError::InvalidChecksum => write!(f, "Invalid superblock checksum"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<F2fsError> for Error<E> {
+    fn from(e: F2fsError) -> Self {
+        Error::F2fs(e)
+    }
+}
+
+const SUPER_OFFSET: u64 = 1024;
+const F2FS_MAGIC: u32 = 0xF2F52010;
+
+pub const F2FS_MINSZ: Option<u64> = None;
+pub const F2FS_MAGICS: Option<&'static [Magic]> = Some(&[Magic {
+    magic: b"\x10\x20\xf5\xf2",
+    b_offset: SUPER_OFFSET,
+}]);
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct F2fsSuperBlock {
+    magic: U32<LittleEndian>,
+    major_ver: U16<LittleEndian>,
+    minor_ver: U16<LittleEndian>,
+    log_sectorsize: U32<LittleEndian>,
+    log_sectors_per_block: U32<LittleEndian>,
+    log_blocksize: U32<LittleEndian>,
+    log_blocks_per_seg: U32<LittleEndian>,
+    segs_per_sec: U32<LittleEndian>,
+    secs_per_zone: U32<LittleEndian>,
+    checksum_offset: U32<LittleEndian>,
+    block_count: U64<LittleEndian>,
+    section_count: U32<LittleEndian>,
+    segment_count: U32<LittleEndian>,
+    segment_count_ckpt: U32<LittleEndian>,
+    segment_count_sit: U32<LittleEndian>,
+    segment_count_nat: U32<LittleEndian>,
+    segment_count_ssa: U32<LittleEndian>,
+    segment_count_main: U32<LittleEndian>,
+    segment0_blkaddr: U32<LittleEndian>,
+    cp_blkaddr: U32<LittleEndian>,
+    sit_blkaddr: U32<LittleEndian>,
+    nat_blkaddr: U32<LittleEndian>,
+    ssa_blkaddr: U32<LittleEndian>,
+    main_blkaddr: U32<LittleEndian>,
+    root_ino: U32<LittleEndian>,
+    node_ino: U32<LittleEndian>,
+    meta_ino: U32<LittleEndian>,
+    uuid: [u8; 16],
+    volume_name: [u8; 1024],
+    extension_count: U32<LittleEndian>,
+    extension_list: [[u8; 8]; 64],
+    cp_payload: U32<LittleEndian>,
+    version: [u8; 256],
+    init_version: [u8; 256],
+    feature: U32<LittleEndian>,
+    encryption_level: u8,
+    encrypt_pw_salt: [u8; 16],
+    devs: [[u8; 68]; 8],
+    qf_ino: [U32<LittleEndian>; 3],
+    hot_ext_count: u8,
+    s_encoding: U16<LittleEndian>,
+    s_encoding_flags: U16<LittleEndian>,
+    s_stop_reason: [u8; 32],
+    s_errors: [u8; 16],
+    reserved: [u8; 258],
+    crc: U32<LittleEndian>,
+}
+
+impl F2fsSuperBlock {
+    const FEATURE_SB_CHKSUM: u32 = 0x0800;
+
+    const MIN_LOG_SECTORSIZE: u32 = 9;
+    const MAX_LOG_SECTORSIZE: u32 = 12;
+    const MIN_LOG_BLOCKSIZE: u32 = 12;
+    const MAX_LOG_BLOCKSIZE: u32 = 14;
+    const LOG_BLOCKS_PER_SEG: u32 = 9;
+}
+
+/// Verifies the superblock crc32, which is seeded with the magic and not
+/// inverted, calculated up to `checksum_offset`.
+fn verify_csum(sb: &F2fsSuperBlock) -> Result<(), F2fsError> {
+    const F2FS_CRC: Algorithm<u32> = Algorithm {
+        width: 32,
+        poly: 0x04c11db7,
+        init: F2FS_MAGIC.reverse_bits(),
+        refin: true,
+        refout: true,
+        xorout: 0,
+        check: 0x1657a0c3,
+        residue: 0,
+    };
+
+    let csum_offset = sb.checksum_offset.get();
+
+    if csum_offset as usize != offset_of!(F2fsSuperBlock, crc) {
+        return Err(F2fsError::InvalidChecksumOffset(csum_offset));
+    }
+
+    let calc_sum = Crc::<u32>::new(&F2FS_CRC).checksum(&sb.as_bytes()[..csum_offset as usize]);
+
+    if calc_sum != sb.crc.get() {
+        return Err(F2fsError::InvalidChecksum);
+    }
+
+    return Ok(());
+}
+
+pub fn probe_f2fs<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    magic: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let buf: [u8; size_of::<F2fsSuperBlock>()] = reader.read_exact_at(offset + SUPER_OFFSET)?;
+    let sb: &F2fsSuperBlock = transmute_ref!(&buf);
+
+    let log_sectorsize = sb.log_sectorsize.get();
+    if !(F2fsSuperBlock::MIN_LOG_SECTORSIZE..=F2fsSuperBlock::MAX_LOG_SECTORSIZE)
+        .contains(&log_sectorsize)
+    {
+        return Err(F2fsError::InvalidSectorSize(log_sectorsize).into());
+    }
+
+    let log_blocksize = sb.log_blocksize.get();
+    if !(F2fsSuperBlock::MIN_LOG_BLOCKSIZE..=F2fsSuperBlock::MAX_LOG_BLOCKSIZE)
+        .contains(&log_blocksize)
+        || log_sectorsize.checked_add(sb.log_sectors_per_block.get()) != Some(log_blocksize)
+    {
+        return Err(F2fsError::InvalidBlockSize(log_blocksize).into());
+    }
+
+    let log_blocks_per_seg = sb.log_blocks_per_seg.get();
+    if log_blocks_per_seg != F2fsSuperBlock::LOG_BLOCKS_PER_SEG {
+        return Err(F2fsError::InvalidSegmentSize(log_blocks_per_seg).into());
+    }
+
+    if sb.feature.get() & F2fsSuperBlock::FEATURE_SB_CHKSUM != 0 {
+        verify_csum(sb)?;
+    }
+
+    let label = if sb.volume_name[..2] != [0, 0] {
+        if flags.contains(ProbeFlags::FailOnInvalidUTF) {
+            Some(
+                decode_utf16_from(&sb.volume_name, Endianness::Little)
+                    .map_err(F2fsError::Utf16Error)?
+                    .to_string(),
+            )
+        } else {
+            Some(decode_utf16_lossy_from(&sb.volume_name, Endianness::Little).to_string())
+        }
+    } else {
+        None
+    };
+
+    let block_size = 1u64 << log_blocksize;
+    let segment_size = block_size << log_blocks_per_seg;
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::F2fs));
+    if let Some(l) = label {
+        info.set(FsTag::Label(l));
+    }
+    info.set(FsTag::FsId(FsId::Uuid(Uuid::from_bytes(sb.uuid))));
+    info.set(FsTag::Usage(Usage::Filesystem));
+    info.set(FsTag::Version(format!(
+        "{}.{}",
+        sb.major_ver.get(),
+        sb.minor_ver.get()
+    )));
+    info.set(FsTag::Magic(magic.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    info.set(FsTag::FsSize(
+        u64::from(sb.segment_count.get()) * segment_size,
+    ));
+    info.set(FsTag::FsLastBlock(sb.block_count.get()));
+    info.set(FsTag::FsBlockSize(block_size));
+    info.set(FsTag::BlockSize(1 << log_sectorsize));
+
+    return Ok(info);
+}
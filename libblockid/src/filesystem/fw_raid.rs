@@ -0,0 +1,725 @@
+use crc::{CRC_32_ISO_HDLC, Crc};
+use fat_volume_id::id32::VolumeId32;
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    byteorder::{BigEndian, LittleEndian, U16, U32},
+    transmute_ref,
+};
+
+use crate::{
+    error::Error,
+    filesystem::{DdfGuid, FsId, FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader, SeekFrom},
+    probe::{Endianness, Magic, ProbeFlags, Usage},
+    std::{fmt, mem::offset_of},
+    util::decode_utf8_lossy_from,
+};
+
+/*
+ * Metadata written by BIOS and controller RAID implementations, all of it kept
+ * in the last sectors of each member disk.
+ *
+ * https://github.com/util-linux/util-linux/tree/master/libblkid/src/superblocks
+ * https://www.snia.org/tech_activities/standards/curr_standards/ddf
+ */
+
+#[derive(Debug, Clone)]
+pub enum FwRaidError {
+    InvalidChecksum,
+    UnsupportedVersion(u8),
+    InvalidRaidLevel(u8),
+    InvalidSize(u32),
+    MissingPrimaryHeader,
+}
+
+impl fmt::Display for FwRaidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FwRaidError::InvalidChecksum => write!(f, "Invalid metadata checksum"),
+            FwRaidError::UnsupportedVersion(v) => write!(f, "Unsupported metadata version: {v}"),
+            FwRaidError::InvalidRaidLevel(l) => write!(f, "Invalid RAID level: {l}"),
+            FwRaidError::InvalidSize(s) => write!(f, "Invalid metadata size: {s}"),
+            FwRaidError::MissingPrimaryHeader => {
+                write!(f, "Anchor points to a missing primary header")
+            }
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<FwRaidError> for Error<E> {
+    fn from(e: FwRaidError) -> Self {
+        Error::FwRaid(e)
+    }
+}
+
+const SECTOR_SIZE: u64 = 512;
+
+pub const FW_RAID_MINSZ: Option<u64> = Some(0x10000);
+/// DDF anchors may be up to 257 sectors from the end.
+pub const DDF_MINSZ: Option<u64> = Some(0x30000);
+/// Promise metadata may be up to 3087 sectors from the end, smaller disks
+/// only have the nearer locations checked.
+pub const PROMISE_MINSZ: Option<u64> = Some(0x40000);
+/// Metadata is placed from the end of the device, the prober of each format
+/// finds its own.
+pub const FW_RAID_MAGICS: Option<&'static [Magic]> = None;
+
+/// Reads the sector `n` sectors back from the end of the device, the last
+/// sector being 1, returning its offset from `offset` with its contents.
+fn read_sector_from_end<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    offset: u64,
+    n: u64,
+) -> Result<(u64, [u8; SECTOR_SIZE as usize]), Error<IO::Error>> {
+    let size = reader.seek(SeekFrom::End(0))?.saturating_sub(offset);
+    let sb_offset = (size / SECTOR_SIZE)
+        .checked_sub(n)
+        .ok_or(Error::UnableToLocateMagicSignature)?
+        * SECTOR_SIZE;
+
+    let buf = reader.read_exact_at(offset + sb_offset)?;
+
+    return Ok((sb_offset, buf));
+}
+
+const ISW_SIGNATURE: [u8; 24] = *b"Intel Raid ISM Cfg Sig. ";
+
+/// Intel Matrix Storage Manager anchor, the start of the metadata.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct IswMetadata {
+    sig: [u8; 24],
+    version: [u8; 6],
+    pad0: [u8; 2],
+    check_sum: U32<LittleEndian>,
+    mpb_size: U32<LittleEndian>,
+    family_num: U32<LittleEndian>,
+    generation_num: U32<LittleEndian>,
+    pad1: [u8; 464],
+}
+
+impl IswMetadata {
+    /// Smallest metadata, the anchor up to its generation number.
+    const MIN_SIZE: u32 = offset_of!(IswMetadata, pad1) as u32;
+    /// Largest metadata mdadm reads, its `MAX_SIGNATURE_LENGTH`.
+    const MAX_SIZE: u32 = 32 << 10;
+
+    /// Verifies the checksum of the `mpb_size` bytes of metadata, the sum of
+    /// its 32-bit words taken without the checksum itself.
+    fn verify_csum(&self, mpb: &[u8]) -> Result<(), FwRaidError> {
+        let sum = mpb.chunks_exact(4).fold(0u32, |sum, word| {
+            sum.wrapping_add(u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+        });
+
+        if sum.wrapping_sub(self.check_sum.get()) != self.check_sum.get() {
+            return Err(FwRaidError::InvalidChecksum);
+        }
+
+        return Ok(());
+    }
+}
+
+/// Reads the `mpb_size` bytes of metadata starting with the anchor at
+/// `sb_offset`. Metadata larger than a sector continues in the sectors in
+/// front of the anchor.
+fn read_isw_mpb<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    offset: u64,
+    sb_offset: u64,
+    anchor: &[u8; SECTOR_SIZE as usize],
+    mpb_size: u32,
+) -> Result<Vec<u8>, Error<IO::Error>> {
+    if !(IswMetadata::MIN_SIZE..=IswMetadata::MAX_SIZE).contains(&mpb_size) {
+        return Err(FwRaidError::InvalidSize(mpb_size).into());
+    }
+
+    let mut mpb = anchor.to_vec();
+    let extended = u64::from(mpb_size).div_ceil(SECTOR_SIZE) - 1;
+
+    if extended > 0 {
+        let ext_offset = sb_offset
+            .checked_sub(extended * SECTOR_SIZE)
+            .ok_or(FwRaidError::InvalidSize(mpb_size))?;
+
+        mpb.extend(reader.read_vec_at(offset + ext_offset, (extended * SECTOR_SIZE) as usize)?);
+    }
+    mpb.truncate(mpb_size as usize);
+
+    return Ok(mpb);
+}
+
+pub fn probe_isw<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    _: ProbeFlags,
+    offset: u64,
+    _: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let (sb_offset, buf) = read_sector_from_end(reader, offset, 2)?;
+    let isw: &IswMetadata = transmute_ref!(&buf);
+
+    if isw.sig != ISW_SIGNATURE {
+        return Err(Error::UnableToLocateMagicSignature);
+    }
+
+    let mpb = read_isw_mpb(reader, offset, sb_offset, &buf, isw.mpb_size.get())?;
+    isw.verify_csum(&mpb)?;
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::IswRaidMember));
+    // Members of the same container share the family number.
+    info.set(FsTag::FsId(FsId::VolumeId32(VolumeId32::from_u32(
+        isw.family_num.get(),
+    ))));
+    info.set(FsTag::Usage(Usage::Raid));
+    info.set(FsTag::Version(decode_utf8_lossy_from(&isw.version)));
+    info.set(FsTag::Magic(ISW_SIGNATURE.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: sb_offset,
+        absolute: offset + sb_offset,
+    });
+
+    return Ok(info);
+}
+
+const DDF_HEADER_MAGIC: u32 = 0xDE11_DE11;
+/// Sectors back from the end the anchor is looked for in, older
+/// implementations left room after it.
+const DDF_ANCHOR_SECTORS: [u64; 2] = [1, 257];
+
+/// DDF header, big-endian as the specification requires. libblkid also
+/// accepts headers written in little-endian by some implementations, told
+/// apart by the byte order of the signature.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct DdfHeader {
+    signature: [u8; 4],
+    crc: [u8; 4],
+    guid: [u8; 24],
+    ddf_rev: [u8; 8],
+    seq: [u8; 4],
+    timestamp: [u8; 4],
+    openflag: u8,
+    foreignflag: u8,
+    enforcegroups: u8,
+    pad0: u8,
+    pad1: [u8; 12],
+    header_ext: [u8; 32],
+    primary_lba: [u8; 8],
+    secondary_lba: [u8; 8],
+    header_type: u8,
+    pad2: [u8; 399],
+}
+
+impl DdfHeader {
+    /// Whether the header is little-endian, `None` without a signature in
+    /// either byte order.
+    fn is_le(&self) -> Option<bool> {
+        match u32::from_be_bytes(self.signature) {
+            DDF_HEADER_MAGIC => Some(false),
+            s if s.swap_bytes() == DDF_HEADER_MAGIC => Some(true),
+            _ => None,
+        }
+    }
+
+    fn primary_lba(&self, le: bool) -> u64 {
+        if le {
+            u64::from_le_bytes(self.primary_lba)
+        } else {
+            u64::from_be_bytes(self.primary_lba)
+        }
+    }
+
+    /// Verifies the crc32 of the header, taken with the crc set to all ones.
+    fn verify_csum(&self, le: bool) -> Result<(), FwRaidError> {
+        let raw = self.as_bytes();
+        let crc = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+        let mut digest = crc.digest();
+
+        digest.update(&raw[..offset_of!(DdfHeader, crc)]);
+        digest.update(&[0xFF; 4]);
+        digest.update(&raw[offset_of!(DdfHeader, guid)..]);
+
+        let crc = if le {
+            u32::from_le_bytes(self.crc)
+        } else {
+            u32::from_be_bytes(self.crc)
+        };
+
+        if digest.finalize() != crc {
+            return Err(FwRaidError::InvalidChecksum);
+        }
+
+        return Ok(());
+    }
+}
+
+/// SNIA Disk Data Format, the anchor header at the end of the disk points to
+/// the primary and secondary headers.
+pub fn probe_ddf<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    _: ProbeFlags,
+    offset: u64,
+    _: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let mut anchor = None;
+    let mut last_err = Error::UnableToLocateMagicSignature;
+
+    for n in DDF_ANCHOR_SECTORS {
+        let (sb_offset, buf) = read_sector_from_end(reader, offset, n)?;
+        let ddf: &DdfHeader = transmute_ref!(&buf);
+
+        let Some(le) = ddf.is_le() else {
+            continue;
+        };
+
+        match ddf.verify_csum(le) {
+            Ok(()) => {
+                anchor = Some((sb_offset, *ddf, le));
+                break;
+            }
+            Err(e) => last_err = e.into(),
+        }
+    }
+
+    let Some((sb_offset, ddf, le)) = anchor else {
+        return Err(last_err);
+    };
+
+    // An unset location is all ones.
+    let lba = ddf.primary_lba(le);
+    if lba != 0 && lba != u64::MAX {
+        let size = reader.seek(SeekFrom::End(0))?.saturating_sub(offset);
+        let primary = lba
+            .checked_mul(SECTOR_SIZE)
+            .filter(|&o| o.saturating_add(4) <= size)
+            .ok_or(FwRaidError::MissingPrimaryHeader)?;
+
+        let signature: [u8; 4] = reader.read_exact_at(offset + primary)?;
+        if signature != ddf.signature {
+            return Err(FwRaidError::MissingPrimaryHeader.into());
+        }
+    }
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::DdfRaidMember));
+    info.set(FsTag::FsId(FsId::Ddf(DdfGuid::from_bytes(ddf.guid))));
+    info.set(FsTag::Usage(Usage::Raid));
+    info.set(FsTag::Version(decode_utf8_lossy_from(&ddf.ddf_rev)));
+    info.set(FsTag::Magic(ddf.signature.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: sb_offset,
+        absolute: offset + sb_offset,
+    });
+    info.set(FsTag::Endianness(if le {
+        Endianness::Little
+    } else {
+        Endianness::Big
+    }));
+
+    return Ok(info);
+}
+
+const LSI_SIGNATURE: [u8; 6] = *b"$XIDE$";
+
+/// LSI MegaRAID software RAID, only a signature is checked.
+pub fn probe_lsi<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    _: ProbeFlags,
+    offset: u64,
+    _: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let (sb_offset, buf) = read_sector_from_end(reader, offset, 1)?;
+
+    if buf[..LSI_SIGNATURE.len()] != LSI_SIGNATURE {
+        return Err(Error::UnableToLocateMagicSignature);
+    }
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::LsiMegaRaidMember));
+    info.set(FsTag::Usage(Usage::Raid));
+    info.set(FsTag::Magic(LSI_SIGNATURE.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: sb_offset,
+        absolute: offset + sb_offset,
+    });
+
+    return Ok(info);
+}
+
+const PROMISE_SIGNATURE: [u8; 24] = *b"Promise Technology, Inc.";
+/// Sectors back from the end the metadata is kept in, depending on the
+/// controller and BIOS version.
+const PROMISE_SECTORS: [u64; 13] = [
+    63, 255, 256, 16, 399, 591, 675, 735, 911, 974, 991, 951, 3087,
+];
+
+/// Promise FastTrack, only a signature is checked.
+pub fn probe_promise<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    _: ProbeFlags,
+    offset: u64,
+    _: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    for n in PROMISE_SECTORS {
+        let (sb_offset, buf) = match read_sector_from_end(reader, offset, n) {
+            Err(Error::UnableToLocateMagicSignature) => continue,
+            r => r?,
+        };
+
+        if buf[..PROMISE_SIGNATURE.len()] != PROMISE_SIGNATURE {
+            continue;
+        }
+
+        let mut info = FsInfo::new();
+
+        info.set(FsTag::FsType(FsType::PromiseFasttrackRaidMember));
+        info.set(FsTag::Usage(Usage::Raid));
+        info.set(FsTag::Magic(PROMISE_SIGNATURE.to_vec()));
+        info.set(FsTag::MagicOffset {
+            relative: sb_offset,
+            absolute: offset + sb_offset,
+        });
+
+        return Ok(info);
+    }
+
+    return Err(Error::UnableToLocateMagicSignature);
+}
+
+const NVIDIA_SIGNATURE: [u8; 8] = *b"NVIDIA  ";
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct NvidiaMetadata {
+    vendor: [u8; 8],
+    /// Size of the metadata in 32-bit words.
+    size: U32<LittleEndian>,
+    chksum: U32<LittleEndian>,
+    version: U16<LittleEndian>,
+    pad: [u8; 102],
+}
+
+impl NvidiaMetadata {
+    const SIZE: u32 = size_of::<NvidiaMetadata>() as u32;
+
+    /// Verifies the metadata, which sums to zero in 32-bit words, checksum
+    /// included.
+    fn verify_csum(&self) -> Result<(), FwRaidError> {
+        let sum = self.as_bytes().chunks_exact(4).fold(0u32, |sum, word| {
+            sum.wrapping_add(u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+        });
+
+        if sum != 0 {
+            return Err(FwRaidError::InvalidChecksum);
+        }
+
+        return Ok(());
+    }
+}
+
+pub fn probe_nvidia<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    _: ProbeFlags,
+    offset: u64,
+    _: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let (sb_offset, buf) = read_sector_from_end(reader, offset, 2)?;
+    let Ok((nv, _)) = NvidiaMetadata::ref_from_prefix(&buf) else {
+        return Err(Error::UnableToLocateMagicSignature);
+    };
+
+    if nv.vendor != NVIDIA_SIGNATURE {
+        return Err(Error::UnableToLocateMagicSignature);
+    }
+
+    let size = nv.size.get();
+    if size.checked_mul(4) != Some(NvidiaMetadata::SIZE) {
+        return Err(FwRaidError::InvalidSize(size).into());
+    }
+
+    nv.verify_csum()?;
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::NvidiaRaidMember));
+    info.set(FsTag::Usage(Usage::Raid));
+    info.set(FsTag::Version(nv.version.get().to_string()));
+    info.set(FsTag::Magic(NVIDIA_SIGNATURE.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: sb_offset,
+        absolute: offset + sb_offset,
+    });
+
+    return Ok(info);
+}
+
+const SILICON_MAGIC: u32 = 0x2F00_0000;
+
+/// Silicon Image Medley metadata, the fields used for probing.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct SiliconMetadata {
+    pad0: [u8; 0x60],
+    magic: U32<LittleEndian>,
+    pad1: [u8; 0xA0],
+    product_id: U16<LittleEndian>,
+    vendor_id: U16<LittleEndian>,
+    minor_ver: U16<LittleEndian>,
+    major_ver: U16<LittleEndian>,
+    pad2: [u8; 0x32],
+    checksum1: U16<LittleEndian>,
+    pad3: [u8; 0xC0],
+}
+
+impl SiliconMetadata {
+    /// Verifies the metadata up to the checksum sums to zero in 16-bit
+    /// words, checksum included.
+    fn verify_csum(&self) -> Result<(), FwRaidError> {
+        let sum = self.as_bytes()[..offset_of!(SiliconMetadata, checksum1)]
+            .chunks_exact(2)
+            .fold(self.checksum1.get(), |sum, word| {
+                sum.wrapping_add(u16::from_le_bytes([word[0], word[1]]))
+            });
+
+        if sum != 0 {
+            return Err(FwRaidError::InvalidChecksum);
+        }
+
+        return Ok(());
+    }
+}
+
+pub fn probe_silicon<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    _: ProbeFlags,
+    offset: u64,
+    _: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let (sb_offset, buf) = read_sector_from_end(reader, offset, 1)?;
+    let sil: &SiliconMetadata = transmute_ref!(&buf);
+
+    if sil.magic.get() != SILICON_MAGIC {
+        return Err(Error::UnableToLocateMagicSignature);
+    }
+
+    sil.verify_csum()?;
+
+    let mag_offset = sb_offset + offset_of!(SiliconMetadata, magic) as u64;
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::SiliconMedleyRaidMember));
+    info.set(FsTag::Usage(Usage::Raid));
+    info.set(FsTag::Version(format!(
+        "{}.{}",
+        sil.major_ver.get(),
+        sil.minor_ver.get()
+    )));
+    info.set(FsTag::Magic(SILICON_MAGIC.to_le_bytes().to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: mag_offset,
+        absolute: offset + mag_offset,
+    });
+
+    return Ok(info);
+}
+
+const VIA_SIGNATURE: u16 = 0xAA55;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct ViaMetadata {
+    signature: U16<LittleEndian>,
+    version_number: u8,
+    disk_bit_mask: U16<LittleEndian>,
+    disk_array_ex: u8,
+    capacity_low: U32<LittleEndian>,
+    capacity_high: U32<LittleEndian>,
+    array_serial_checksum: U32<LittleEndian>,
+    serial_checksum: [U32<LittleEndian>; 8],
+    checksum: u8,
+}
+
+impl ViaMetadata {
+    const MAX_VERSION: u8 = 2;
+
+    /// Verifies the 8-bit sum of the bytes before the checksum.
+    fn verify_csum(&self) -> Result<(), FwRaidError> {
+        let sum = self.as_bytes()[..offset_of!(ViaMetadata, checksum)]
+            .iter()
+            .fold(0u8, |sum, &b| sum.wrapping_add(b));
+
+        if sum != self.checksum {
+            return Err(FwRaidError::InvalidChecksum);
+        }
+
+        return Ok(());
+    }
+}
+
+/// VIA VT6420 and related controllers, the signature alone is only two
+/// bytes and is the same as the boot sector signature.
+pub fn probe_via<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    _: ProbeFlags,
+    offset: u64,
+    _: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let (sb_offset, buf) = read_sector_from_end(reader, offset, 1)?;
+    let Ok((via, _)) = ViaMetadata::ref_from_prefix(&buf) else {
+        return Err(Error::UnableToLocateMagicSignature);
+    };
+
+    if via.signature.get() != VIA_SIGNATURE {
+        return Err(Error::UnableToLocateMagicSignature);
+    }
+
+    if via.version_number > ViaMetadata::MAX_VERSION {
+        return Err(FwRaidError::UnsupportedVersion(via.version_number).into());
+    }
+
+    via.verify_csum()?;
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::ViaRaidMember));
+    info.set(FsTag::Usage(Usage::Raid));
+    info.set(FsTag::Version(via.version_number.to_string()));
+    info.set(FsTag::Magic(VIA_SIGNATURE.to_le_bytes().to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: sb_offset,
+        absolute: offset + sb_offset,
+    });
+
+    return Ok(info);
+}
+
+const JM_SIGNATURE: [u8; 2] = *b"JM";
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct JmicronMetadata {
+    signature: [u8; 2],
+    version: U16<LittleEndian>,
+    checksum: U16<LittleEndian>,
+    filler0: [u8; 10],
+    identity: U32<LittleEndian>,
+    segment_base: U32<LittleEndian>,
+    segment_range: U32<LittleEndian>,
+    segment_range2: U16<LittleEndian>,
+    filler1: [u8; 2],
+    name: [u8; 16],
+    mode: u8,
+    block: u8,
+    attribute: U16<LittleEndian>,
+    filler2: [u8; 4],
+    spare: [U32<LittleEndian>; 2],
+    member: [U32<LittleEndian>; 8],
+    filler3: [u8; 0x20],
+}
+
+impl JmicronMetadata {
+    /// Highest RAID mode, JBOD.
+    const MAX_MODE: u8 = 5;
+
+    /// Verifies the metadata sums to zero or one in 16-bit words, checksum
+    /// included.
+    fn verify_csum(&self) -> Result<(), FwRaidError> {
+        let sum = self.as_bytes().chunks_exact(2).fold(0u16, |sum, word| {
+            sum.wrapping_add(u16::from_le_bytes([word[0], word[1]]))
+        });
+
+        if sum > 1 {
+            return Err(FwRaidError::InvalidChecksum);
+        }
+
+        return Ok(());
+    }
+}
+
+pub fn probe_jmicron<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    _: ProbeFlags,
+    offset: u64,
+    _: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let (sb_offset, buf) = read_sector_from_end(reader, offset, 1)?;
+    let Ok((jm, _)) = JmicronMetadata::ref_from_prefix(&buf) else {
+        return Err(Error::UnableToLocateMagicSignature);
+    };
+
+    if jm.signature != JM_SIGNATURE {
+        return Err(Error::UnableToLocateMagicSignature);
+    }
+
+    jm.verify_csum()?;
+
+    if jm.mode > JmicronMetadata::MAX_MODE {
+        return Err(FwRaidError::InvalidRaidLevel(jm.mode).into());
+    }
+
+    let version = jm.version.get();
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::JmicronRaidMember));
+    info.set(FsTag::Usage(Usage::Raid));
+    info.set(FsTag::Version(format!(
+        "{}.{}",
+        version >> 8,
+        version & 0xFF
+    )));
+    info.set(FsTag::Magic(JM_SIGNATURE.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: sb_offset,
+        absolute: offset + sb_offset,
+    });
+
+    return Ok(info);
+}
+
+/// Marks the block as reserved for the controller.
+const ADAPTEC_MAGIC: u32 = 0x37FC_4D1E;
+const ADAPTEC_SIGNATURE: [u8; 4] = *b"DPTM";
+
+/// Adaptec HostRAID reserved block, the fields used for probing.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct AdaptecMetadata {
+    b0idcode: U32<BigEndian>,
+    pad0: [u8; 0x3B],
+    resver: u8,
+    pad1: [u8; 0xC0],
+    smagic: [u8; 4],
+    pad2: [u8; 0xFC],
+}
+
+pub fn probe_adaptec<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    _: ProbeFlags,
+    offset: u64,
+    _: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let (sb_offset, buf) = read_sector_from_end(reader, offset, 1)?;
+    let ad: &AdaptecMetadata = transmute_ref!(&buf);
+
+    if ad.b0idcode.get() != ADAPTEC_MAGIC || ad.smagic != ADAPTEC_SIGNATURE {
+        return Err(Error::UnableToLocateMagicSignature);
+    }
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::AdaptecRaidMember));
+    info.set(FsTag::Usage(Usage::Raid));
+    info.set(FsTag::Version(ad.resver.to_string()));
+    info.set(FsTag::Magic(ADAPTEC_MAGIC.to_be_bytes().to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: sb_offset,
+        absolute: offset + sb_offset,
+    });
+
+    return Ok(info);
+}
@@ -0,0 +1,428 @@
+use fat_volume_id::id64::VolumeId64;
+use widestring::error::Utf16Error;
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    byteorder::{BigEndian, U16, U32, U64},
+    transmute_ref,
+};
+
+use crate::{
+    error::Error,
+    filesystem::{FsId, FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader},
+    probe::{Endianness, Magic, ProbeFlags, Usage},
+    std::{fmt, str::Utf8Error},
+    util::{decode_utf8_from, decode_utf8_lossy_from, decode_utf16_from, decode_utf16_lossy_from},
+};
+
+/*
+ * https://developer.apple.com/library/archive/technotes/tn/tn1150.html
+ */
+
+#[derive(Debug, Clone)]
+pub enum HfsError {
+    Utf8Error(Utf8Error),
+    Utf16Error(Utf16Error),
+    InvalidBlockSize(u32),
+    UnsupportedVersion(u16),
+    InvalidEmbeddedSignature,
+}
+
+impl fmt::Display for HfsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HfsError::Utf8Error(e) => write!(f, "Volume name contains invalid UTF-8: {e}"),
+            HfsError::Utf16Error(e) => write!(f, "Volume name contains invalid UTF-16: {e}"),
+            HfsError::InvalidBlockSize(b) => write!(f, "Invalid allocation block size: {b}"),
+            HfsError::UnsupportedVersion(v) => write!(f, "Unsupported HFS+ version: {v}"),
+            HfsError::InvalidEmbeddedSignature => {
+                write!(f, "HFS wrapper does not hold an HFS+ volume")
+            }
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<HfsError> for Error<E> {
+    fn from(e: HfsError) -> Self {
+        Error::Hfs(e)
+    }
+}
+
+/// Offset of the HFS master directory block and of the HFS+ volume header.
+const SUPER_OFFSET: u64 = 1024;
+const SECTOR_SIZE: u64 = 512;
+
+const HFS_MAGIC: &[u8; 2] = b"BD";
+const HFSPLUS_MAGIC: &[u8; 2] = b"H+";
+const HFSX_MAGIC: &[u8; 2] = b"HX";
+
+pub const HFS_MINSZ: Option<u64> = None;
+pub const HFS_MAGICS: Option<&'static [Magic]> = Some(&[Magic {
+    magic: HFS_MAGIC,
+    b_offset: SUPER_OFFSET,
+}]);
+
+pub const HFSPLUS_MINSZ: Option<u64> = None;
+pub const HFSPLUS_MAGICS: Option<&'static [Magic]> = Some(&[Magic {
+    magic: HFSPLUS_MAGIC,
+    b_offset: SUPER_OFFSET,
+}]);
+
+pub const HFSX_MINSZ: Option<u64> = None;
+pub const HFSX_MAGICS: Option<&'static [Magic]> = Some(&[Magic {
+    magic: HFSX_MAGIC,
+    b_offset: SUPER_OFFSET,
+}]);
+
+/// HFS master directory block.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct HfsMdb {
+    signature: [u8; 2],
+    cr_date: U32<BigEndian>,
+    ls_mod: U32<BigEndian>,
+    atrb: U16<BigEndian>,
+    nm_fls: U16<BigEndian>,
+    vbm_st: U16<BigEndian>,
+    alloc_ptr: U16<BigEndian>,
+    nm_al_blks: U16<BigEndian>,
+    al_blk_size: U32<BigEndian>,
+    clp_size: U32<BigEndian>,
+    al_bl_st: U16<BigEndian>,
+    nxt_cnid: U32<BigEndian>,
+    free_bks: U16<BigEndian>,
+    label_len: u8,
+    label: [u8; 27],
+    vol_bkup: U32<BigEndian>,
+    vol_seq_num: U16<BigEndian>,
+    wr_cnt: U32<BigEndian>,
+    xt_clump_size: U32<BigEndian>,
+    ct_clump_size: U32<BigEndian>,
+    num_root_dirs: U16<BigEndian>,
+    file_count: U32<BigEndian>,
+    dir_count: U32<BigEndian>,
+    finder_info: [u8; 24],
+    volume_id: [u8; 8],
+    embed_sig: [u8; 2],
+    embed_startblock: U16<BigEndian>,
+    embed_blockcount: U16<BigEndian>,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct HfsPlusExtent {
+    start_block: U32<BigEndian>,
+    block_count: U32<BigEndian>,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct HfsPlusFork {
+    total_size: U64<BigEndian>,
+    clump_size: U32<BigEndian>,
+    total_blocks: U32<BigEndian>,
+    extents: [HfsPlusExtent; 8],
+}
+
+impl HfsPlusFork {
+    /// Maps a block within the fork to a volume block, only using the
+    /// extents recorded in the fork itself.
+    fn map_block(&self, block: u64) -> Option<u64> {
+        let mut base = 0u64;
+
+        for ext in self.extents {
+            let count = u64::from(ext.block_count.get());
+
+            if block < base + count {
+                return Some(u64::from(ext.start_block.get()) + block - base);
+            }
+            base += count;
+        }
+
+        return None;
+    }
+}
+
+/// HFS+ and HFSX volume header.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct HfsPlusVolumeHeader {
+    signature: [u8; 2],
+    version: U16<BigEndian>,
+    attributes: U32<BigEndian>,
+    last_mount_vers: U32<BigEndian>,
+    journal_info_block: U32<BigEndian>,
+    create_date: U32<BigEndian>,
+    modify_date: U32<BigEndian>,
+    backup_date: U32<BigEndian>,
+    checked_date: U32<BigEndian>,
+    file_count: U32<BigEndian>,
+    folder_count: U32<BigEndian>,
+    blocksize: U32<BigEndian>,
+    total_blocks: U32<BigEndian>,
+    free_blocks: U32<BigEndian>,
+    next_alloc: U32<BigEndian>,
+    rsrc_clump_sz: U32<BigEndian>,
+    data_clump_sz: U32<BigEndian>,
+    next_cnid: U32<BigEndian>,
+    write_count: U32<BigEndian>,
+    encodings_bmp: U64<BigEndian>,
+    finder_info: [u8; 24],
+    volume_id: [u8; 8],
+    alloc_file: HfsPlusFork,
+    ext_file: HfsPlusFork,
+    cat_file: HfsPlusFork,
+    attr_file: HfsPlusFork,
+    start_file: HfsPlusFork,
+}
+
+impl HfsPlusVolumeHeader {
+    const HFSPLUS_VERSION: u16 = 4;
+    const HFSX_VERSION: u16 = 5;
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct BTreeNodeDescriptor {
+    next: U32<BigEndian>,
+    prev: U32<BigEndian>,
+    kind: i8,
+    height: u8,
+    num_recs: U16<BigEndian>,
+    reserved: U16<BigEndian>,
+}
+
+impl BTreeNodeDescriptor {
+    const KIND_LEAF: i8 = -1;
+}
+
+/// Header node of a B-tree, up to the fields needed to find the first leaf.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct BTreeHeaderNode {
+    descriptor: BTreeNodeDescriptor,
+    depth: U16<BigEndian>,
+    root: U32<BigEndian>,
+    leaf_records: U32<BigEndian>,
+    first_leaf: U32<BigEndian>,
+    last_leaf: U32<BigEndian>,
+    node_size: U16<BigEndian>,
+}
+
+/// First record of a catalog leaf node, up to the end of its key.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct CatalogLeafNode {
+    descriptor: BTreeNodeDescriptor,
+    key_len: U16<BigEndian>,
+    parent_id: U32<BigEndian>,
+    name_len: U16<BigEndian>,
+    name: [u8; 510],
+}
+
+impl CatalogLeafNode {
+    /// Parent of the root folder, whose name is the volume name.
+    const ROOT_PARENT_ID: u32 = 1;
+}
+
+/// Builds the 64-bit identifier Mac OS X keeps in the last 8 bytes of the
+/// finder info, or `None` if it was never set.
+fn volume_id(id: [u8; 8]) -> Option<FsId> {
+    if id == [0; 8] {
+        return None;
+    }
+
+    return Some(FsId::VolumeId64(VolumeId64::from_bytes(
+        u64::from_be_bytes(id).to_le_bytes(),
+    )));
+}
+
+/// Finds the volume name by reading the key of the first record in the first
+/// catalog leaf, which is always the root folder. Returns `None` if the
+/// catalog cannot be followed.
+fn catalog_label<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    vol_offset: u64,
+    vh: &HfsPlusVolumeHeader,
+) -> Result<Option<String>, Error<IO::Error>> {
+    let blocksize = u64::from(vh.blocksize.get());
+
+    let Some(header_block) = vh.cat_file.map_block(0) else {
+        return Ok(None);
+    };
+
+    let buf: [u8; size_of::<BTreeHeaderNode>()] =
+        reader.read_exact_at(vol_offset + header_block * blocksize)?;
+    let header: &BTreeHeaderNode = transmute_ref!(&buf);
+
+    let node_size = u64::from(header.node_size.get());
+    let first_leaf = u64::from(header.first_leaf.get());
+
+    if node_size < SECTOR_SIZE || first_leaf == 0 {
+        return Ok(None);
+    }
+
+    let leaf = first_leaf * node_size;
+    let Some(leaf_block) = vh.cat_file.map_block(leaf / blocksize) else {
+        return Ok(None);
+    };
+
+    let buf: [u8; size_of::<CatalogLeafNode>()] =
+        reader.read_exact_at(vol_offset + leaf_block * blocksize + leaf % blocksize)?;
+    let node: &CatalogLeafNode = transmute_ref!(&buf);
+
+    if node.descriptor.kind != BTreeNodeDescriptor::KIND_LEAF
+        || node.descriptor.num_recs.get() == 0
+        || node.parent_id.get() != CatalogLeafNode::ROOT_PARENT_ID
+    {
+        return Ok(None);
+    }
+
+    let name_len = usize::from(node.name_len.get()) * 2;
+    let Some(name) = node.name.get(..name_len) else {
+        return Ok(None);
+    };
+
+    if name.is_empty() {
+        return Ok(None);
+    }
+
+    let label = if flags.contains(ProbeFlags::FailOnInvalidUTF) {
+        decode_utf16_from(name, Endianness::Big)
+            .map_err(HfsError::Utf16Error)?
+            .to_string()
+    } else {
+        decode_utf16_lossy_from(name, Endianness::Big).to_string()
+    };
+
+    return Ok(Some(label));
+}
+
+/// Probes the HFS+ or HFSX volume header at `vol_offset` from the probe
+/// offset, which is non-zero for a volume embedded in an HFS wrapper.
+fn probe_hfsplus_at<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    vol_offset: u64,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let buf: [u8; size_of::<HfsPlusVolumeHeader>()] =
+        reader.read_exact_at(offset + vol_offset + SUPER_OFFSET)?;
+    let vh: &HfsPlusVolumeHeader = transmute_ref!(&buf);
+
+    let (fs_type, magic, version) = match &vh.signature {
+        HFSPLUS_MAGIC => (
+            FsType::HfsPlus,
+            HFSPLUS_MAGIC,
+            HfsPlusVolumeHeader::HFSPLUS_VERSION,
+        ),
+        HFSX_MAGIC => (FsType::Hfsx, HFSX_MAGIC, HfsPlusVolumeHeader::HFSX_VERSION),
+        _ => return Err(HfsError::InvalidEmbeddedSignature.into()),
+    };
+
+    if vh.version.get() != version {
+        return Err(HfsError::UnsupportedVersion(vh.version.get()).into());
+    }
+
+    let blocksize = vh.blocksize.get();
+    if !blocksize.is_power_of_two() || u64::from(blocksize) < SECTOR_SIZE {
+        return Err(HfsError::InvalidBlockSize(blocksize).into());
+    }
+
+    let label = catalog_label(reader, flags, offset + vol_offset, vh)?;
+    let mag_offset = vol_offset + SUPER_OFFSET;
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(fs_type));
+    if let Some(l) = label {
+        info.set(FsTag::Label(l));
+    }
+    if let Some(id) = volume_id(vh.volume_id) {
+        info.set(FsTag::FsId(id));
+    }
+    info.set(FsTag::Usage(Usage::Filesystem));
+    info.set(FsTag::Version(vh.version.get().to_string()));
+    info.set(FsTag::Magic(magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: mag_offset,
+        absolute: offset + mag_offset,
+    });
+    info.set(FsTag::FsSize(
+        u64::from(vh.total_blocks.get()) * u64::from(blocksize),
+    ));
+    info.set(FsTag::FsLastBlock(u64::from(vh.total_blocks.get())));
+    info.set(FsTag::FsBlockSize(u64::from(blocksize)));
+    info.set(FsTag::BlockSize(SECTOR_SIZE));
+
+    return Ok(info);
+}
+
+/// Probes a classic HFS volume, following the embedded volume if it is a
+/// wrapper around HFS+.
+pub fn probe_hfs<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    magic: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let buf: [u8; size_of::<HfsMdb>()] = reader.read_exact_at(offset + SUPER_OFFSET)?;
+    let mdb: &HfsMdb = transmute_ref!(&buf);
+
+    let al_blk_size = mdb.al_blk_size.get();
+    if al_blk_size == 0 || u64::from(al_blk_size) % SECTOR_SIZE != 0 {
+        return Err(HfsError::InvalidBlockSize(al_blk_size).into());
+    }
+
+    if &mdb.embed_sig == HFSPLUS_MAGIC {
+        let vol_offset = u64::from(mdb.al_bl_st.get()) * SECTOR_SIZE
+            + u64::from(mdb.embed_startblock.get()) * u64::from(al_blk_size);
+
+        return probe_hfsplus_at(reader, flags, offset, vol_offset);
+    }
+
+    let label_len = usize::from(mdb.label_len).min(mdb.label.len());
+    let label = if label_len != 0 {
+        if flags.contains(ProbeFlags::FailOnInvalidUTF) {
+            Some(decode_utf8_from(&mdb.label[..label_len]).map_err(HfsError::Utf8Error)?)
+        } else {
+            Some(decode_utf8_lossy_from(&mdb.label[..label_len]))
+        }
+    } else {
+        None
+    };
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Hfs));
+    if let Some(l) = label {
+        info.set(FsTag::Label(l));
+    }
+    if let Some(id) = volume_id(mdb.volume_id) {
+        info.set(FsTag::FsId(id));
+    }
+    info.set(FsTag::Usage(Usage::Filesystem));
+    info.set(FsTag::Magic(magic.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    info.set(FsTag::FsSize(
+        u64::from(mdb.nm_al_blks.get()) * u64::from(al_blk_size),
+    ));
+    info.set(FsTag::FsLastBlock(u64::from(mdb.nm_al_blks.get())));
+    info.set(FsTag::FsBlockSize(u64::from(al_blk_size)));
+    info.set(FsTag::BlockSize(SECTOR_SIZE));
+
+    return Ok(info);
+}
+
+pub fn probe_hfsplus<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    _: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    return probe_hfsplus_at(reader, flags, offset, 0);
+}
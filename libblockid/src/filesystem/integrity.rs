@@ -0,0 +1,125 @@
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    byteorder::{LittleEndian, U16, U32, U64},
+    transmute_ref,
+};
+
+use crate::{
+    error::Error,
+    filesystem::{FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader},
+    probe::{Magic, ProbeFlags, Usage},
+    std::fmt,
+};
+
+/*
+ * https://docs.kernel.org/admin-guide/device-mapper/dm-integrity.html
+ * https://github.com/torvalds/linux/blob/master/drivers/md/dm-integrity.c
+ */
+
+#[derive(Debug, Clone)]
+pub enum IntegrityError {
+    UnsupportedVersion(u8),
+    InvalidTagSize,
+    InvalidBlockSize(u8),
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityError::UnsupportedVersion(v) => {
+                write!(f, "Unsupported superblock version: {v}")
+            }
+            IntegrityError::InvalidTagSize => write!(f, "Integrity tag size is zero"),
+            IntegrityError::InvalidBlockSize(l) => write!(f, "Invalid block size: 2^{l} sectors"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<IntegrityError> for Error<E> {
+    fn from(e: IntegrityError) -> Self {
+        Error::Integrity(e)
+    }
+}
+
+const INTEGRITY_MAGIC: [u8; 8] = *b"integrt\0";
+const SECTOR_SIZE: u64 = 512;
+
+pub const INTEGRITY_MINSZ: Option<u64> = Some(SECTOR_SIZE);
+pub const INTEGRITY_MAGICS: Option<&'static [Magic]> = Some(&[Magic {
+    magic: &INTEGRITY_MAGIC,
+    b_offset: 0,
+}]);
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct IntegritySuperBlock {
+    magic: [u8; 8],
+    version: u8,
+    log2_interleave_sectors: u8,
+    integrity_tag_size: U16<LittleEndian>,
+    journal_sections: U32<LittleEndian>,
+    provided_data_sectors: U64<LittleEndian>,
+    flags: U32<LittleEndian>,
+    log2_sectors_per_block: u8,
+    log2_blocks_per_bitmap_bit: u8,
+    pad: [u8; 2],
+    recalc_sector: U64<LittleEndian>,
+    pad2: [u8; 8],
+    salt: [u8; 16],
+}
+
+impl IntegritySuperBlock {
+    const MIN_VERSION: u8 = 1;
+    const MAX_VERSION: u8 = 6;
+    /// Blocks are 512 bytes to 4 KiB.
+    const MAX_LOG2_SECTORS_PER_BLOCK: u8 = 3;
+}
+
+/// The superblock takes the first sector, the journal and the interleaved
+/// data and tags follow.
+pub fn probe_integrity<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    _: ProbeFlags,
+    offset: u64,
+    magic: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let buf: [u8; size_of::<IntegritySuperBlock>()] = reader.read_exact_at(offset)?;
+    let sb: &IntegritySuperBlock = transmute_ref!(&buf);
+
+    if !(IntegritySuperBlock::MIN_VERSION..=IntegritySuperBlock::MAX_VERSION).contains(&sb.version)
+    {
+        return Err(IntegrityError::UnsupportedVersion(sb.version).into());
+    }
+
+    let tag_size = sb.integrity_tag_size.get();
+    if tag_size == 0 {
+        return Err(IntegrityError::InvalidTagSize.into());
+    }
+
+    let log2_spb = sb.log2_sectors_per_block;
+    if log2_spb > IntegritySuperBlock::MAX_LOG2_SECTORS_PER_BLOCK {
+        return Err(IntegrityError::InvalidBlockSize(log2_spb).into());
+    }
+
+    let block_size = SECTOR_SIZE << log2_spb;
+    let data_sectors = sb.provided_data_sectors.get();
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::DmIntegrity));
+    info.set(FsTag::Usage(Usage::Crypto));
+    info.set(FsTag::Version(sb.version.to_string()));
+    info.set(FsTag::Magic(magic.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    info.set(FsTag::FsSize(data_sectors.saturating_mul(SECTOR_SIZE)));
+    info.set(FsTag::FsLastBlock(data_sectors >> log2_spb));
+    info.set(FsTag::FsBlockSize(block_size));
+    info.set(FsTag::BlockSize(block_size));
+    info.set(FsTag::TagSize(tag_size));
+
+    return Ok(info);
+}
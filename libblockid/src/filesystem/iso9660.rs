@@ -0,0 +1,259 @@
+use widestring::error::Utf16Error;
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    byteorder::{LittleEndian, U16, U32},
+    transmute_ref,
+};
+
+use crate::{
+    error::Error,
+    filesystem::{FsId, FsInfo, FsTag, FsType, PseudoUuid},
+    io::{BlockIo, Reader, SeekFrom},
+    probe::{Endianness, Magic, ProbeFlags, Usage},
+    std::{
+        fmt,
+        str::{self, Utf8Error},
+    },
+    util::{decode_utf8_from, decode_utf8_lossy_from, decode_utf16_from, decode_utf16_lossy_from},
+};
+
+/*
+ * ECMA-119 https://ecma-international.org/publications-and-standards/standards/ecma-119/
+ */
+
+#[derive(Debug, Clone)]
+pub enum Iso9660Error {
+    Utf8Error(Utf8Error),
+    Utf16Error(Utf16Error),
+    MissingPrimaryDescriptor,
+    InvalidBlockSize(u16),
+}
+
+impl fmt::Display for Iso9660Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Iso9660Error::Utf8Error(e) => {
+                write!(f, "Volume identifier contains invalid UTF-8: {e}")
+            }
+            Iso9660Error::Utf16Error(e) => {
+                write!(f, "Volume identifier contains invalid UTF-16: {e}")
+            }
+            Iso9660Error::MissingPrimaryDescriptor => {
+                write!(f, "No primary volume descriptor found")
+            }
+            Iso9660Error::InvalidBlockSize(b) => write!(f, "Invalid logical block size: {b}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<Iso9660Error> for Error<E> {
+    fn from(e: Iso9660Error) -> Self {
+        Error::Iso9660(e)
+    }
+}
+
+/// Volume descriptors start at the 16th 2048 byte sector.
+pub(crate) const VD_OFFSET: u64 = 0x8000;
+pub(crate) const VD_SIZE: u64 = 2048;
+pub(crate) const ISO_MAGIC: &[u8; 5] = b"CD001";
+
+pub const ISO9660_MINSZ: Option<u64> = None;
+pub const ISO9660_MAGICS: Option<&'static [Magic]> = Some(&[Magic {
+    magic: ISO_MAGIC,
+    b_offset: VD_OFFSET + 1,
+}]);
+
+/// Primary and supplementary volume descriptors share this layout.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct IsoVolumeDescriptor {
+    vd_type: u8,
+    vd_id: [u8; 5],
+    vd_version: u8,
+    flags: u8,
+    system_id: [u8; 32],
+    volume_id: [u8; 32],
+    unused: [u8; 8],
+    space_size: U32<LittleEndian>,
+    space_size_be: [u8; 4],
+    escape_sequences: [u8; 32],
+    set_size: [u8; 4],
+    seq_num: [u8; 4],
+    logical_block_size: U16<LittleEndian>,
+    logical_block_size_be: [u8; 2],
+    path_table_size: [u8; 8],
+    path_tables: [u8; 16],
+    root_dir_record: [u8; 34],
+    volume_set_id: [u8; 128],
+    publisher_id: [u8; 128],
+    data_preparer_id: [u8; 128],
+    application_id: [u8; 128],
+    copyright_file_id: [u8; 37],
+    abstract_file_id: [u8; 37],
+    bibliographic_file_id: [u8; 37],
+    created: [u8; 17],
+    modified: [u8; 17],
+    expiration: [u8; 17],
+    effective: [u8; 17],
+}
+
+impl IsoVolumeDescriptor {
+    const TYPE_PRIMARY: u8 = 1;
+    const TYPE_SUPPLEMENTARY: u8 = 2;
+    const TYPE_TERMINATOR: u8 = 255;
+
+    /// Escape sequences marking a supplementary descriptor as Joliet, for
+    /// UCS-2 levels 1 to 3.
+    const JOLIET_ESCAPES: [&[u8; 3]; 3] = [b"%/@", b"%/C", b"%/E"];
+
+    /// Limit on the number of descriptors read before the terminator.
+    const MAX_DESCRIPTORS: u64 = 64;
+
+    fn is_joliet(&self) -> bool {
+        self.vd_type == IsoVolumeDescriptor::TYPE_SUPPLEMENTARY
+            && IsoVolumeDescriptor::JOLIET_ESCAPES
+                .iter()
+                .any(|esc| self.escape_sequences.starts_with(*esc))
+    }
+}
+
+/// Formats a volume date as `YYYY-MM-DD-HH-MM-SS-hh`, the same as libblkid
+/// does for the ISO9660 UUID, if the date is set.
+fn date_id(date: &[u8; 17]) -> Option<PseudoUuid> {
+    let digits = &date[..16];
+
+    if !digits.iter().all(u8::is_ascii_digit) || digits.iter().all(|d| *d == b'0') {
+        return None;
+    }
+
+    let d = str::from_utf8(digits).ok()?;
+
+    return PseudoUuid::new(&format!(
+        "{}-{}-{}-{}-{}-{}-{}",
+        &d[0..4],
+        &d[4..6],
+        &d[6..8],
+        &d[8..10],
+        &d[10..12],
+        &d[12..14],
+        &d[14..16]
+    ));
+}
+
+/// Decodes a space padded ASCII identifier, returning `None` if blank.
+fn decode_ascii_id(raw: &[u8], flags: ProbeFlags) -> Result<Option<String>, Iso9660Error> {
+    let id = if flags.contains(ProbeFlags::FailOnInvalidUTF) {
+        decode_utf8_from(raw).map_err(Iso9660Error::Utf8Error)?
+    } else {
+        decode_utf8_lossy_from(raw)
+    };
+
+    let id = id.trim_end_matches([' ', '\0']);
+
+    return Ok((!id.is_empty()).then(|| id.to_string()));
+}
+
+fn decode_joliet_id(raw: &[u8], flags: ProbeFlags) -> Result<Option<String>, Iso9660Error> {
+    let id = if flags.contains(ProbeFlags::FailOnInvalidUTF) {
+        decode_utf16_from(raw, Endianness::Big)
+            .map_err(Iso9660Error::Utf16Error)?
+            .to_string()
+    } else {
+        decode_utf16_lossy_from(raw, Endianness::Big).to_string()
+    };
+
+    let id = id.trim_end_matches(' ');
+
+    return Ok((!id.is_empty()).then(|| id.to_string()));
+}
+
+pub fn probe_iso9660<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    magic: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let end = reader.seek(SeekFrom::End(0))?;
+
+    let mut pvd: Option<IsoVolumeDescriptor> = None;
+    let mut joliet: Option<IsoVolumeDescriptor> = None;
+
+    for i in 0..IsoVolumeDescriptor::MAX_DESCRIPTORS {
+        let vd_offset = offset + VD_OFFSET + i * VD_SIZE;
+
+        if vd_offset + size_of::<IsoVolumeDescriptor>() as u64 > end {
+            break;
+        }
+
+        let buf: [u8; size_of::<IsoVolumeDescriptor>()] = reader.read_exact_at(vd_offset)?;
+        let vd: &IsoVolumeDescriptor = transmute_ref!(&buf);
+
+        if vd.vd_id != *ISO_MAGIC || vd.vd_type == IsoVolumeDescriptor::TYPE_TERMINATOR {
+            break;
+        }
+
+        if vd.vd_type == IsoVolumeDescriptor::TYPE_PRIMARY && pvd.is_none() {
+            pvd = Some(*vd);
+        } else if vd.is_joliet() && joliet.is_none() {
+            joliet = Some(*vd);
+        }
+    }
+
+    let Some(pvd) = pvd else {
+        return Err(Iso9660Error::MissingPrimaryDescriptor.into());
+    };
+
+    let block_size = pvd.logical_block_size.get();
+
+    if !block_size.is_power_of_two() || !(512..=2048).contains(&block_size) {
+        return Err(Iso9660Error::InvalidBlockSize(block_size).into());
+    }
+
+    // Joliet allows longer and non-ASCII labels, but some mastering tools
+    // leave it blank.
+    let label = match joliet {
+        Some(joliet) => decode_joliet_id(&joliet.volume_id, flags)?,
+        None => None,
+    };
+    let label = match label {
+        Some(label) => Some(label),
+        None => decode_ascii_id(&pvd.volume_id, flags)?,
+    };
+
+    let system_id = decode_ascii_id(&pvd.system_id, flags)?;
+    let publisher_id = decode_ascii_id(&pvd.publisher_id, flags)?;
+    let application_id = decode_ascii_id(&pvd.application_id, flags)?;
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Iso9660));
+    if let Some(l) = label {
+        info.set(FsTag::Label(l));
+    }
+    if let Some(id) = date_id(&pvd.modified).or_else(|| date_id(&pvd.created)) {
+        info.set(FsTag::FsId(FsId::PseudoUuid(id)));
+    }
+    info.set(FsTag::Usage(Usage::Filesystem));
+    info.set(FsTag::Magic(magic.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    info.set(FsTag::FsSize(
+        u64::from(pvd.space_size.get()) * u64::from(block_size),
+    ));
+    info.set(FsTag::FsLastBlock(u64::from(pvd.space_size.get())));
+    info.set(FsTag::FsBlockSize(u64::from(block_size)));
+    info.set(FsTag::BlockSize(u64::from(block_size)));
+    if let Some(id) = system_id {
+        info.set(FsTag::SystemId(id));
+    }
+    if let Some(id) = publisher_id {
+        info.set(FsTag::PublisherId(id));
+    }
+    if let Some(id) = application_id {
+        info.set(FsTag::ApplicationId(id));
+    }
+
+    return Ok(info);
+}
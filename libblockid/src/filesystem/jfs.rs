@@ -0,0 +1,155 @@
+use uuid::Uuid;
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    byteorder::{LittleEndian, U16, U32, U64},
+    transmute_ref,
+};
+
+use crate::{
+    error::Error,
+    filesystem::{FsId, FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader},
+    probe::{Magic, ProbeFlags, Usage},
+    std::{fmt, str::Utf8Error},
+    util::{decode_utf8_from, decode_utf8_lossy_from},
+};
+
+/*
+ * https://github.com/torvalds/linux/blob/master/fs/jfs/jfs_superblock.h
+ */
+
+#[derive(Debug, Clone)]
+pub enum JfsError {
+    Utf8Error(Utf8Error),
+    InvalidBlockSize(u32),
+    InvalidDeviceBlockSize(u32),
+    InvalidBlockFactor(u16),
+}
+
+impl fmt::Display for JfsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JfsError::Utf8Error(e) => write!(f, "Filesystem label contains invalid UTF-8: {e}"),
+            JfsError::InvalidBlockSize(s) => write!(f, "Invalid aggregate block size: {s}"),
+            JfsError::InvalidDeviceBlockSize(s) => write!(f, "Invalid device block size: {s}"),
+            JfsError::InvalidBlockFactor(l) => write!(f, "Invalid block size factor: 2^{l}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<JfsError> for Error<E> {
+    fn from(e: JfsError) -> Self {
+        Error::Jfs(e)
+    }
+}
+
+const SUPER_OFFSET: u64 = 32 * 1024;
+
+pub const JFS_MINSZ: Option<u64> = None;
+pub const JFS_MAGICS: Option<&'static [Magic]> = Some(&[Magic {
+    magic: b"JFS1",
+    b_offset: SUPER_OFFSET,
+}]);
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct JfsSuperBlock {
+    magic: [u8; 4],
+    version: U32<LittleEndian>,
+    size: U64<LittleEndian>,
+    bsize: U32<LittleEndian>,
+    l2bsize: U16<LittleEndian>,
+    l2bfactor: U16<LittleEndian>,
+    pbsize: U32<LittleEndian>,
+    l2pbsize: U16<LittleEndian>,
+    pad: U16<LittleEndian>,
+    agsize: U32<LittleEndian>,
+    flag: U32<LittleEndian>,
+    state: U32<LittleEndian>,
+    compress: U32<LittleEndian>,
+    ait2: [u8; 8],
+    aim2: [u8; 8],
+    logdev: U32<LittleEndian>,
+    logserial: U32<LittleEndian>,
+    logpxd: [u8; 8],
+    fsckpxd: [u8; 8],
+    time: [u8; 8],
+    fsckloglen: U32<LittleEndian>,
+    fscklog: u8,
+    fpack: [u8; 11],
+    xsize: U64<LittleEndian>,
+    xfsckpxd: [u8; 8],
+    xlogpxd: [u8; 8],
+    uuid: [u8; 16],
+    label: [u8; 16],
+    loguuid: [u8; 16],
+}
+
+impl JfsSuperBlock {
+    /// Journal kept on a separate device, named by `loguuid`.
+    const FLAG_INLINELOG: u32 = 0x0000_0800;
+}
+
+pub fn probe_jfs<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    magic: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let buf: [u8; size_of::<JfsSuperBlock>()] = reader.read_exact_at(offset + SUPER_OFFSET)?;
+    let sb: &JfsSuperBlock = transmute_ref!(&buf);
+
+    let bsize = sb.bsize.get();
+    let l2bsize = sb.l2bsize.get();
+    if l2bsize >= 32 || bsize != 1 << l2bsize {
+        return Err(JfsError::InvalidBlockSize(bsize).into());
+    }
+
+    let pbsize = sb.pbsize.get();
+    let l2pbsize = sb.l2pbsize.get();
+    if l2pbsize >= 32 || pbsize != 1 << l2pbsize {
+        return Err(JfsError::InvalidDeviceBlockSize(pbsize).into());
+    }
+
+    let l2bfactor = sb.l2bfactor.get();
+    if l2bsize.checked_sub(l2pbsize) != Some(l2bfactor) {
+        return Err(JfsError::InvalidBlockFactor(l2bfactor).into());
+    }
+
+    let label = if sb.label[0] != 0 {
+        if flags.contains(ProbeFlags::FailOnInvalidUTF) {
+            Some(decode_utf8_from(&sb.label).map_err(JfsError::Utf8Error)?)
+        } else {
+            Some(decode_utf8_lossy_from(&sb.label))
+        }
+    } else {
+        None
+    };
+
+    // Sizes are kept in device blocks.
+    let fs_size = sb.size.get() << l2pbsize;
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Jfs));
+    if let Some(l) = label {
+        info.set(FsTag::Label(l));
+    }
+    info.set(FsTag::FsId(FsId::Uuid(Uuid::from_bytes(sb.uuid))));
+    if sb.flag.get() & JfsSuperBlock::FLAG_INLINELOG == 0 && sb.loguuid != [0; 16] {
+        info.set(FsTag::ExtJournalId(Uuid::from_bytes(sb.loguuid)));
+    }
+    info.set(FsTag::Usage(Usage::Filesystem));
+    info.set(FsTag::Version(sb.version.get().to_string()));
+    info.set(FsTag::Magic(magic.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    info.set(FsTag::FsSize(fs_size));
+    info.set(FsTag::FsLastBlock(fs_size >> l2bsize));
+    info.set(FsTag::FsBlockSize(u64::from(bsize)));
+    info.set(FsTag::BlockSize(u64::from(bsize)));
+
+    return Ok(info);
+}
@@ -0,0 +1,355 @@
+use uuid::Uuid;
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    byteorder::{LittleEndian, U16, U32, U64},
+    transmute_ref,
+};
+
+use crate::{
+    error::Error,
+    filesystem::{FsId, FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader, SeekFrom},
+    probe::{Endianness, Magic, ProbeFlags, Usage},
+    std::{fmt, mem::offset_of, str::Utf8Error},
+    util::{decode_utf8_from, decode_utf8_lossy_from},
+};
+
+/*
+ * https://github.com/torvalds/linux/blob/master/include/uapi/linux/raid/md_p.h
+ * https://github.com/torvalds/linux/blob/master/drivers/md/md.c
+ */
+
+#[derive(Debug, Clone)]
+pub enum LinuxRaidError {
+    Utf8Error(Utf8Error),
+    UnsupportedVersion(u32, u32),
+    InvalidChecksum,
+    InvalidMaxDevices(u32),
+    SuperblockOffsetMismatch(u64),
+}
+
+impl fmt::Display for LinuxRaidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinuxRaidError::Utf8Error(e) => write!(f, "Array name contains invalid UTF-8: {e}"),
+            LinuxRaidError::UnsupportedVersion(major, minor) => {
+                write!(f, "Unsupported superblock version: {major}.{minor}")
+            }
+            LinuxRaidError::InvalidChecksum => write!(f, "Invalid superblock checksum"),
+            LinuxRaidError::InvalidMaxDevices(n) => write!(f, "Invalid device count: {n}"),
+            LinuxRaidError::SuperblockOffsetMismatch(s) => {
+                write!(f, "Superblock records a different location: sector {s}")
+            }
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<LinuxRaidError> for Error<E> {
+    fn from(e: LinuxRaidError) -> Self {
+        Error::LinuxRaid(e)
+    }
+}
+
+const MD_SB_MAGIC: u32 = 0xa92b4efc;
+const MD_SB_BYTES: usize = 4096;
+
+/// Space reserved at the end of the device for the 0.90 superblock, which
+/// starts on a multiple of it.
+const MD_RESERVED_BYTES: u64 = 64 * 1024;
+
+/// Location of the 1.1 and 1.2 superblocks, 1.0 is at the end.
+const MD_SB1_1_OFFSET: u64 = 0;
+const MD_SB1_2_OFFSET: u64 = 4 * 1024;
+
+pub const LINUX_RAID_MINSZ: Option<u64> = Some(MD_RESERVED_BYTES);
+/// The 0.90 and 1.0 superblocks are placed from the end of the device, all
+/// of the locations are looked for by the prober.
+pub const LINUX_RAID_MAGICS: Option<&'static [Magic]> = None;
+
+/// The 0.90 superblock, written in the byte order of the machine that
+/// created it.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct MdpSuperBlock0 {
+    md_magic: [u8; 4],
+    major_version: [u8; 4],
+    minor_version: [u8; 4],
+    patch_version: [u8; 4],
+    gvalid_words: [u8; 4],
+    set_uuid0: [u8; 4],
+    ctime: [u8; 4],
+    level: [u8; 4],
+    size: [u8; 4],
+    nr_disks: [u8; 4],
+    raid_disks: [u8; 4],
+    md_minor: [u8; 4],
+    not_persistent: [u8; 4],
+    set_uuid1: [u8; 4],
+    set_uuid2: [u8; 4],
+    set_uuid3: [u8; 4],
+    gstate_creserved: [u8; 64],
+    utime: [u8; 4],
+    state: [u8; 4],
+    active_disks: [u8; 4],
+    working_disks: [u8; 4],
+    failed_disks: [u8; 4],
+    spare_disks: [u8; 4],
+    sb_csum: [u8; 4],
+    reserved: [u8; 3940],
+}
+
+/// The 1.x superblock, always little endian.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct MdpSuperBlock1 {
+    magic: U32<LittleEndian>,
+    major_version: U32<LittleEndian>,
+    feature_map: U32<LittleEndian>,
+    pad0: U32<LittleEndian>,
+    set_uuid: [u8; 16],
+    set_name: [u8; 32],
+    ctime: U64<LittleEndian>,
+    level: U32<LittleEndian>,
+    layout: U32<LittleEndian>,
+    size: U64<LittleEndian>,
+    chunksize: U32<LittleEndian>,
+    raid_disks: U32<LittleEndian>,
+    bitmap_offset: U32<LittleEndian>,
+    new_level: U32<LittleEndian>,
+    reshape_position: U64<LittleEndian>,
+    delta_disks: U32<LittleEndian>,
+    new_layout: U32<LittleEndian>,
+    new_chunk: U32<LittleEndian>,
+    new_offset: U32<LittleEndian>,
+    data_offset: U64<LittleEndian>,
+    data_size: U64<LittleEndian>,
+    super_offset: U64<LittleEndian>,
+    recovery_offset: U64<LittleEndian>,
+    dev_number: U32<LittleEndian>,
+    cnt_corrected_read: U32<LittleEndian>,
+    device_uuid: [u8; 16],
+    devflags: u8,
+    bblog_shift: u8,
+    bblog_size: U16<LittleEndian>,
+    bblog_offset: U32<LittleEndian>,
+    utime: U64<LittleEndian>,
+    events: U64<LittleEndian>,
+    resync_offset: U64<LittleEndian>,
+    sb_csum: U32<LittleEndian>,
+    max_dev: U32<LittleEndian>,
+    pad3: [u8; 32],
+    dev_roles: [U16<LittleEndian>; 1920],
+}
+
+fn get_u32(bytes: [u8; 4], endianness: Endianness) -> u32 {
+    match endianness {
+        Endianness::Little => u32::from_le_bytes(bytes),
+        Endianness::Big => u32::from_be_bytes(bytes),
+    }
+}
+
+/// Sums `bytes` as 32-bit words, folding the carry back in like md does.
+fn md_csum(bytes: &[u8], endianness: Endianness) -> u32 {
+    let mut sum: u64 = bytes
+        .chunks_exact(4)
+        .map(|w| u64::from(get_u32([w[0], w[1], w[2], w[3]], endianness)))
+        .sum();
+
+    // A trailing half word is only left by the 1.x superblock.
+    if let [a, b] = bytes.chunks_exact(4).remainder() {
+        sum += u64::from(u16::from_le_bytes([*a, *b]));
+    }
+
+    return (sum as u32).wrapping_add((sum >> 32) as u32);
+}
+
+impl MdpSuperBlock0 {
+    fn verify_csum(&self, endianness: Endianness) -> Result<(), LinuxRaidError> {
+        let csum_offset = offset_of!(MdpSuperBlock0, sb_csum);
+
+        let mut raw = [0u8; MD_SB_BYTES];
+        raw.copy_from_slice(self.as_bytes());
+        raw[csum_offset..csum_offset + 4].fill(0);
+
+        if md_csum(&raw, endianness) != get_u32(self.sb_csum, endianness) {
+            return Err(LinuxRaidError::InvalidChecksum);
+        }
+
+        return Ok(());
+    }
+}
+
+impl MdpSuperBlock1 {
+    fn verify_csum(&self) -> Result<(), LinuxRaidError> {
+        let max_dev = self.max_dev.get();
+        if max_dev as usize > self.dev_roles.len() {
+            return Err(LinuxRaidError::InvalidMaxDevices(max_dev));
+        }
+
+        let len = offset_of!(MdpSuperBlock1, dev_roles) + max_dev as usize * 2;
+        let csum_offset = offset_of!(MdpSuperBlock1, sb_csum);
+
+        let mut raw = self.as_bytes()[..len].to_vec();
+        raw[csum_offset..csum_offset + 4].fill(0);
+
+        if md_csum(&raw, Endianness::Little) != self.sb_csum.get() {
+            return Err(LinuxRaidError::InvalidChecksum);
+        }
+
+        return Ok(());
+    }
+}
+
+fn probe_md0<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    offset: u64,
+    sb_offset: u64,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let buf: [u8; size_of::<MdpSuperBlock0>()] = reader.read_exact_at(offset + sb_offset)?;
+    let sb: &MdpSuperBlock0 = transmute_ref!(&buf);
+
+    let endianness = if sb.md_magic == MD_SB_MAGIC.to_le_bytes() {
+        Endianness::Little
+    } else if sb.md_magic == MD_SB_MAGIC.to_be_bytes() {
+        Endianness::Big
+    } else {
+        return Err(Error::UnableToLocateMagicSignature);
+    };
+
+    let major = get_u32(sb.major_version, endianness);
+    let minor = get_u32(sb.minor_version, endianness);
+    if major != 0 || minor != 90 {
+        return Err(LinuxRaidError::UnsupportedVersion(major, minor).into());
+    }
+
+    sb.verify_csum(endianness)?;
+
+    // The UUID is kept as four words, mdadm prints each of them most
+    // significant byte first.
+    let mut uuid = [0u8; 16];
+    for (dst, word) in
+        uuid.chunks_exact_mut(4)
+            .zip([sb.set_uuid0, sb.set_uuid1, sb.set_uuid2, sb.set_uuid3])
+    {
+        dst.copy_from_slice(&get_u32(word, endianness).to_be_bytes());
+    }
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::LinuxRaidMember));
+    info.set(FsTag::FsId(FsId::Uuid(Uuid::from_bytes(uuid))));
+    info.set(FsTag::Usage(Usage::Raid));
+    info.set(FsTag::Version(format!(
+        "{major}.{minor}.{}",
+        get_u32(sb.patch_version, endianness)
+    )));
+    info.set(FsTag::Magic(sb.md_magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: sb_offset,
+        absolute: offset + sb_offset,
+    });
+    info.set(FsTag::Endianness(endianness));
+
+    return Ok(info);
+}
+
+fn probe_md1<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    sb_offset: u64,
+    minor: u32,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let buf: [u8; size_of::<MdpSuperBlock1>()] = reader.read_exact_at(offset + sb_offset)?;
+    let sb: &MdpSuperBlock1 = transmute_ref!(&buf);
+
+    if sb.magic.get() != MD_SB_MAGIC {
+        return Err(Error::UnableToLocateMagicSignature);
+    }
+
+    let major = sb.major_version.get();
+    if major != 1 {
+        return Err(LinuxRaidError::UnsupportedVersion(major, minor).into());
+    }
+
+    // Superblocks from a whole disk or another location seen through this
+    // one record where they really belong.
+    let super_offset = sb.super_offset.get();
+    if super_offset != sb_offset >> 9 {
+        return Err(LinuxRaidError::SuperblockOffsetMismatch(super_offset).into());
+    }
+
+    sb.verify_csum()?;
+
+    let label = if sb.set_name[0] != 0 {
+        if flags.contains(ProbeFlags::FailOnInvalidUTF) {
+            Some(decode_utf8_from(&sb.set_name).map_err(LinuxRaidError::Utf8Error)?)
+        } else {
+            Some(decode_utf8_lossy_from(&sb.set_name))
+        }
+    } else {
+        None
+    };
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::LinuxRaidMember));
+    if let Some(l) = label {
+        info.set(FsTag::Label(l));
+    }
+    info.set(FsTag::FsId(FsId::Uuid(Uuid::from_bytes(sb.set_uuid))));
+    info.set(FsTag::SubMemberId(FsId::Uuid(Uuid::from_bytes(
+        sb.device_uuid,
+    ))));
+    info.set(FsTag::Usage(Usage::Raid));
+    info.set(FsTag::Version(format!("{major}.{minor}")));
+    info.set(FsTag::Magic(MD_SB_MAGIC.to_le_bytes().to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: sb_offset,
+        absolute: offset + sb_offset,
+    });
+
+    return Ok(info);
+}
+
+/// Probes for a member of a Linux MD array, trying the 0.90 and 1.0
+/// superblocks at the end of the device before the 1.1 and 1.2 ones at its
+/// start, the same as libblkid.
+pub fn probe_linux_raid<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    _: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let end = reader.seek(SeekFrom::End(0))?;
+    let size = end.saturating_sub(offset);
+
+    let md0_offset = (size & !(MD_RESERVED_BYTES - 1)).saturating_sub(MD_RESERVED_BYTES);
+    // 8 KiB from the end, rounded down to 4 KiB.
+    let md1_0_offset = ((size >> 9).saturating_sub(16) & !7) << 9;
+
+    // Minor version of each 1.x superblock location, 0.90 has none.
+    let locations = [
+        (md0_offset, None),
+        (md1_0_offset, Some(0)),
+        (MD_SB1_1_OFFSET, Some(1)),
+        (MD_SB1_2_OFFSET, Some(2)),
+    ];
+    let mut last_err = Error::UnableToLocateMagicSignature;
+
+    for (sb_offset, minor) in locations {
+        let attempt = match minor {
+            None => probe_md0(reader, offset, sb_offset),
+            Some(minor) => probe_md1(reader, flags, offset, sb_offset, minor),
+        };
+
+        match attempt {
+            Ok(info) => return Ok(info),
+            Err(Error::Io(e)) => return Err(Error::Io(e)),
+            Err(Error::UnableToLocateMagicSignature) => {}
+            Err(e) => last_err = e,
+        }
+    }
+
+    return Err(last_err);
+}
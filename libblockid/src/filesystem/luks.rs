@@ -8,7 +8,7 @@ use zerocopy::{
 use crate::{
     error::Error,
     filesystem::{FsInfo, FsTag, FsType, FsId},
-    io::{BlockIo, Reader},
+    io::{BlockIo, Reader, SeekFrom},
     probe::{Magic, ProbeFlags, Usage},
     std::{
         fmt,
@@ -71,10 +71,48 @@ pub const LUKS1_MAGICS: Option<&'static [Magic]> = Some(&[Magic {
 }]);
 
 pub const LUKS2_MINSZ: Option<u64> = Some(4194304);
-pub const LUKS2_MAGICS: Option<&'static [Magic]> = Some(&[Magic {
-    magic: &LUKS2_MAGIC,
-    b_offset: 0,
-}]);
+pub const LUKS2_MAGICS: Option<&'static [Magic]> = Some(&[
+    Magic {
+        magic: &LUKS1_MAGIC,
+        b_offset: 0,
+    },
+    Magic {
+        magic: &LUKS2_MAGIC,
+        b_offset: SECONDARY_OFFSETS[0],
+    },
+    Magic {
+        magic: &LUKS2_MAGIC,
+        b_offset: SECONDARY_OFFSETS[1],
+    },
+    Magic {
+        magic: &LUKS2_MAGIC,
+        b_offset: SECONDARY_OFFSETS[2],
+    },
+    Magic {
+        magic: &LUKS2_MAGIC,
+        b_offset: SECONDARY_OFFSETS[3],
+    },
+    Magic {
+        magic: &LUKS2_MAGIC,
+        b_offset: SECONDARY_OFFSETS[4],
+    },
+    Magic {
+        magic: &LUKS2_MAGIC,
+        b_offset: SECONDARY_OFFSETS[5],
+    },
+    Magic {
+        magic: &LUKS2_MAGIC,
+        b_offset: SECONDARY_OFFSETS[6],
+    },
+    Magic {
+        magic: &LUKS2_MAGIC,
+        b_offset: SECONDARY_OFFSETS[7],
+    },
+    Magic {
+        magic: &LUKS2_MAGIC,
+        b_offset: SECONDARY_OFFSETS[8],
+    },
+]);
 
 pub const LUKSOPAL_MAGICS: Option<&'static [Magic]> = None;
 
@@ -122,23 +160,47 @@ pub struct Luks2Header {
 }
 
 impl Luks2Header {
-    fn luks_valid<IO: BlockIo>(self, reader: &mut Reader<IO>) -> Result<bool, Error<IO::Error>> {
-        if self.magic == LUKS1_MAGIC && u16::from(self.version) == 2 {
-            return Ok(true);
+    fn primary_valid(&self) -> bool {
+        return self.magic == LUKS1_MAGIC
+            && u16::from(self.version) == 2
+            && u64::from(self.hdr_offset) == 0;
+    }
+
+    fn secondary_valid(&self, hdr_offset: u64) -> bool {
+        return self.magic == LUKS2_MAGIC
+            && u16::from(self.version) == 2
+            && u64::from(self.hdr_offset) == hdr_offset;
+    }
+
+    /// Returns the primary header at `offset`, falling back to the first
+    /// valid secondary header when the primary one is damaged.
+    fn find_valid<IO: BlockIo>(
+        reader: &mut Reader<IO>,
+        offset: u64,
+    ) -> Result<Option<Luks2Header>, Error<IO::Error>> {
+        let buf: [u8; size_of::<Luks2Header>()] = reader.read_exact_at(offset)?;
+        let hdr: &Luks2Header = transmute_ref!(&buf);
+
+        if hdr.primary_valid() {
+            return Ok(Some(*hdr));
         }
 
-        let mut buf: [u8; size_of::<Luks2Header>()] = [0u8; size_of::<Luks2Header>()];
-        for offset in SECONDARY_OFFSETS {
-            reader.read_at(offset, &mut buf)?;
+        let end = reader.seek(SeekFrom::End(0))?;
 
+        for sec_offset in SECONDARY_OFFSETS {
+            if offset + sec_offset + size_of::<Luks2Header>() as u64 > end {
+                break;
+            }
+
+            let buf: [u8; size_of::<Luks2Header>()] = reader.read_exact_at(offset + sec_offset)?;
             let hdr: &Luks2Header = transmute_ref!(&buf);
 
-            if u16::from(hdr.version) == 2 && u64::from(hdr.hdr_offset) == offset {
-                return Ok(true);
+            if hdr.secondary_valid(sec_offset) {
+                return Ok(Some(*hdr));
             }
         }
 
-        return Ok(false);
+        return Ok(None);
     }
 }
 
@@ -168,7 +230,10 @@ pub fn probe_luks1<IO: BlockIo>(
     info.set(FsTag::Usage(Usage::Crypto));
     info.set(FsTag::Version(version));
     info.set(FsTag::Magic(magic.magic.to_vec()));
-    info.set(FsTag::MagicOffset(magic.b_offset));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
 
     return Ok(info);
 }
@@ -179,13 +244,7 @@ pub fn probe_luks2<IO: BlockIo>(
     offset: u64,
     magic: Magic,
 ) -> Result<FsInfo, Error<IO::Error>> {
-    let buf: [u8; size_of::<Luks2Header>()] = reader.read_exact_at(offset)?;
-
-    let sb: &Luks2Header = transmute_ref!(&buf);
-
-    if !sb.luks_valid(reader)? {
-        return Err(LuksError::InvalidLuks2.into());
-    }
+    let sb = Luks2Header::find_valid(reader, offset)?.ok_or(LuksError::InvalidLuks2)?;
 
     let utf = decode_utf8_from(&sb.uuid).map_err(LuksError::Utf8Error)?;
     let uuid = Uuid::from_str(&utf).map_err(LuksError::UuidConversionError)?;
@@ -199,7 +258,10 @@ pub fn probe_luks2<IO: BlockIo>(
     info.set(FsTag::Usage(Usage::Crypto));
     info.set(FsTag::Version(version));
     info.set(FsTag::Magic(magic.magic.to_vec()));
-    info.set(FsTag::MagicOffset(magic.b_offset));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
 
     return Ok(info);
 }
@@ -210,13 +272,7 @@ pub fn probe_luks_opal<IO: BlockIo>(
     offset: u64,
     magic: Magic,
 ) -> Result<FsInfo, Error<IO::Error>> {
-    let buf: [u8; size_of::<Luks2Header>()] = reader.read_exact_at(offset)?;
-
-    let sb: &Luks2Header = transmute_ref!(&buf);
-
-    if !sb.luks_valid(reader)? {
-        return Err(LuksError::InvalidLuks2Opal.into());
-    }
+    let sb = Luks2Header::find_valid(reader, offset)?.ok_or(LuksError::InvalidLuks2Opal)?;
 
     if sb.subsystem[0..7] != LUKS2_HW_OPAL_SUBSYSTEM {
         return Err(LuksError::InvalidLuks2Opal.into());
@@ -234,7 +290,10 @@ pub fn probe_luks_opal<IO: BlockIo>(
     info.set(FsTag::Usage(Usage::Crypto));
     info.set(FsTag::Version(version));
     info.set(FsTag::Magic(magic.magic.to_vec()));
-    info.set(FsTag::MagicOffset(magic.b_offset));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
 
     return Ok(info);
 }
@@ -0,0 +1,278 @@
+use crc::{CRC_32_ISO_HDLC, Crc};
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    byteorder::{LittleEndian, U32, U64},
+};
+
+use crate::{
+    error::Error,
+    filesystem::{FsId, FsInfo, FsTag, FsType, LvmUuid},
+    io::{BlockIo, Reader, SeekFrom},
+    probe::{Magic, ProbeFlags, Usage},
+    std::{fmt, mem::offset_of},
+    util::decode_utf8_lossy_from,
+};
+
+/*
+ * https://github.com/lvmteam/lvm2/blob/main/lib/format_text/layout.h
+ * https://github.com/util-linux/util-linux/blob/master/libblkid/src/superblocks/lvm.c
+ */
+
+#[derive(Debug, Clone)]
+pub enum LvmError {
+    SectorMismatch(u64),
+    InvalidChecksum,
+    UnsupportedType([u8; 8]),
+    InvalidPvHeaderOffset(u32),
+    InvalidPvUuid,
+}
+
+impl fmt::Display for LvmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LvmError::SectorMismatch(s) => write!(f, "Label claims to be in sector {s}"),
+            LvmError::InvalidChecksum => write!(f, "Invalid label header checksum"),
+            LvmError::UnsupportedType(t) => write!(f, "Unsupported label type: {t:x?}"),
+            LvmError::InvalidPvHeaderOffset(o) => write!(f, "Invalid PV header offset: {o}"),
+            LvmError::InvalidPvUuid => write!(f, "PV UUID contains invalid characters"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<LvmError> for Error<E> {
+    fn from(e: LvmError) -> Self {
+        Error::Lvm(e)
+    }
+}
+
+const SECTOR_SIZE: u64 = 512;
+const LVM2_LABEL_ID: [u8; 8] = *b"LABELONE";
+const LVM2_LABEL_TYPE: [u8; 8] = *b"LVM2 001";
+const MDA_HEADER_MAGIC: [u8; 16] = *b" LVM2 x[5A%r0N*>";
+/// Seed of the crc32 LVM uses for its label and metadata area headers.
+const INITIAL_CRC: u32 = 0xf597_a6cf;
+/// Longest name LVM allows for a volume group.
+const MAX_VG_NAME: usize = 127;
+
+/// Room for the four sectors the label may be in.
+pub const LVM2_MINSZ: Option<u64> = Some(4 * SECTOR_SIZE);
+/// The label may be written to any of the first four sectors, pvcreate uses
+/// the second.
+pub const LVM2_MAGICS: Option<&'static [Magic]> = Some(&[
+    Magic {
+        magic: &LVM2_LABEL_ID,
+        b_offset: 0,
+    },
+    Magic {
+        magic: &LVM2_LABEL_ID,
+        b_offset: SECTOR_SIZE,
+    },
+    Magic {
+        magic: &LVM2_LABEL_ID,
+        b_offset: 2 * SECTOR_SIZE,
+    },
+    Magic {
+        magic: &LVM2_LABEL_ID,
+        b_offset: 3 * SECTOR_SIZE,
+    },
+]);
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct LabelHeader {
+    id: [u8; 8],
+    sector_xl: U64<LittleEndian>,
+    crc_xl: U32<LittleEndian>,
+    offset_xl: U32<LittleEndian>,
+    label_type: [u8; 8],
+}
+
+/// Followed by two lists of [`DiskLocn`], the data areas then the metadata
+/// areas, each ended by an entry of zeros.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct PvHeader {
+    pv_uuid: [u8; 32],
+    device_size_xl: U64<LittleEndian>,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct DiskLocn {
+    offset: U64<LittleEndian>,
+    size: U64<LittleEndian>,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct MdaHeader {
+    checksum_xl: U32<LittleEndian>,
+    magic: [u8; 16],
+    version: U32<LittleEndian>,
+    start: U64<LittleEndian>,
+    size: U64<LittleEndian>,
+    raw_locns: [RawLocn; 4],
+}
+
+/// Where the current metadata text is within the metadata area.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct RawLocn {
+    offset: U64<LittleEndian>,
+    size: U64<LittleEndian>,
+    checksum: U32<LittleEndian>,
+    flags: U32<LittleEndian>,
+}
+
+/// The standard reflected crc32, seeded with [`INITIAL_CRC`] and not
+/// inverted.
+fn lvm_crc(bytes: &[u8]) -> u32 {
+    let crc = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+    let mut digest = crc.digest_with_initial(INITIAL_CRC.reverse_bits());
+
+    digest.update(bytes);
+
+    // The digest inverts its result, LVM does not.
+    return !digest.finalize();
+}
+
+/// Reads the volume group name from the first metadata area, `None` if there
+/// is no usable metadata.
+fn read_vg_name<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    offset: u64,
+    mda: &DiskLocn,
+) -> Result<Option<String>, Error<IO::Error>> {
+    let end = reader.seek(SeekFrom::End(0))?;
+    let mda_offset = mda.offset.get();
+    let mda_size = mda.size.get();
+
+    if mda_size < SECTOR_SIZE
+        || offset
+            .checked_add(mda_offset)
+            .and_then(|o| o.checked_add(mda_size))
+            .is_none_or(|e| e > end)
+    {
+        return Ok(None);
+    }
+
+    let buf: [u8; SECTOR_SIZE as usize] = reader.read_exact_at(offset + mda_offset)?;
+    let Ok((hdr, _)) = MdaHeader::ref_from_prefix(&buf) else {
+        return Ok(None);
+    };
+
+    if hdr.magic != MDA_HEADER_MAGIC
+        || hdr.start.get() != mda_offset
+        || lvm_crc(&buf[offset_of!(MdaHeader, magic)..]) != hdr.checksum_xl.get()
+    {
+        return Ok(None);
+    }
+
+    // Text is written after the header and wraps around the end of the
+    // area, the name is taken from what fits before it.
+    let rlocn = &hdr.raw_locns[0];
+    let text_offset = rlocn.offset.get();
+    if text_offset < SECTOR_SIZE || text_offset >= mda_size {
+        return Ok(None);
+    }
+
+    let len = rlocn
+        .size
+        .get()
+        .min(mda_size - text_offset)
+        .min(MAX_VG_NAME as u64 + 1);
+    let text = reader.read_vec_at(offset + mda_offset + text_offset, len as usize)?;
+
+    // The text starts with `<name> {`, names only use these characters.
+    let name_len = text
+        .iter()
+        .position(|&c| !(c.is_ascii_alphanumeric() || b"+_.-".contains(&c)))
+        .unwrap_or(text.len());
+
+    if name_len == 0
+        || name_len > MAX_VG_NAME
+        || !text
+            .get(name_len)
+            .is_some_and(|&c| c == b'{' || c.is_ascii_whitespace())
+    {
+        return Ok(None);
+    }
+
+    return Ok(Some(decode_utf8_lossy_from(&text[..name_len])));
+}
+
+pub fn probe_lvm2<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    _: ProbeFlags,
+    offset: u64,
+    magic: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let sector: [u8; SECTOR_SIZE as usize] = reader.read_exact_at(offset + magic.b_offset)?;
+    let Ok((label, _)) = LabelHeader::ref_from_prefix(&sector) else {
+        return Err(Error::UnableToLocateMagicSignature);
+    };
+
+    if label.id != LVM2_LABEL_ID {
+        return Err(Error::UnableToLocateMagicSignature);
+    }
+
+    let sector_no = label.sector_xl.get();
+    if sector_no != magic.b_offset / SECTOR_SIZE {
+        return Err(LvmError::SectorMismatch(sector_no).into());
+    }
+
+    if lvm_crc(&sector[offset_of!(LabelHeader, offset_xl)..]) != label.crc_xl.get() {
+        return Err(LvmError::InvalidChecksum.into());
+    }
+
+    if label.label_type != LVM2_LABEL_TYPE {
+        return Err(LvmError::UnsupportedType(label.label_type).into());
+    }
+
+    let pv_offset = label.offset_xl.get();
+    if (pv_offset as usize) < size_of::<LabelHeader>() {
+        return Err(LvmError::InvalidPvHeaderOffset(pv_offset).into());
+    }
+    let Some(Ok((pv, locns))) = sector
+        .get(pv_offset as usize..)
+        .map(PvHeader::ref_from_prefix)
+    else {
+        return Err(LvmError::InvalidPvHeaderOffset(pv_offset).into());
+    };
+
+    let pv_uuid = LvmUuid::from_bytes(pv.pv_uuid).ok_or(LvmError::InvalidPvUuid)?;
+
+    // Skip past the data areas to the first metadata area, both lists must
+    // end within the sector.
+    let mut areas = locns
+        .chunks_exact(size_of::<DiskLocn>())
+        .filter_map(|b| DiskLocn::ref_from_bytes(b).ok());
+    let mda = areas
+        .by_ref()
+        .find(|dl| dl.offset.get() == 0)
+        .and_then(|_| areas.next())
+        .filter(|dl| dl.offset.get() != 0);
+
+    let vg_name = match mda {
+        Some(mda) => read_vg_name(reader, offset, mda)?,
+        None => None,
+    };
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Lvm2Member));
+    info.set(FsTag::FsId(FsId::Lvm(pv_uuid)));
+    info.set(FsTag::Usage(Usage::Raid));
+    info.set(FsTag::Version("LVM2 001".to_string()));
+    info.set(FsTag::Magic(LVM2_LABEL_ID.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    info.set(FsTag::FsSize(pv.device_size_xl.get()));
+    if let Some(vg) = vg_name {
+        info.set(FsTag::VolumeGroup(vg));
+    }
+
+    return Ok(info);
+}
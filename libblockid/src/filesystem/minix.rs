@@ -0,0 +1,297 @@
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned, transmute_ref};
+
+use crate::{
+    error::Error,
+    filesystem::{FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader},
+    probe::{Endianness, Magic, ProbeFlags, Usage},
+    std::fmt,
+};
+
+/*
+ * https://github.com/torvalds/linux/blob/master/fs/minix/minix.h
+ * https://github.com/util-linux/util-linux/blob/master/disk-utils/fsck.minix.c
+ */
+
+#[derive(Debug, Clone)]
+pub enum MinixError {
+    InvalidState(u16),
+    InvalidZoneSize(u16),
+    InvalidBlockSize(u16),
+    InvalidInodeCount(u32),
+    InvalidBitmaps,
+    ProbablyExt,
+}
+
+impl fmt::Display for MinixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MinixError::InvalidState(s) => write!(f, "Invalid filesystem state: {s:#x}"),
+            MinixError::InvalidZoneSize(l) => write!(f, "Unsupported zone size: 2^{l} blocks"),
+            MinixError::InvalidBlockSize(s) => write!(f, "Invalid block size: {s}"),
+            MinixError::InvalidInodeCount(n) => write!(f, "Invalid inode count: {n}"),
+            MinixError::InvalidBitmaps => write!(f, "Bitmaps too small for the filesystem"),
+            MinixError::ProbablyExt => write!(f, "Superblock belongs to an EXT filesystem"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<MinixError> for Error<E> {
+    fn from(e: MinixError) -> Self {
+        Error::Minix(e)
+    }
+}
+
+const SUPER_OFFSET: u64 = 1024;
+/// Magic of the v1 and v2 superblock.
+const MAGIC_OFFSET: u64 = SUPER_OFFSET + 0x10;
+/// Magic of the v3 superblock, which widened the fields before it.
+const MAGIC3_OFFSET: u64 = SUPER_OFFSET + 0x18;
+
+/// Magic of each version, stored in either byte order.
+const VERSION_MAGICS: [(u16, u8); 5] = [
+    (0x137F, 1), // 14 character names
+    (0x138F, 1), // 30 character names
+    (0x2468, 2),
+    (0x2478, 2),
+    (0x4D5A, 3),
+];
+
+pub const MINIX_MINSZ: Option<u64> = None;
+pub const MINIX_MAGICS: Option<&'static [Magic]> = Some(&[
+    Magic {
+        magic: &[0x7F, 0x13],
+        b_offset: MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &[0x13, 0x7F],
+        b_offset: MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &[0x8F, 0x13],
+        b_offset: MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &[0x13, 0x8F],
+        b_offset: MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &[0x68, 0x24],
+        b_offset: MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &[0x24, 0x68],
+        b_offset: MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &[0x78, 0x24],
+        b_offset: MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &[0x24, 0x78],
+        b_offset: MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &[0x5A, 0x4D],
+        b_offset: MAGIC3_OFFSET,
+    },
+    Magic {
+        magic: &[0x4D, 0x5A],
+        b_offset: MAGIC3_OFFSET,
+    },
+]);
+
+/// Superblock of v1 and v2, v1 only uses the 16-bit zone count.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct MinixSuperBlock {
+    ninodes: [u8; 2],
+    nzones: [u8; 2],
+    imap_blocks: [u8; 2],
+    zmap_blocks: [u8; 2],
+    firstdatazone: [u8; 2],
+    log_zone_size: [u8; 2],
+    max_size: [u8; 4],
+    magic: [u8; 2],
+    state: [u8; 2],
+    zones: [u8; 4],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct Minix3SuperBlock {
+    ninodes: [u8; 4],
+    pad0: [u8; 2],
+    imap_blocks: [u8; 2],
+    zmap_blocks: [u8; 2],
+    firstdatazone: [u8; 2],
+    log_zone_size: [u8; 2],
+    pad1: [u8; 2],
+    max_size: [u8; 4],
+    zones: [u8; 4],
+    magic: [u8; 2],
+    pad2: [u8; 2],
+    blocksize: [u8; 2],
+    disk_version: u8,
+}
+
+/// Fields common to every version, in native byte order.
+struct MinixGeometry {
+    ninodes: u32,
+    zones: u32,
+    imap_blocks: u16,
+    zmap_blocks: u16,
+    firstdatazone: u16,
+    log_zone_size: u16,
+    block_size: u16,
+}
+
+impl MinixSuperBlock {
+    const VALID_FS: u16 = 0x0001;
+    const ERROR_FS: u16 = 0x0002;
+}
+
+/// Block size of v1 and v2, v3 records its own.
+const BLOCK_SIZE: u16 = 1024;
+
+fn get_u16(bytes: [u8; 2], endianness: Endianness) -> u16 {
+    match endianness {
+        Endianness::Little => u16::from_le_bytes(bytes),
+        Endianness::Big => u16::from_be_bytes(bytes),
+    }
+}
+
+fn get_u32(bytes: [u8; 4], endianness: Endianness) -> u32 {
+    match endianness {
+        Endianness::Little => u32::from_le_bytes(bytes),
+        Endianness::Big => u32::from_be_bytes(bytes),
+    }
+}
+
+/// Finds the version and byte order from the magic that matched.
+fn version(magic: Magic) -> Option<(u8, Endianness)> {
+    let bytes: [u8; 2] = magic.magic.try_into().ok()?;
+
+    return VERSION_MAGICS.iter().find_map(|&(m, version)| {
+        let endianness = if u16::from_le_bytes(bytes) == m {
+            Endianness::Little
+        } else if u16::from_be_bytes(bytes) == m {
+            Endianness::Big
+        } else {
+            return None;
+        };
+
+        // The v3 magic is only valid at its own offset, and the others
+        // only at theirs.
+        if (version == 3) != (magic.b_offset == MAGIC3_OFFSET) {
+            return None;
+        }
+
+        return Some((version, endianness));
+    });
+}
+
+/// Sanity checks fsck.minix makes, a two byte magic alone matches far too
+/// much.
+fn check_geometry(geo: &MinixGeometry) -> Result<(), MinixError> {
+    if geo.log_zone_size != 0 {
+        return Err(MinixError::InvalidZoneSize(geo.log_zone_size));
+    }
+    if geo.block_size < BLOCK_SIZE || !geo.block_size.is_power_of_two() {
+        return Err(MinixError::InvalidBlockSize(geo.block_size));
+    }
+    if geo.ninodes == 0 || geo.ninodes == u32::MAX {
+        return Err(MinixError::InvalidInodeCount(geo.ninodes));
+    }
+
+    let bitmap_block_bits = u64::from(geo.block_size) * 8;
+    if u64::from(geo.imap_blocks) * bitmap_block_bits < u64::from(geo.ninodes) + 1 {
+        return Err(MinixError::InvalidBitmaps);
+    }
+    if u32::from(geo.firstdatazone) > geo.zones {
+        return Err(MinixError::InvalidBitmaps);
+    }
+    if u64::from(geo.zmap_blocks) * bitmap_block_bits
+        < u64::from(geo.zones - u32::from(geo.firstdatazone)) + 1
+    {
+        return Err(MinixError::InvalidBitmaps);
+    }
+
+    return Ok(());
+}
+
+pub fn probe_minix<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    _: ProbeFlags,
+    offset: u64,
+    magic: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let (version, endianness) = version(magic).ok_or(Error::UnableToLocateMagicSignature)?;
+
+    let geo = if version == 3 {
+        let buf: [u8; size_of::<Minix3SuperBlock>()] =
+            reader.read_exact_at(offset + SUPER_OFFSET)?;
+        let sb: &Minix3SuperBlock = transmute_ref!(&buf);
+
+        MinixGeometry {
+            ninodes: get_u32(sb.ninodes, endianness),
+            zones: get_u32(sb.zones, endianness),
+            imap_blocks: get_u16(sb.imap_blocks, endianness),
+            zmap_blocks: get_u16(sb.zmap_blocks, endianness),
+            firstdatazone: get_u16(sb.firstdatazone, endianness),
+            log_zone_size: get_u16(sb.log_zone_size, endianness),
+            block_size: get_u16(sb.blocksize, endianness),
+        }
+    } else {
+        let buf: [u8; size_of::<MinixSuperBlock>()] =
+            reader.read_exact_at(offset + SUPER_OFFSET)?;
+        let sb: &MinixSuperBlock = transmute_ref!(&buf);
+
+        let state = get_u16(sb.state, endianness);
+        if state & !(MinixSuperBlock::VALID_FS | MinixSuperBlock::ERROR_FS) != 0 {
+            return Err(MinixError::InvalidState(state).into());
+        }
+
+        MinixGeometry {
+            ninodes: u32::from(get_u16(sb.ninodes, endianness)),
+            zones: if version == 2 {
+                get_u32(sb.zones, endianness)
+            } else {
+                u32::from(get_u16(sb.nzones, endianness))
+            },
+            imap_blocks: get_u16(sb.imap_blocks, endianness),
+            zmap_blocks: get_u16(sb.zmap_blocks, endianness),
+            firstdatazone: get_u16(sb.firstdatazone, endianness),
+            log_zone_size: get_u16(sb.log_zone_size, endianness),
+            block_size: BLOCK_SIZE,
+        }
+    };
+
+    check_geometry(&geo)?;
+
+    // Parts of an ext superblock can pass for a minix one.
+    let ext_magic: [u8; 2] = reader.read_exact_at(offset + 0x438)?;
+    if ext_magic == [0x53, 0xEF] {
+        return Err(MinixError::ProbablyExt.into());
+    }
+
+    let block_size = u64::from(geo.block_size);
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Minix));
+    info.set(FsTag::Usage(Usage::Filesystem));
+    info.set(FsTag::Version(version.to_string()));
+    info.set(FsTag::Magic(magic.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    info.set(FsTag::FsSize(u64::from(geo.zones) * block_size));
+    info.set(FsTag::FsLastBlock(u64::from(geo.zones)));
+    info.set(FsTag::FsBlockSize(block_size));
+    info.set(FsTag::BlockSize(block_size));
+    info.set(FsTag::Endianness(endianness));
+
+    return Ok(info);
+}
@@ -0,0 +1,231 @@
+use crc::{CRC_32_ISO_HDLC, Crc};
+use uuid::Uuid;
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    byteorder::{LittleEndian, U16, U32, U64},
+    transmute_ref,
+};
+
+use crate::{
+    error::Error,
+    filesystem::{FsId, FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader, SeekFrom},
+    probe::{Magic, ProbeFlags, Usage},
+    std::{fmt, mem::offset_of, str::Utf8Error},
+    util::{decode_utf8_from, decode_utf8_lossy_from},
+};
+
+/*
+ * https://github.com/torvalds/linux/blob/master/include/uapi/linux/nilfs2_ondisk.h
+ */
+
+#[derive(Debug, Clone)]
+pub enum NilfsError {
+    Utf8Error(Utf8Error),
+    InvalidSize(u16),
+    InvalidBlockSize(u32),
+    InvalidChecksum,
+}
+
+impl fmt::Display for NilfsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NilfsError::Utf8Error(e) => write!(f, "Volume name contains invalid UTF-8: {e}"),
+            NilfsError::InvalidSize(s) => write!(f, "Invalid superblock size: {s}"),
+            NilfsError::InvalidBlockSize(bits) => write!(f, "Invalid block size: 2^{bits}"),
+            NilfsError::InvalidChecksum => write!(f, "Invalid superblock checksum"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<NilfsError> for Error<E> {
+    fn from(e: NilfsError) -> Self {
+        Error::Nilfs(e)
+    }
+}
+
+const SUPER_OFFSET: u64 = 1024;
+const MAGIC_OFFSET: u64 = offset_of!(NilfsSuperBlock, magic) as u64;
+const NILFS_MAGIC: [u8; 2] = [0x34, 0x34];
+
+/// Room for the primary superblock and the secondary in its own 4 KiB.
+pub const NILFS_MINSZ: Option<u64> = Some(2 * 4096);
+/// The secondary superblock sits in the last 4 KiB of the device and has no
+/// fixed offset, both are looked for by the prober.
+pub const NILFS_MAGICS: Option<&'static [Magic]> = None;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct NilfsSuperBlock {
+    rev_level: U32<LittleEndian>,
+    minor_rev_level: U16<LittleEndian>,
+    magic: [u8; 2],
+    bytes: U16<LittleEndian>,
+    flags: U16<LittleEndian>,
+    crc_seed: U32<LittleEndian>,
+    sum: U32<LittleEndian>,
+    log_block_size: U32<LittleEndian>,
+    nsegments: U64<LittleEndian>,
+    dev_size: U64<LittleEndian>,
+    first_data_block: U64<LittleEndian>,
+    blocks_per_segment: U32<LittleEndian>,
+    r_segments_percentage: U32<LittleEndian>,
+    last_cno: U64<LittleEndian>,
+    last_pseg: U64<LittleEndian>,
+    last_seq: U64<LittleEndian>,
+    free_blocks_count: U64<LittleEndian>,
+    ctime: U64<LittleEndian>,
+    mtime: U64<LittleEndian>,
+    wtime: U64<LittleEndian>,
+    mnt_count: U16<LittleEndian>,
+    max_mnt_count: U16<LittleEndian>,
+    state: U16<LittleEndian>,
+    errors: U16<LittleEndian>,
+    lastcheck: U64<LittleEndian>,
+    checkinterval: U32<LittleEndian>,
+    creator_os: U32<LittleEndian>,
+    def_resuid: U16<LittleEndian>,
+    def_resgid: U16<LittleEndian>,
+    first_ino: U32<LittleEndian>,
+    inode_size: U16<LittleEndian>,
+    dat_entry_size: U16<LittleEndian>,
+    checkpoint_size: U16<LittleEndian>,
+    segment_usage_size: U16<LittleEndian>,
+    uuid: [u8; 16],
+    volume_name: [u8; 80],
+    c_interval: U32<LittleEndian>,
+    c_block_max: U32<LittleEndian>,
+    feature_compat: U64<LittleEndian>,
+    feature_compat_ro: U64<LittleEndian>,
+    feature_incompat: U64<LittleEndian>,
+    reserved: [U32<LittleEndian>; 186],
+}
+
+impl NilfsSuperBlock {
+    const MIN_LOG_BLOCK_SIZE: u32 = 0;
+    const MAX_LOG_BLOCK_SIZE: u32 = 6;
+
+    /// Offset of the secondary superblock on a device of `size` bytes.
+    fn secondary_offset(size: u64) -> u64 {
+        return (size >> 12).saturating_sub(1) << 12;
+    }
+
+    /// Verifies the crc32 of the first `bytes` bytes of the superblock,
+    /// taken with the checksum zeroed, seeded with `crc_seed` and not
+    /// inverted.
+    fn verify_csum(&self) -> Result<(), NilfsError> {
+        let bytes = self.bytes.get();
+        let sum_end = offset_of!(NilfsSuperBlock, sum) + 4;
+
+        if usize::from(bytes) < sum_end || usize::from(bytes) > size_of::<NilfsSuperBlock>() {
+            return Err(NilfsError::InvalidSize(bytes));
+        }
+
+        let raw = self.as_bytes();
+        let crc = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+        let mut digest = crc.digest_with_initial(self.crc_seed.get().reverse_bits());
+
+        digest.update(&raw[..offset_of!(NilfsSuperBlock, sum)]);
+        digest.update(&[0; 4]);
+        digest.update(&raw[sum_end..usize::from(bytes)]);
+
+        // The digest inverts its result, the checksum is not inverted.
+        if !digest.finalize() != self.sum.get() {
+            return Err(NilfsError::InvalidChecksum);
+        }
+
+        return Ok(());
+    }
+}
+
+/// Reads the superblock at `sb_offset` if it has the nilfs2 magic and a
+/// valid checksum.
+fn read_super<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    sb_offset: u64,
+) -> Result<NilfsSuperBlock, Error<IO::Error>> {
+    let buf: [u8; size_of::<NilfsSuperBlock>()] = reader.read_exact_at(sb_offset)?;
+    let sb: &NilfsSuperBlock = transmute_ref!(&buf);
+
+    if sb.magic != NILFS_MAGIC {
+        return Err(Error::UnableToLocateMagicSignature);
+    }
+
+    sb.verify_csum()?;
+
+    return Ok(*sb);
+}
+
+pub fn probe_nilfs<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    _: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let end = reader.seek(SeekFrom::End(0))?;
+    let secondary_offset = NilfsSuperBlock::secondary_offset(end.saturating_sub(offset));
+
+    let primary = match read_super(reader, offset + SUPER_OFFSET) {
+        Err(Error::Io(e)) => return Err(Error::Io(e)),
+        sb => sb,
+    };
+    let secondary = match read_super(reader, offset + secondary_offset) {
+        Err(Error::Io(e)) => return Err(Error::Io(e)),
+        sb => sb,
+    };
+
+    // Like the kernel, the secondary superblock is used when the primary is
+    // damaged or older.
+    let (sb, sb_offset) = match (primary, secondary) {
+        (Ok(p), Ok(s)) if s.last_cno.get() > p.last_cno.get() => (s, secondary_offset),
+        (Ok(p), _) => (p, SUPER_OFFSET),
+        (Err(_), Ok(s)) => (s, secondary_offset),
+        (Err(e), Err(_)) => return Err(e),
+    };
+
+    let log_block_size = sb.log_block_size.get();
+    if !(NilfsSuperBlock::MIN_LOG_BLOCK_SIZE..=NilfsSuperBlock::MAX_LOG_BLOCK_SIZE)
+        .contains(&log_block_size)
+    {
+        return Err(NilfsError::InvalidBlockSize(log_block_size + 10).into());
+    }
+
+    let label = if sb.volume_name[0] != 0 {
+        if flags.contains(ProbeFlags::FailOnInvalidUTF) {
+            Some(decode_utf8_from(&sb.volume_name).map_err(NilfsError::Utf8Error)?)
+        } else {
+            Some(decode_utf8_lossy_from(&sb.volume_name))
+        }
+    } else {
+        None
+    };
+
+    let block_size = 1024u64 << log_block_size;
+    let dev_size = sb.dev_size.get();
+    let mag_offset = sb_offset + MAGIC_OFFSET;
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Nilfs2));
+    if let Some(l) = label {
+        info.set(FsTag::Label(l));
+    }
+    info.set(FsTag::FsId(FsId::Uuid(Uuid::from_bytes(sb.uuid))));
+    info.set(FsTag::Usage(Usage::Filesystem));
+    info.set(FsTag::Version(format!(
+        "{}.{}",
+        sb.rev_level.get(),
+        sb.minor_rev_level.get()
+    )));
+    info.set(FsTag::Magic(NILFS_MAGIC.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: mag_offset,
+        absolute: offset + mag_offset,
+    });
+    info.set(FsTag::FsSize(dev_size));
+    info.set(FsTag::FsLastBlock(dev_size / block_size));
+    info.set(FsTag::FsBlockSize(block_size));
+    info.set(FsTag::BlockSize(block_size));
+
+    return Ok(info);
+}
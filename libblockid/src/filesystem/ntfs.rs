@@ -179,6 +179,7 @@ impl NtfsSuperBlock {
         &self,
         reader: &mut Reader<IO>,
         flags: ProbeFlags,
+        offset: u64,
         sector_size: u64,
         sectors_per_cluster: u64,
     ) -> Result<Option<String>, Error<IO::Error>> {
@@ -200,7 +201,8 @@ impl NtfsSuperBlock {
             return Err(NtfsError::MftClusterLocationGreaterThanNrClusters.into());
         }
 
-        let mut off = u64::from(self.mft_cluster_location) * sector_size * sectors_per_cluster;
+        let mut off =
+            offset + u64::from(self.mft_cluster_location) * sector_size * sectors_per_cluster;
 
         if mft_record_size < 4 {
             return Err(NtfsError::InvalidMftRecordSize.into());
@@ -283,7 +285,7 @@ pub fn probe_is_ntfs<IO: BlockIo>(
     let sb: &NtfsSuperBlock = transmute_ref!(&buf);
 
     if reader
-        .get_magic(NTFS_MAGICS.expect("NTFS magics is not `None`"))?
+        .get_magic(offset, NTFS_MAGICS.expect("NTFS magics is not `None`"))?
         .is_none()
     {
         return Ok(false);
@@ -306,7 +308,7 @@ pub fn probe_ntfs<IO: BlockIo>(
 
     let (sector_size, sectors_per_cluster) = sb.check_ntfs()?;
 
-    let label = sb.find_label(reader, flags, sector_size, sectors_per_cluster)?;
+    let label = sb.find_label(reader, flags, offset, sector_size, sectors_per_cluster)?;
 
     let mut info = FsInfo::new();
 
@@ -319,6 +321,10 @@ pub fn probe_ntfs<IO: BlockIo>(
     )));
     info.set(FsTag::Usage(Usage::Filesystem));
     info.set(FsTag::Magic(mag.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: mag.b_offset,
+        absolute: offset + mag.b_offset,
+    });
     info.set(FsTag::FsSize(u64::from(
         sb.number_of_sectors * sector_size,
     )));
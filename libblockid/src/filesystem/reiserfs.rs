@@ -0,0 +1,181 @@
+use uuid::Uuid;
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    byteorder::{LittleEndian, U16, U32},
+    transmute_ref,
+};
+
+use crate::{
+    error::Error,
+    filesystem::{FsId, FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader},
+    probe::{Magic, ProbeFlags, Usage},
+    std::{fmt, mem::offset_of, str::Utf8Error},
+    util::{decode_utf8_from, decode_utf8_lossy_from},
+};
+
+/*
+ * https://github.com/torvalds/linux/blob/v5.19/fs/reiserfs/reiserfs.h
+ */
+
+#[derive(Debug, Clone)]
+pub enum ReiserfsError {
+    Utf8Error(Utf8Error),
+    InvalidBlockSize(u16),
+    SuperblockInJournal,
+}
+
+impl fmt::Display for ReiserfsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReiserfsError::Utf8Error(e) => {
+                write!(f, "Filesystem label contains invalid UTF-8: {e}")
+            }
+            ReiserfsError::InvalidBlockSize(s) => write!(f, "Invalid block size: {s}"),
+            ReiserfsError::SuperblockInJournal => {
+                write!(f, "Superblock found is a copy inside the journal")
+            }
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<ReiserfsError> for Error<E> {
+    fn from(e: ReiserfsError) -> Self {
+        Error::Reiserfs(e)
+    }
+}
+
+const MAGIC_OFFSET: u64 = offset_of!(ReiserfsSuperBlock, magic) as u64;
+
+/// Format 3.5 keeps its superblock at 8 KiB, later formats moved it to 64
+/// KiB, leaving room for a partition table and boot loader.
+const OLD_SUPER_OFFSET: u64 = 8 * 1024;
+const NEW_SUPER_OFFSET: u64 = 64 * 1024;
+
+const REISERFS_MAGIC: &[u8] = b"ReIsErFs";
+const REISER2FS_MAGIC: &[u8] = b"ReIsEr2Fs";
+/// Format 3.6 with a non-standard journal, relocated or on another device.
+const REISER2FS_JR_MAGIC: &[u8] = b"ReIsEr3Fs";
+
+pub const REISERFS_MINSZ: Option<u64> = None;
+pub const REISERFS_MAGICS: Option<&'static [Magic]> = Some(&[
+    Magic {
+        magic: REISER2FS_MAGIC,
+        b_offset: NEW_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: REISER2FS_JR_MAGIC,
+        b_offset: NEW_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: REISERFS_MAGIC,
+        b_offset: NEW_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: REISERFS_MAGIC,
+        b_offset: OLD_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: REISER2FS_MAGIC,
+        b_offset: OLD_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: REISER2FS_JR_MAGIC,
+        b_offset: OLD_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+]);
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct ReiserfsSuperBlock {
+    block_count: U32<LittleEndian>,
+    free_blocks: U32<LittleEndian>,
+    root_block: U32<LittleEndian>,
+    journal_block: U32<LittleEndian>,
+    journal_dev: U32<LittleEndian>,
+    journal_size: U32<LittleEndian>,
+    journal_trans_max: U32<LittleEndian>,
+    journal_magic: U32<LittleEndian>,
+    journal_max_batch: U32<LittleEndian>,
+    journal_max_commit_age: U32<LittleEndian>,
+    journal_max_trans_age: U32<LittleEndian>,
+    blocksize: U16<LittleEndian>,
+    oid_maxsize: U16<LittleEndian>,
+    oid_cursize: U16<LittleEndian>,
+    umount_state: U16<LittleEndian>,
+    magic: [u8; 10],
+    fs_state: U16<LittleEndian>,
+    hash_function_code: U32<LittleEndian>,
+    tree_height: U16<LittleEndian>,
+    bmap_nr: U16<LittleEndian>,
+    version: U16<LittleEndian>,
+    reserved_for_journal: U16<LittleEndian>,
+    inode_generation: U32<LittleEndian>,
+    flags: U32<LittleEndian>,
+    uuid: [u8; 16],
+    label: [u8; 16],
+}
+
+pub fn probe_reiserfs<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    magic: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let sb_offset = magic.b_offset - MAGIC_OFFSET;
+
+    let buf: [u8; size_of::<ReiserfsSuperBlock>()] = reader.read_exact_at(offset + sb_offset)?;
+    let sb: &ReiserfsSuperBlock = transmute_ref!(&buf);
+
+    let block_size = sb.blocksize.get();
+    if block_size < 512 || !block_size.is_power_of_two() {
+        return Err(ReiserfsError::InvalidBlockSize(block_size).into());
+    }
+
+    // The journal holds copies of the superblock, only the one in front of
+    // it is the real one. Uses the same estimate of where the journal
+    // starts as libblkid.
+    if (sb_offset >> 10) / u64::from(block_size >> 9) > u64::from(sb.journal_block.get()) / 2 {
+        return Err(ReiserfsError::SuperblockInJournal.into());
+    }
+
+    let version = match magic.magic {
+        REISER2FS_JR_MAGIC => "JR",
+        REISER2FS_MAGIC => "3.6",
+        _ => "3.5",
+    };
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Reiserfs));
+    // Only later 3.6 filesystems have a label and UUID, older ones leave
+    // the space zeroed.
+    if magic.magic != REISERFS_MAGIC {
+        if sb.label[0] != 0 {
+            let label = if flags.contains(ProbeFlags::FailOnInvalidUTF) {
+                decode_utf8_from(&sb.label).map_err(ReiserfsError::Utf8Error)?
+            } else {
+                decode_utf8_lossy_from(&sb.label)
+            };
+            info.set(FsTag::Label(label));
+        }
+        if sb.uuid != [0; 16] {
+            info.set(FsTag::FsId(FsId::Uuid(Uuid::from_bytes(sb.uuid))));
+        }
+    }
+    info.set(FsTag::Usage(Usage::Filesystem));
+    info.set(FsTag::Version(version.to_string()));
+    info.set(FsTag::Magic(magic.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    info.set(FsTag::FsSize(
+        u64::from(sb.block_count.get()) * u64::from(block_size),
+    ));
+    info.set(FsTag::FsLastBlock(u64::from(sb.block_count.get())));
+    info.set(FsTag::FsBlockSize(u64::from(block_size)));
+    info.set(FsTag::BlockSize(u64::from(block_size)));
+
+    return Ok(info);
+}
@@ -0,0 +1,265 @@
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    byteorder::{LittleEndian, U16, U32, U64},
+    transmute_ref,
+};
+
+use crate::{
+    error::Error,
+    filesystem::{FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader},
+    probe::{Endianness, Magic, ProbeFlags, Usage},
+    std::fmt,
+};
+
+/*
+ * https://github.com/plougher/squashfs-tools/blob/master/squashfs-tools/squashfs_fs.h
+ */
+
+#[derive(Debug, Clone)]
+pub enum SquashfsError {
+    UnsupportedVersion(u16, u16),
+    InvalidBlockSize(u32),
+    UnknownCompression(u16),
+    InvalidSize,
+}
+
+impl fmt::Display for SquashfsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SquashfsError::UnsupportedVersion(major, minor) => {
+                write!(f, "Unsupported squashfs version: {major}.{minor}")
+            }
+            SquashfsError::InvalidBlockSize(b) => write!(f, "Invalid block size: {b}"),
+            SquashfsError::UnknownCompression(c) => write!(f, "Unknown compression id: {c}"),
+            SquashfsError::InvalidSize => write!(f, "Invalid filesystem size"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<SquashfsError> for Error<E> {
+    fn from(e: SquashfsError) -> Self {
+        Error::Squashfs(e)
+    }
+}
+
+const LITTLE_ENDIAN_MAGIC: &[u8; 4] = b"hsqs";
+const BIG_ENDIAN_MAGIC: &[u8; 4] = b"sqsh";
+
+pub const SQUASHFS_MINSZ: Option<u64> = None;
+pub const SQUASHFS_MAGICS: Option<&'static [Magic]> = Some(&[
+    Magic {
+        magic: LITTLE_ENDIAN_MAGIC,
+        b_offset: 0,
+    },
+    Magic {
+        magic: BIG_ENDIAN_MAGIC,
+        b_offset: 0,
+    },
+]);
+
+/// Superblock used since version 4, always little-endian.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct SquashfsSuperBlock {
+    s_magic: [u8; 4],
+    inodes: U32<LittleEndian>,
+    mkfs_time: U32<LittleEndian>,
+    block_size: U32<LittleEndian>,
+    fragments: U32<LittleEndian>,
+    compression: U16<LittleEndian>,
+    block_log: U16<LittleEndian>,
+    flags: U16<LittleEndian>,
+    no_ids: U16<LittleEndian>,
+    s_major: U16<LittleEndian>,
+    s_minor: U16<LittleEndian>,
+    root_inode: U64<LittleEndian>,
+    bytes_used: U64<LittleEndian>,
+}
+
+impl SquashfsSuperBlock {
+    const MIN_BLOCK_LOG: u16 = 12;
+    const MAX_BLOCK_LOG: u16 = 20;
+    const MAX_COMPRESSION: u16 = 6;
+}
+
+/// Superblock used up to version 3, in the byte order of the machine that
+/// created it.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct Squashfs3SuperBlock {
+    s_magic: [u8; 4],
+    inodes: [u8; 4],
+    bytes_used_2: [u8; 4],
+    uid_start_2: [u8; 4],
+    guid_start_2: [u8; 4],
+    inode_table_start_2: [u8; 4],
+    directory_table_start_2: [u8; 4],
+    s_major: [u8; 2],
+    s_minor: [u8; 2],
+    block_size_1: [u8; 2],
+    block_log: [u8; 2],
+    flags: u8,
+    no_uids: u8,
+    no_guids: u8,
+    mkfs_time: [u8; 4],
+    root_inode: [u8; 8],
+    block_size: [u8; 4],
+    fragments: [u8; 4],
+    fragment_table_start_2: [u8; 4],
+    bytes_used: [u8; 8],
+}
+
+impl Squashfs3SuperBlock {
+    /// Versions before 4 only compress with zlib.
+    const COMPRESSION_ZLIB: u16 = 1;
+}
+
+fn sqfs16(endianness: Endianness, raw: [u8; 2]) -> u16 {
+    match endianness {
+        Endianness::Little => u16::from_le_bytes(raw),
+        Endianness::Big => u16::from_be_bytes(raw),
+    }
+}
+
+fn sqfs32(endianness: Endianness, raw: [u8; 4]) -> u32 {
+    match endianness {
+        Endianness::Little => u32::from_le_bytes(raw),
+        Endianness::Big => u32::from_be_bytes(raw),
+    }
+}
+
+fn sqfs64(endianness: Endianness, raw: [u8; 8]) -> u64 {
+    match endianness {
+        Endianness::Little => u64::from_le_bytes(raw),
+        Endianness::Big => u64::from_be_bytes(raw),
+    }
+}
+
+fn probe_squashfs4<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    offset: u64,
+    magic: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let buf: [u8; size_of::<SquashfsSuperBlock>()] = reader.read_exact_at(offset)?;
+    let sb: &SquashfsSuperBlock = transmute_ref!(&buf);
+
+    let block_size = sb.block_size.get();
+    let block_log = sb.block_log.get();
+
+    if !(SquashfsSuperBlock::MIN_BLOCK_LOG..=SquashfsSuperBlock::MAX_BLOCK_LOG).contains(&block_log)
+        || block_size != 1 << block_log
+    {
+        return Err(SquashfsError::InvalidBlockSize(block_size).into());
+    }
+
+    let compression = sb.compression.get();
+
+    if compression == 0 || compression > SquashfsSuperBlock::MAX_COMPRESSION {
+        return Err(SquashfsError::UnknownCompression(compression).into());
+    }
+
+    if sb.bytes_used.get() == 0 {
+        return Err(SquashfsError::InvalidSize.into());
+    }
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Squashfs));
+    info.set(FsTag::Usage(Usage::Filesystem));
+    info.set(FsTag::Version(format!(
+        "{}.{}",
+        sb.s_major.get(),
+        sb.s_minor.get()
+    )));
+    info.set(FsTag::Magic(magic.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    info.set(FsTag::FsSize(sb.bytes_used.get()));
+    info.set(FsTag::FsBlockSize(u64::from(block_size)));
+    info.set(FsTag::BlockSize(u64::from(block_size)));
+    info.set(FsTag::CompressionId(compression));
+    info.set(FsTag::Endianness(Endianness::Little));
+
+    return Ok(info);
+}
+
+fn probe_squashfs3<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    offset: u64,
+    magic: Magic,
+    endianness: Endianness,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let buf: [u8; size_of::<Squashfs3SuperBlock>()] = reader.read_exact_at(offset)?;
+    let sb: &Squashfs3SuperBlock = transmute_ref!(&buf);
+
+    let major = sqfs16(endianness, sb.s_major);
+
+    // Version 2 added the 32-bit block size and version 3 the 64-bit size.
+    let block_size = if major >= 2 {
+        sqfs32(endianness, sb.block_size)
+    } else {
+        u32::from(sqfs16(endianness, sb.block_size_1))
+    };
+    let bytes_used = if major >= 3 {
+        sqfs64(endianness, sb.bytes_used)
+    } else {
+        u64::from(sqfs32(endianness, sb.bytes_used_2))
+    };
+
+    if !block_size.is_power_of_two() {
+        return Err(SquashfsError::InvalidBlockSize(block_size).into());
+    }
+
+    if bytes_used == 0 {
+        return Err(SquashfsError::InvalidSize.into());
+    }
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Squashfs));
+    info.set(FsTag::Usage(Usage::Filesystem));
+    info.set(FsTag::Version(format!(
+        "{}.{}",
+        major,
+        sqfs16(endianness, sb.s_minor)
+    )));
+    info.set(FsTag::Magic(magic.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    info.set(FsTag::FsSize(bytes_used));
+    info.set(FsTag::FsBlockSize(u64::from(block_size)));
+    info.set(FsTag::BlockSize(u64::from(block_size)));
+    info.set(FsTag::CompressionId(Squashfs3SuperBlock::COMPRESSION_ZLIB));
+    info.set(FsTag::Endianness(endianness));
+
+    return Ok(info);
+}
+
+pub fn probe_squashfs<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    _: ProbeFlags,
+    offset: u64,
+    magic: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let endianness = if magic.magic == LITTLE_ENDIAN_MAGIC {
+        Endianness::Little
+    } else {
+        Endianness::Big
+    };
+
+    // The version is at the same offset in both layouts.
+    let version: [u8; 4] = reader.read_exact_at(offset + 28)?;
+    let major = sqfs16(endianness, [version[0], version[1]]);
+    let minor = sqfs16(endianness, [version[2], version[3]]);
+
+    match (major, endianness) {
+        (4, Endianness::Little) => probe_squashfs4(reader, offset, magic),
+        (1..=3, _) => probe_squashfs3(reader, offset, magic, endianness),
+        _ => Err(SquashfsError::UnsupportedVersion(major, minor).into()),
+    }
+}
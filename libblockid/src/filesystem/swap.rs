@@ -0,0 +1,229 @@
+use uuid::Uuid;
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned, transmute_ref};
+
+use crate::{
+    error::Error,
+    filesystem::{FsId, FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader},
+    probe::{Endianness, Magic, ProbeFlags, Usage},
+    std::{fmt, str::Utf8Error},
+    util::{decode_utf8_from, decode_utf8_lossy_from},
+};
+
+/*
+ * https://github.com/torvalds/linux/blob/master/include/linux/swap.h
+ */
+
+#[derive(Debug, Clone)]
+pub enum SwapError {
+    Utf8Error(Utf8Error),
+    UnsupportedVersion(u32),
+    InvalidLastPage,
+}
+
+impl fmt::Display for SwapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SwapError::Utf8Error(e) => write!(f, "Swap label contains invalid UTF-8: {e}"),
+            SwapError::UnsupportedVersion(v) => write!(f, "Unsupported swap header version: {v}"),
+            SwapError::InvalidLastPage => write!(f, "Swap header has no usable pages"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<SwapError> for Error<E> {
+    fn from(e: SwapError) -> Self {
+        Error::Swap(e)
+    }
+}
+
+/// Page sizes swap areas are created with, the signature is stored in the
+/// last 10 bytes of the first page.
+const PAGE_SIZES: [u64; 5] = [4096, 8192, 16384, 32768, 65536];
+const SIGNATURE_SIZE: u64 = 10;
+
+const SWAP_V0_MAGIC: &[u8] = b"SWAP-SPACE";
+const SWAP_V1_MAGIC: &[u8] = b"SWAPSPACE2";
+
+/// Suspend image signatures written over the swap signature, along with the
+/// version reported for each.
+const SUSPEND_MAGICS: [(&[u8], &str); 5] = [
+    (b"S1SUSPEND", "s1suspend"),
+    (b"S2SUSPEND", "s2suspend"),
+    (b"ULSUSPEND", "ulsuspend"),
+    (b"\xed\xc3\x02\xe9\x98\x56\xe5\x0c", "tuxonice"),
+    (b"LINHIB0001", "linhib0001"),
+];
+
+/// Builds the magics for every signature at the end of the first page for
+/// every page size.
+const fn page_magics<const N: usize>(magics: &[&'static [u8]]) -> [Magic; N] {
+    let mut out = [Magic {
+        magic: &[],
+        b_offset: 0,
+    }; N];
+
+    let mut i = 0;
+    while i < N {
+        out[i] = Magic {
+            magic: magics[i / PAGE_SIZES.len()],
+            b_offset: PAGE_SIZES[i % PAGE_SIZES.len()] - SIGNATURE_SIZE,
+        };
+        i += 1;
+    }
+
+    return out;
+}
+
+const SWAP_PAGE_MAGICS: [Magic; 10] = page_magics(&[SWAP_V0_MAGIC, SWAP_V1_MAGIC]);
+const SUSPEND_PAGE_MAGICS: [Magic; 25] = page_magics(&[
+    SUSPEND_MAGICS[0].0,
+    SUSPEND_MAGICS[1].0,
+    SUSPEND_MAGICS[2].0,
+    SUSPEND_MAGICS[3].0,
+    SUSPEND_MAGICS[4].0,
+]);
+
+pub const SWAP_MINSZ: Option<u64> = None;
+pub const SWAP_MAGICS: Option<&'static [Magic]> = Some(&SWAP_PAGE_MAGICS);
+
+pub const SWSUSPEND_MINSZ: Option<u64> = None;
+pub const SWSUSPEND_MAGICS: Option<&'static [Magic]> = Some(&SUSPEND_PAGE_MAGICS);
+
+/// Version 1 swap header, following the 1024 byte boot block in the byte
+/// order of the machine that created it.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct SwapHeaderV1 {
+    version: [u8; 4],
+    last_page: [u8; 4],
+    nr_badpages: [u8; 4],
+    uuid: [u8; 16],
+    volume_name: [u8; 16],
+}
+
+impl SwapHeaderV1 {
+    const OFFSET: u64 = 1024;
+    const VERSION: u32 = 1;
+
+    fn endianness(&self) -> Result<Endianness, SwapError> {
+        if u32::from_le_bytes(self.version) == SwapHeaderV1::VERSION {
+            return Ok(Endianness::Little);
+        } else if u32::from_be_bytes(self.version) == SwapHeaderV1::VERSION {
+            return Ok(Endianness::Big);
+        }
+
+        return Err(SwapError::UnsupportedVersion(u32::from_le_bytes(
+            self.version,
+        )));
+    }
+
+    fn last_page(&self, endianness: Endianness) -> u32 {
+        match endianness {
+            Endianness::Little => u32::from_le_bytes(self.last_page),
+            Endianness::Big => u32::from_be_bytes(self.last_page),
+        }
+    }
+}
+
+/// Adds the details of the version 1 header to `info`, shared by swap areas
+/// and the suspend images written into them.
+fn set_v1_info<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    page_size: u64,
+    info: &mut FsInfo,
+) -> Result<(), Error<IO::Error>> {
+    let buf: [u8; size_of::<SwapHeaderV1>()] =
+        reader.read_exact_at(offset + SwapHeaderV1::OFFSET)?;
+    let hdr: &SwapHeaderV1 = transmute_ref!(&buf);
+
+    let endianness = hdr.endianness()?;
+    let last_page = hdr.last_page(endianness);
+
+    if last_page == 0 {
+        return Err(SwapError::InvalidLastPage.into());
+    }
+
+    if hdr.volume_name[0] != 0 {
+        let label = if flags.contains(ProbeFlags::FailOnInvalidUTF) {
+            decode_utf8_from(&hdr.volume_name).map_err(SwapError::Utf8Error)?
+        } else {
+            decode_utf8_lossy_from(&hdr.volume_name)
+        };
+        info.set(FsTag::Label(label));
+    }
+    if hdr.uuid != [0; 16] {
+        info.set(FsTag::FsId(FsId::Uuid(Uuid::from_bytes(hdr.uuid))));
+    }
+    info.set(FsTag::FsSize((u64::from(last_page) + 1) * page_size));
+    info.set(FsTag::FsLastBlock(u64::from(last_page)));
+    info.set(FsTag::Endianness(endianness));
+
+    return Ok(());
+}
+
+pub fn probe_swap<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    magic: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let page_size = magic.b_offset + SIGNATURE_SIZE;
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Swap));
+    info.set(FsTag::Usage(Usage::Other));
+    if magic.magic == SWAP_V1_MAGIC {
+        set_v1_info(reader, flags, offset, page_size, &mut info)?;
+        info.set(FsTag::Version(SwapHeaderV1::VERSION.to_string()));
+    } else {
+        // The old format only has a bitmap of usable pages.
+        info.set(FsTag::Version(String::from("0")));
+    }
+    info.set(FsTag::Magic(magic.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    info.set(FsTag::FsBlockSize(page_size));
+    info.set(FsTag::BlockSize(page_size));
+
+    return Ok(info);
+}
+
+/// Probes for a hibernation image, written over a version 1 swap area
+/// which must not be reused as swap until the image has been resumed from
+/// or discarded.
+pub fn probe_swsuspend<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    magic: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let page_size = magic.b_offset + SIGNATURE_SIZE;
+
+    let version = SUSPEND_MAGICS
+        .iter()
+        .find(|(m, _)| *m == magic.magic)
+        .map(|(_, v)| *v)
+        .unwrap_or_default();
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Swsuspend));
+    info.set(FsTag::Usage(Usage::Other));
+    set_v1_info(reader, flags, offset, page_size, &mut info)?;
+    info.set(FsTag::Version(version.to_string()));
+    info.set(FsTag::Magic(magic.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    info.set(FsTag::FsBlockSize(page_size));
+    info.set(FsTag::BlockSize(page_size));
+
+    return Ok(info);
+}
@@ -0,0 +1,412 @@
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    byteorder::{LittleEndian, U16, U32},
+    transmute_ref,
+};
+
+use crate::{
+    error::Error,
+    filesystem::{
+        FsId, FsInfo, FsTag, FsType, PseudoUuid,
+        iso9660::{VD_OFFSET, VD_SIZE},
+    },
+    io::{BlockIo, Reader, SeekFrom},
+    probe::{Endianness, Magic, ProbeFlags, Usage},
+    std::fmt,
+    util::decode_utf16_lossy_from,
+};
+
+/*
+ * ECMA-167 https://ecma-international.org/publications-and-standards/standards/ecma-167/
+ * OSTA UDF http://www.osta.org/specs/pdf/udf260.pdf
+ */
+
+#[derive(Debug, Clone)]
+pub enum UdfError {
+    MissingNsrDescriptor,
+    MissingAnchor,
+    MissingVolumeDescriptor,
+}
+
+impl fmt::Display for UdfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UdfError::MissingNsrDescriptor => {
+                write!(f, "No NSR descriptor in volume recognition sequence")
+            }
+            UdfError::MissingAnchor => write!(f, "No anchor volume descriptor pointer found"),
+            UdfError::MissingVolumeDescriptor => {
+                write!(f, "No primary or logical volume descriptor found")
+            }
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<UdfError> for Error<E> {
+    fn from(e: UdfError) -> Self {
+        Error::Udf(e)
+    }
+}
+
+/// Structure identifiers found in the volume recognition sequence.
+const VRS_IDS: [&[u8; 5]; 7] = [
+    b"BEA01", b"BOOT2", b"CD001", b"CDW02", b"NSR02", b"NSR03", b"TEA01",
+];
+
+pub const UDF_MINSZ: Option<u64> = None;
+pub const UDF_MAGICS: Option<&'static [Magic]> = Some(&[
+    Magic {
+        magic: VRS_IDS[0],
+        b_offset: VD_OFFSET + 1,
+    },
+    Magic {
+        magic: VRS_IDS[1],
+        b_offset: VD_OFFSET + 1,
+    },
+    Magic {
+        magic: VRS_IDS[2],
+        b_offset: VD_OFFSET + 1,
+    },
+    Magic {
+        magic: VRS_IDS[3],
+        b_offset: VD_OFFSET + 1,
+    },
+    Magic {
+        magic: VRS_IDS[4],
+        b_offset: VD_OFFSET + 1,
+    },
+    Magic {
+        magic: VRS_IDS[5],
+        b_offset: VD_OFFSET + 1,
+    },
+    Magic {
+        magic: VRS_IDS[6],
+        b_offset: VD_OFFSET + 1,
+    },
+]);
+
+/// Logical sector sizes tried when looking for the anchor.
+const BLOCK_SIZES: [u64; 4] = [512, 1024, 2048, 4096];
+
+/// Sector the anchor volume descriptor pointer is recorded at.
+const ANCHOR_SECTOR: u64 = 256;
+
+/// Limit on the number of descriptors read from each sequence.
+const MAX_DESCRIPTORS: u64 = 64;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct DescriptorTag {
+    tag_ident: U16<LittleEndian>,
+    descriptor_version: U16<LittleEndian>,
+    checksum: u8,
+    reserved: u8,
+    serial_number: U16<LittleEndian>,
+    descriptor_crc: U16<LittleEndian>,
+    descriptor_crc_length: U16<LittleEndian>,
+    location: U32<LittleEndian>,
+}
+
+impl DescriptorTag {
+    const PRIMARY_VOLUME: u16 = 1;
+    const ANCHOR_VOLUME_POINTER: u16 = 2;
+    const LOGICAL_VOLUME: u16 = 6;
+    const TERMINATING: u16 = 8;
+
+    /// Checks the tag checksum, the sum of every other byte of the tag, and
+    /// that the tag records the sector it was read from.
+    fn is_valid(&self, sector: u64) -> bool {
+        let sum = self
+            .as_bytes()
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != 4)
+            .fold(0u8, |acc, (_, b)| acc.wrapping_add(*b));
+
+        return sum == self.checksum && u64::from(self.location.get()) == sector;
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct AnchorVolumeDescriptor {
+    tag: DescriptorTag,
+    main_vds_length: U32<LittleEndian>,
+    main_vds_location: U32<LittleEndian>,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct PrimaryVolumeDescriptor {
+    tag: DescriptorTag,
+    vds_number: U32<LittleEndian>,
+    pvd_number: U32<LittleEndian>,
+    volume_id: [u8; 32],
+    volume_seq_number: U16<LittleEndian>,
+    max_volume_seq_number: U16<LittleEndian>,
+    interchange_level: U16<LittleEndian>,
+    max_interchange_level: U16<LittleEndian>,
+    charset_list: U32<LittleEndian>,
+    max_charset_list: U32<LittleEndian>,
+    volume_set_id: [u8; 128],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct LogicalVolumeDescriptor {
+    tag: DescriptorTag,
+    vds_number: U32<LittleEndian>,
+    charset: [u8; 64],
+    logical_volume_id: [u8; 128],
+    logical_block_size: U32<LittleEndian>,
+    domain_flags: u8,
+    domain_id: [u8; 23],
+    udf_revision: U16<LittleEndian>,
+    domain_suffix: [u8; 6],
+}
+
+impl LogicalVolumeDescriptor {
+    /// Domain identifier whose suffix records the UDF revision.
+    const OSTA_DOMAIN: &[u8] = b"*OSTA UDF Compliant";
+}
+
+/// Decodes a `dstring`, whose first byte gives the character width and last
+/// byte the number of bytes used, including the first. Compression ids 254
+/// and 255 mark the same widths as 8 and 16, any other id leaves the string
+/// undecoded.
+fn decode_dstring(raw: &[u8]) -> Option<String> {
+    let (&used, raw) = raw.split_last()?;
+    let used = usize::from(used).min(raw.len());
+
+    if used <= 1 {
+        return None;
+    }
+
+    let chars = &raw[1..used];
+
+    let id: String = match raw[0] {
+        8 | 254 => chars.iter().map(|&c| char::from(c)).collect(),
+        16 | 255 => decode_utf16_lossy_from(chars, Endianness::Big).to_string(),
+        _ => return None,
+    };
+
+    let id = id.trim_end_matches([' ', '\0']);
+
+    return (!id.is_empty()).then(|| id.to_string());
+}
+
+/// Builds an identifier from the start of the volume set identifier the same
+/// way libblkid does for the UDF UUID. Up to 16 leading lowercase hex digits
+/// are kept as is, the first 8 bytes are hex encoded if there are fewer than
+/// 8 of them and the first 4 are hex encoded otherwise.
+fn volume_set_uuid(volume_set_id: &str) -> Option<PseudoUuid> {
+    if volume_set_id.is_empty() {
+        return None;
+    }
+
+    let mut buf = [0u8; 16];
+    let len = volume_set_id.len().min(buf.len());
+    buf[..len].copy_from_slice(&volume_set_id.as_bytes()[..len]);
+
+    let hex_len = buf
+        .iter()
+        .position(|c| !matches!(c, b'0'..=b'9' | b'a'..=b'f'))
+        .unwrap_or(buf.len());
+
+    let id = if hex_len < 8 {
+        buf[..8]
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>()
+    } else if hex_len < 16 {
+        let mut id = String::from_utf8_lossy(&buf[..8]).to_string();
+        id.extend(buf[8..12].iter().map(|b| format!("{b:02x}")));
+        id
+    } else {
+        String::from_utf8_lossy(&buf).to_string()
+    };
+
+    return PseudoUuid::new(&id);
+}
+
+/// Walks the volume recognition sequence looking for an NSR descriptor,
+/// each descriptor taking a 2048 byte sector or a whole larger one.
+fn has_nsr<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    offset: u64,
+    end: u64,
+    block_size: u64,
+) -> Result<bool, Error<IO::Error>> {
+    let step = block_size.max(VD_SIZE);
+
+    for i in 0..MAX_DESCRIPTORS {
+        let vd_offset = offset + VD_OFFSET + i * step;
+
+        if vd_offset + 6 > end {
+            break;
+        }
+
+        let vd: [u8; 6] = reader.read_exact_at(vd_offset)?;
+
+        if vd[0] == 0 && (&vd[1..] == VRS_IDS[4] || &vd[1..] == VRS_IDS[5]) {
+            return Ok(true);
+        }
+
+        if !VRS_IDS.iter().any(|id| vd[1..] == id[..]) {
+            break;
+        }
+    }
+
+    return Ok(false);
+}
+
+/// Finds the anchor volume descriptor pointer, returning it along with the
+/// logical sector size it was found with.
+fn find_anchor<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    offset: u64,
+    end: u64,
+) -> Result<(u64, AnchorVolumeDescriptor), Error<IO::Error>> {
+    let mut nsr = false;
+
+    for block_size in BLOCK_SIZES {
+        if !has_nsr(reader, offset, end, block_size)? {
+            continue;
+        }
+        nsr = true;
+
+        // The anchor is also recorded in the last sector of the volume.
+        let last = (end - offset) / block_size;
+        for sector in [ANCHOR_SECTOR, last.saturating_sub(1)] {
+            let avd_offset = offset + sector * block_size;
+
+            if avd_offset + size_of::<AnchorVolumeDescriptor>() as u64 > end {
+                continue;
+            }
+
+            let buf: [u8; size_of::<AnchorVolumeDescriptor>()] =
+                reader.read_exact_at(avd_offset)?;
+            let avd: &AnchorVolumeDescriptor = transmute_ref!(&buf);
+
+            if avd.tag.tag_ident.get() == DescriptorTag::ANCHOR_VOLUME_POINTER
+                && avd.tag.is_valid(sector)
+            {
+                return Ok((block_size, *avd));
+            }
+        }
+    }
+
+    if !nsr {
+        return Err(UdfError::MissingNsrDescriptor.into());
+    }
+
+    return Err(UdfError::MissingAnchor.into());
+}
+
+pub fn probe_udf<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    _: ProbeFlags,
+    offset: u64,
+    magic: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let end = reader.seek(SeekFrom::End(0))?;
+
+    let (block_size, avd) = find_anchor(reader, offset, end)?;
+
+    let mut pvd: Option<PrimaryVolumeDescriptor> = None;
+    let mut lvd: Option<LogicalVolumeDescriptor> = None;
+
+    let vds_location = u64::from(avd.main_vds_location.get());
+    let vds_count = (u64::from(avd.main_vds_length.get()) / block_size).min(MAX_DESCRIPTORS);
+
+    for sector in vds_location..vds_location + vds_count {
+        let vd_offset = offset + sector * block_size;
+
+        if vd_offset + size_of::<LogicalVolumeDescriptor>() as u64 > end {
+            break;
+        }
+
+        let buf: [u8; size_of::<LogicalVolumeDescriptor>()] = reader.read_exact_at(vd_offset)?;
+        let Ok((tag, _)) = DescriptorTag::ref_from_prefix(&buf) else {
+            break;
+        };
+
+        if !tag.is_valid(sector) {
+            break;
+        }
+
+        match tag.tag_ident.get() {
+            DescriptorTag::PRIMARY_VOLUME if pvd.is_none() => {
+                pvd = PrimaryVolumeDescriptor::read_from_prefix(&buf)
+                    .ok()
+                    .map(|(vd, _)| vd);
+            }
+            DescriptorTag::LOGICAL_VOLUME if lvd.is_none() => {
+                let vd: &LogicalVolumeDescriptor = transmute_ref!(&buf);
+                lvd = Some(*vd);
+            }
+            DescriptorTag::TERMINATING => break,
+            _ => {}
+        }
+    }
+
+    if pvd.is_none() && lvd.is_none() {
+        return Err(UdfError::MissingVolumeDescriptor.into());
+    }
+
+    // The logical volume identifier is the one shown by most systems, the
+    // primary volume identifier is limited to 30 characters.
+    let label = match lvd {
+        Some(lvd) => decode_dstring(&lvd.logical_volume_id),
+        None => None,
+    };
+    let label = match (label, pvd) {
+        (Some(label), _) => Some(label),
+        (None, Some(pvd)) => decode_dstring(&pvd.volume_id),
+        (None, None) => None,
+    };
+
+    let uuid = match pvd {
+        Some(pvd) => decode_dstring(&pvd.volume_set_id)
+            .as_deref()
+            .and_then(volume_set_uuid),
+        None => None,
+    };
+
+    let fs_block_size = match lvd {
+        Some(lvd) if lvd.logical_block_size.get() != 0 => u64::from(lvd.logical_block_size.get()),
+        _ => block_size,
+    };
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Udf));
+    if let Some(l) = label {
+        info.set(FsTag::Label(l));
+    }
+    if let Some(id) = uuid {
+        info.set(FsTag::FsId(FsId::PseudoUuid(id)));
+    }
+    info.set(FsTag::Usage(Usage::Filesystem));
+    if let Some(lvd) = lvd
+        && lvd
+            .domain_id
+            .starts_with(LogicalVolumeDescriptor::OSTA_DOMAIN)
+    {
+        let revision = lvd.udf_revision.get();
+        info.set(FsTag::Version(format!(
+            "{:x}.{:02x}",
+            revision >> 8,
+            revision & 0xff
+        )));
+    }
+    info.set(FsTag::Magic(magic.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    info.set(FsTag::FsBlockSize(fs_block_size));
+    info.set(FsTag::BlockSize(block_size));
+
+    return Ok(info);
+}
@@ -0,0 +1,246 @@
+use fat_volume_id::id64::VolumeId64;
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned, transmute_ref};
+
+use crate::{
+    error::Error,
+    filesystem::{FsId, FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader},
+    probe::{Endianness, Magic, ProbeFlags, Usage},
+    std::{fmt, mem::offset_of, str::Utf8Error},
+    util::{decode_utf8_from, decode_utf8_lossy_from},
+};
+
+/*
+ * https://github.com/freebsd/freebsd-src/blob/main/sys/ufs/ffs/fs.h
+ */
+
+#[derive(Debug, Clone)]
+pub enum UfsError {
+    Utf8Error(Utf8Error),
+    InvalidBlockSize(u32),
+    InvalidFragmentSize(u32),
+}
+
+impl fmt::Display for UfsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UfsError::Utf8Error(e) => write!(f, "Volume name contains invalid UTF-8: {e}"),
+            UfsError::InvalidBlockSize(s) => write!(f, "Invalid block size: {s}"),
+            UfsError::InvalidFragmentSize(s) => write!(f, "Invalid fragment size: {s}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<UfsError> for Error<E> {
+    fn from(e: UfsError) -> Self {
+        Error::Ufs(e)
+    }
+}
+
+const MAGIC_OFFSET: u64 = offset_of!(UfsSuperBlock, magic) as u64;
+
+/// Superblock locations newfs uses, tried by the kernel in this order.
+const UFS2_SUPER_OFFSET: u64 = 64 * 1024;
+const UFS1_SUPER_OFFSET: u64 = 8 * 1024;
+const FLOPPY_SUPER_OFFSET: u64 = 0;
+const PIGGY_SUPER_OFFSET: u64 = 256 * 1024;
+
+const UFS1_MAGIC: u32 = 0x0001_1954;
+const UFS2_MAGIC: u32 = 0x1954_0119;
+
+pub const UFS_MINSZ: Option<u64> = None;
+/// Either format may be found at any of the superblock locations, written in
+/// the byte order of the machine that created it.
+pub const UFS_MAGICS: Option<&'static [Magic]> = Some(&[
+    Magic {
+        magic: &UFS2_MAGIC.to_le_bytes(),
+        b_offset: UFS2_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &UFS2_MAGIC.to_be_bytes(),
+        b_offset: UFS2_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &UFS1_MAGIC.to_le_bytes(),
+        b_offset: UFS2_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &UFS1_MAGIC.to_be_bytes(),
+        b_offset: UFS2_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &UFS1_MAGIC.to_le_bytes(),
+        b_offset: UFS1_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &UFS1_MAGIC.to_be_bytes(),
+        b_offset: UFS1_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &UFS2_MAGIC.to_le_bytes(),
+        b_offset: UFS1_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &UFS2_MAGIC.to_be_bytes(),
+        b_offset: UFS1_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &UFS1_MAGIC.to_le_bytes(),
+        b_offset: FLOPPY_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &UFS1_MAGIC.to_be_bytes(),
+        b_offset: FLOPPY_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &UFS2_MAGIC.to_le_bytes(),
+        b_offset: FLOPPY_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &UFS2_MAGIC.to_be_bytes(),
+        b_offset: FLOPPY_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &UFS2_MAGIC.to_le_bytes(),
+        b_offset: PIGGY_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &UFS2_MAGIC.to_be_bytes(),
+        b_offset: PIGGY_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &UFS1_MAGIC.to_le_bytes(),
+        b_offset: PIGGY_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+    Magic {
+        magic: &UFS1_MAGIC.to_be_bytes(),
+        b_offset: PIGGY_SUPER_OFFSET + MAGIC_OFFSET,
+    },
+]);
+
+/// The fields of `struct fs` used for probing, the rest is left as padding.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct UfsSuperBlock {
+    pad0: [u8; 36],
+    old_size: [u8; 4],
+    old_dsize: [u8; 4],
+    ncg: [u8; 4],
+    bsize: [u8; 4],
+    fsize: [u8; 4],
+    pad1: [u8; 88],
+    id: [[u8; 4]; 2],
+    pad2: [u8; 528],
+    volname: [u8; 32],
+    pad3: [u8; 368],
+    size: [u8; 8],
+    dsize: [u8; 8],
+    pad4: [u8; 276],
+    magic: [u8; 4],
+}
+
+impl UfsSuperBlock {
+    const MIN_BSIZE: u32 = 4096;
+    const MAX_BSIZE: u32 = 65536;
+    const MIN_FSIZE: u32 = 512;
+}
+
+fn get_u32(bytes: [u8; 4], endianness: Endianness) -> u32 {
+    match endianness {
+        Endianness::Little => u32::from_le_bytes(bytes),
+        Endianness::Big => u32::from_be_bytes(bytes),
+    }
+}
+
+fn get_u64(bytes: [u8; 8], endianness: Endianness) -> u64 {
+    match endianness {
+        Endianness::Little => u64::from_le_bytes(bytes),
+        Endianness::Big => u64::from_be_bytes(bytes),
+    }
+}
+
+pub fn probe_ufs<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    magic: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let sb_offset = magic.b_offset - MAGIC_OFFSET;
+
+    let buf: [u8; size_of::<UfsSuperBlock>()] = reader.read_exact_at(offset + sb_offset)?;
+    let sb: &UfsSuperBlock = transmute_ref!(&buf);
+
+    let (ufs2, endianness) = if sb.magic == UFS2_MAGIC.to_le_bytes() {
+        (true, Endianness::Little)
+    } else if sb.magic == UFS2_MAGIC.to_be_bytes() {
+        (true, Endianness::Big)
+    } else if sb.magic == UFS1_MAGIC.to_le_bytes() {
+        (false, Endianness::Little)
+    } else if sb.magic == UFS1_MAGIC.to_be_bytes() {
+        (false, Endianness::Big)
+    } else {
+        return Err(Error::UnableToLocateMagicSignature);
+    };
+
+    let bsize = get_u32(sb.bsize, endianness);
+    if !(UfsSuperBlock::MIN_BSIZE..=UfsSuperBlock::MAX_BSIZE).contains(&bsize)
+        || !bsize.is_power_of_two()
+    {
+        return Err(UfsError::InvalidBlockSize(bsize).into());
+    }
+
+    let fsize = get_u32(sb.fsize, endianness);
+    if fsize < UfsSuperBlock::MIN_FSIZE || fsize > bsize || !fsize.is_power_of_two() {
+        return Err(UfsError::InvalidFragmentSize(fsize).into());
+    }
+
+    // UFS1 has its volume name in the same place, but older systems used the
+    // space for a longer last mount point instead.
+    let label = if ufs2 && sb.volname[0] != 0 {
+        if flags.contains(ProbeFlags::FailOnInvalidUTF) {
+            Some(decode_utf8_from(&sb.volname).map_err(UfsError::Utf8Error)?)
+        } else {
+            Some(decode_utf8_lossy_from(&sb.volname))
+        }
+    } else {
+        None
+    };
+
+    // The id is two 32-bit words, the first made from the creation time and
+    // the second random.
+    let fs_id =
+        u64::from(get_u32(sb.id[0], endianness)) << 32 | u64::from(get_u32(sb.id[1], endianness));
+
+    // Sizes are kept in fragments.
+    let frags = if ufs2 {
+        get_u64(sb.size, endianness)
+    } else {
+        u64::from(get_u32(sb.old_size, endianness))
+    };
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Ufs));
+    if let Some(l) = label {
+        info.set(FsTag::Label(l));
+    }
+    if fs_id != 0 {
+        info.set(FsTag::FsId(FsId::VolumeId64(VolumeId64::from_bytes(
+            fs_id.to_le_bytes(),
+        ))));
+    }
+    info.set(FsTag::Usage(Usage::Filesystem));
+    info.set(FsTag::Version(if ufs2 { "2" } else { "1" }.to_string()));
+    info.set(FsTag::Magic(magic.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    info.set(FsTag::FsSize(frags.saturating_mul(u64::from(fsize))));
+    info.set(FsTag::FsLastBlock(frags));
+    info.set(FsTag::FsBlockSize(u64::from(fsize)));
+    info.set(FsTag::BlockSize(u64::from(fsize)));
+    info.set(FsTag::Endianness(endianness));
+
+    return Ok(info);
+}
@@ -0,0 +1,137 @@
+use uuid::Uuid;
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    byteorder::{LittleEndian, U16, U32, U64},
+    transmute_ref,
+};
+
+use crate::{
+    error::Error,
+    filesystem::{FsId, FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader},
+    probe::{Magic, ProbeFlags, Usage},
+    std::fmt,
+};
+
+/*
+ * https://gitlab.com/cryptsetup/cryptsetup/-/wikis/DMVerity
+ * https://gitlab.com/cryptsetup/cryptsetup/-/blob/main/lib/verity/verity.c
+ */
+
+#[derive(Debug, Clone)]
+pub enum VerityError {
+    UnsupportedVersion(u32),
+    UnsupportedHashType(u32),
+    InvalidBlockSize(u32),
+    InvalidSaltSize(u16),
+}
+
+impl fmt::Display for VerityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerityError::UnsupportedVersion(v) => write!(f, "Unsupported superblock version: {v}"),
+            VerityError::UnsupportedHashType(t) => write!(f, "Unsupported hash type: {t}"),
+            VerityError::InvalidBlockSize(s) => write!(f, "Invalid block size: {s}"),
+            VerityError::InvalidSaltSize(s) => write!(f, "Invalid salt size: {s}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<VerityError> for Error<E> {
+    fn from(e: VerityError) -> Self {
+        Error::Verity(e)
+    }
+}
+
+const VERITY_MAGIC: [u8; 8] = *b"verity\0\0";
+
+pub const VERITY_MINSZ: Option<u64> = Some(size_of::<VeritySuperBlock>() as u64);
+pub const VERITY_MAGICS: Option<&'static [Magic]> = Some(&[Magic {
+    magic: &VERITY_MAGIC,
+    b_offset: 0,
+}]);
+
+/// Written by veritysetup at the start of the hash device, unless it was
+/// formatted with `--no-superblock`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct VeritySuperBlock {
+    signature: [u8; 8],
+    version: U32<LittleEndian>,
+    hash_type: U32<LittleEndian>,
+    uuid: [u8; 16],
+    algorithm: [u8; 32],
+    data_block_size: U32<LittleEndian>,
+    hash_block_size: U32<LittleEndian>,
+    data_blocks: U64<LittleEndian>,
+    salt_size: U16<LittleEndian>,
+    pad1: [u8; 6],
+    salt: [u8; 256],
+    pad2: [u8; 168],
+}
+
+impl VeritySuperBlock {
+    const VERSION: u32 = 1;
+    /// Hash type 0 is the original Chrome OS format, 1 the current one.
+    const MAX_HASH_TYPE: u32 = 1;
+    const MIN_BLOCK_SIZE: u32 = 512;
+    const MAX_BLOCK_SIZE: u32 = 512 * 1024;
+}
+
+fn check_block_size(size: u32) -> Result<u64, VerityError> {
+    if !(VeritySuperBlock::MIN_BLOCK_SIZE..=VeritySuperBlock::MAX_BLOCK_SIZE).contains(&size)
+        || !size.is_power_of_two()
+    {
+        return Err(VerityError::InvalidBlockSize(size));
+    }
+
+    return Ok(u64::from(size));
+}
+
+pub fn probe_verity<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    _: ProbeFlags,
+    offset: u64,
+    magic: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let buf: [u8; size_of::<VeritySuperBlock>()] = reader.read_exact_at(offset)?;
+    let sb: &VeritySuperBlock = transmute_ref!(&buf);
+
+    let version = sb.version.get();
+    if version != VeritySuperBlock::VERSION {
+        return Err(VerityError::UnsupportedVersion(version).into());
+    }
+
+    let hash_type = sb.hash_type.get();
+    if hash_type > VeritySuperBlock::MAX_HASH_TYPE {
+        return Err(VerityError::UnsupportedHashType(hash_type).into());
+    }
+
+    let data_block_size = check_block_size(sb.data_block_size.get())?;
+    let hash_block_size = check_block_size(sb.hash_block_size.get())?;
+
+    let salt_size = sb.salt_size.get();
+    if usize::from(salt_size) > sb.salt.len() {
+        return Err(VerityError::InvalidSaltSize(salt_size).into());
+    }
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::DmVerityHash));
+    info.set(FsTag::FsId(FsId::Uuid(Uuid::from_bytes(sb.uuid))));
+    info.set(FsTag::Usage(Usage::Crypto));
+    info.set(FsTag::Version(version.to_string()));
+    info.set(FsTag::Magic(magic.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    info.set(FsTag::DataBlockSize(data_block_size));
+    info.set(FsTag::HashBlockSize(hash_block_size));
+    info.set(FsTag::BlockSize(hash_block_size));
+    if salt_size != 0 {
+        info.set(FsTag::Salt(sb.salt[..usize::from(salt_size)].to_vec()));
+    }
+
+    return Ok(info);
+}
@@ -7,7 +7,7 @@ use zerocopy::{
 
 use crate::{
     error::Error,
-    filesystem::{FsInfo, FsTag, FsType, SubType, FsId},
+    filesystem::{FsInfo, FsTag, FsType, SubType, FsId, bitlocker::probe_is_bitlocker},
     io::{BlockIo, Reader},
     probe::{ Magic, ProbeFlags, Usage},
     std::{fmt, str::Utf8Error},
@@ -22,6 +22,7 @@ pub enum VFatError {
     ProbablyDOS,
     ProbablyJFS,
     ProbablyHPFS,
+    ProbablyBitlocker,
     InvalidFatTableCount,
     InvalidReservedValue,
     InvalidSectorRange,
@@ -45,6 +46,7 @@ impl fmt::Display for VFatError {
             VFatError::ProbablyDOS => write!(f, "Filesystem looks like DOS/MBR"),
             VFatError::ProbablyJFS => write!(f, "Filesystem has JFS magic number"),
             VFatError::ProbablyHPFS => write!(f, "Filesystem has HPFS magic number"),
+            VFatError::ProbablyBitlocker => write!(f, "Filesystem is BitLocker To Go"),
             VFatError::InvalidFatTableCount => write!(f, "Should be atleast one fat table"),
             VFatError::InvalidReservedValue => write!(f, "ms_reserved is invalid"),
             VFatError::InvalidSectorRange => write!(f, "sector size is not within 512 to 4096"),
@@ -330,7 +332,7 @@ pub fn probe_is_vfat<IO: BlockIo>(
     let ms: &MsDosSuperBlock = transmute_ref!(&buf);
     let vs: &VFatSuperBlock = transmute_ref!(&buf);
 
-    let mag: Magic = match reader.get_magic(VFAT_MAGICS.expect("VFAT magics is not `None`"))? {
+    let mag: Magic = match reader.get_magic(offset, VFAT_MAGICS.expect("VFAT magics is not `None`"))? {
         Some(t) => t,
         None => return Err(VFatError::InvalidFatSignature.into()),
     };
@@ -390,15 +392,21 @@ pub fn search_fat_label<IO: BlockIo>(
 fn probe_fat16<IO: BlockIo>(
     reader: &mut Reader<IO>,
     flags: ProbeFlags,
+    offset: u64,
     ms: &MsDosSuperBlock,
     vs: &VFatSuperBlock,
     fat_size: u32,
 ) -> Result<(Option<String>, VolumeId32), Error<IO::Error>> {
     let reserved: u32 = ms.ms_reserved.into();
 
-    let root_start: u32 = (reserved + fat_size) * u32::from(ms.ms_sector_size);
+    let root_start: u64 = u64::from(reserved + fat_size) * u64::from(ms.ms_sector_size);
 
-    let vol_label = search_fat_label(reader, flags, root_start.into(), vs.vs_dir_entries.into())?;
+    let vol_label = search_fat_label(
+        reader,
+        flags,
+        offset + root_start,
+        vs.vs_dir_entries.into(),
+    )?;
 
     let vol_serno = if ms.ms_ext_boot_sign == 0x28 || ms.ms_ext_boot_sign == 0x29 {
         VolumeId32::from_bytes(ms.ms_serno)
@@ -412,6 +420,7 @@ fn probe_fat16<IO: BlockIo>(
 fn probe_fat32<IO: BlockIo>(
     reader: &mut Reader<IO>,
     flags: ProbeFlags,
+    offset: u64,
     ms: &MsDosSuperBlock,
     vs: &VFatSuperBlock,
     fat_size: u32,
@@ -436,14 +445,14 @@ fn probe_fat32<IO: BlockIo>(
         let next_off: u64 = (start_data_sect as u64 + next_sect_off) * u64::from(ms.ms_sector_size);
         let count: u64 = buf_size / 32;
 
-        match search_fat_label(reader, flags, next_off, count)? {
+        match search_fat_label(reader, flags, offset + next_off, count)? {
             Some(label) => {
                 break Some(label);
             }
             None => {
                 let fat_entry_off =
                     (u64::from(reserved) * u64::from(ms.ms_sector_size)) + (u64::from(next) * 4);
-                let buf = reader.read_vec_at(fat_entry_off, buf_size as usize)?;
+                let buf = reader.read_vec_at(offset + fat_entry_off, buf_size as usize)?;
 
                 if buf.len() < 4 {
                     break None;
@@ -459,7 +468,7 @@ fn probe_fat32<IO: BlockIo>(
     let fsinfo_sect = u64::from(vs.vs_fsinfo_sector);
     if fsinfo_sect != 0 {
         let buf: [u8; size_of::<Fat32FsInfo>()] =
-            reader.read_exact_at(fsinfo_sect * u64::from(ms.ms_sector_size))?;
+            reader.read_exact_at(offset + fsinfo_sect * u64::from(ms.ms_sector_size))?;
 
         let fsinfo: &Fat32FsInfo = transmute_ref!(&buf);
 
@@ -491,12 +500,16 @@ pub fn probe_vfat<IO: BlockIo>(
 
     let sub_type = valid_fat(ms, vs, &magic)?;
 
+    if probe_is_bitlocker(reader, offset)? {
+        return Err(VFatError::ProbablyBitlocker.into());
+    }
+
     let fat_size = get_fat_size(ms, vs).ok_or(VFatError::Overflow)?;
 
     let (label, serno) = if ms.ms_fat_length != 0 {
-        probe_fat16(reader, flags, ms, vs, fat_size)?
+        probe_fat16(reader, flags, offset, ms, vs, fat_size)?
     } else if vs.vs_fat32_length != 0 {
-        probe_fat32(reader, flags, ms, vs, fat_size)?
+        probe_fat32(reader, flags, offset, ms, vs, fat_size)?
     } else {
         return Err(VFatError::InvalidVFat.into());
     };
@@ -511,7 +524,10 @@ pub fn probe_vfat<IO: BlockIo>(
     }
     info.set(FsTag::Usage(Usage::Filesystem));
     info.set(FsTag::Magic(magic.magic.to_vec()));
-    info.set(FsTag::MagicOffset(magic.b_offset));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
     info.set(FsTag::FsSize(
         u64::from(ms.ms_sector_size) * u64::from(get_sect_count(ms)),
     ));
@@ -2,20 +2,28 @@ use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned, transmut
 
 use crate::{
     error::Error,
-    filesystem::{FsInfo, FsTag},
+    filesystem::{FsInfo, FsTag, FsType},
     io::{BlockIo, Reader},
-    probe::{Endianness, Magic, ProbeFlags},
+    probe::{Endianness, Magic, ProbeFlags, Usage},
     std::fmt,
 };
 
 #[derive(Debug, Clone)]
-pub enum VxfsError {}
+pub enum VxfsError {
+    UnsupportedVersion(u32),
+    InvalidBlockSize(u32),
+    InvalidSize,
+}
 
-// impl fmt::Display for VxfsError {
-//     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-//         match self {}
-//     }
-// }
+impl fmt::Display for VxfsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VxfsError::UnsupportedVersion(v) => write!(f, "Unsupported VxFS version: {v}"),
+            VxfsError::InvalidBlockSize(b) => write!(f, "Invalid block size: {b}"),
+            VxfsError::InvalidSize => write!(f, "Invalid filesystem size"),
+        }
+    }
+}
 
 impl<E: fmt::Debug> From<VxfsError> for Error<E> {
     fn from(e: VxfsError) -> Self {
@@ -51,6 +59,13 @@ struct VxfsSuperBlock {
     vs_dsize: [u8; 4],
 }
 
+impl VxfsSuperBlock {
+    /// Oldest layout version with this superblock format.
+    const MIN_VERSION: u32 = 2;
+    const MIN_BSIZE: u32 = 1024;
+    const MAX_BSIZE: u32 = 8192;
+}
+
 const LITTLE_ENDIAN_MAGIC: &[u8; 4] = b"\xf5\xfc\x01\xa5";
 const BIG_ENDIAN_MAGIC: &[u8; 4] = b"\xa5\x01\xfc\xf5";
 
@@ -60,37 +75,51 @@ pub fn probe_vxfs<IO: BlockIo>(
     offset: u64,
     magic: Magic,
 ) -> Result<FsInfo, Error<IO::Error>> {
-    let buf: [u8; size_of::<VxfsSuperBlock>()] = reader.read_exact_at(offset)?;
+    let buf: [u8; size_of::<VxfsSuperBlock>()] = reader.read_exact_at(offset + magic.b_offset)?;
 
     let xvfs: &VxfsSuperBlock = transmute_ref!(&buf);
 
+    let (endianness, to_cpu): (Endianness, fn([u8; 4]) -> u32) =
+        if magic.magic == LITTLE_ENDIAN_MAGIC {
+            (Endianness::Little, u32::from_le_bytes)
+        } else {
+            (Endianness::Big, u32::from_be_bytes)
+        };
+
+    let version = to_cpu(xvfs.vs_version);
+    let bsize = to_cpu(xvfs.vs_bsize);
+    let size = to_cpu(xvfs.vs_size);
+    let dsize = to_cpu(xvfs.vs_dsize);
+
+    if version < VxfsSuperBlock::MIN_VERSION {
+        return Err(VxfsError::UnsupportedVersion(version).into());
+    }
+
+    if !bsize.is_power_of_two()
+        || !(VxfsSuperBlock::MIN_BSIZE..=VxfsSuperBlock::MAX_BSIZE).contains(&bsize)
+    {
+        return Err(VxfsError::InvalidBlockSize(bsize).into());
+    }
+
+    if size == 0 || dsize > size {
+        return Err(VxfsError::InvalidSize.into());
+    }
+
     let mut info = FsInfo::new();
 
-    if magic.magic == LITTLE_ENDIAN_MAGIC {
-        info.set(FsTag::Version(format!(
-            "{}",
-            u32::from_le_bytes(xvfs.vs_version)
-        )));
-        info.set(FsTag::FsBlockSize(
-            u32::from_le_bytes(xvfs.vs_bsize).into(),
-        ));
-        info.set(FsTag::BlockSize(
-            u32::from_le_bytes(xvfs.vs_bsize).into(),
-        ));
-        info.set(FsTag::Endianness(Endianness::Little));
-    } else {
-        info.set(FsTag::Version(format!(
-            "{}",
-            u32::from_be_bytes(xvfs.vs_version)
-        )));
-        info.set(FsTag::FsBlockSize(
-            u32::from_be_bytes(xvfs.vs_bsize).into(),
-        ));
-        info.set(FsTag::BlockSize(
-            u32::from_be_bytes(xvfs.vs_bsize).into(),
-        ));
-        info.set(FsTag::Endianness(Endianness::Big));
-    };
+    info.set(FsTag::FsType(FsType::Vxfs));
+    info.set(FsTag::Usage(Usage::Filesystem));
+    info.set(FsTag::Version(format!("{}", version)));
+    info.set(FsTag::Magic(magic.magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    info.set(FsTag::FsSize(u64::from(size) * u64::from(bsize)));
+    info.set(FsTag::FsLastBlock(u64::from(size)));
+    info.set(FsTag::FsBlockSize(bsize.into()));
+    info.set(FsTag::BlockSize(bsize.into()));
+    info.set(FsTag::Endianness(endianness));
 
     return Ok(info);
 }
@@ -10,7 +10,7 @@ use crate::{
     ProbeFlags, Usage,
     error::Error,
     filesystem::{FsInfo, FsTag, FsType, FsId},
-    io::{BlockIo, Reader},
+    io::{BlockIo, Reader, SeekFrom},
     probe::Magic,
     std::{fmt, mem::offset_of, str::Utf8Error},
     util::{decode_utf8_from, decode_utf8_lossy_from},
@@ -239,11 +239,98 @@ pub fn probe_xfs<IO: BlockIo>(
     }
     info.set(FsTag::Usage(Usage::Filesystem));
     info.set(FsTag::Magic(magic.magic.to_vec()));
-    info.set(FsTag::MagicOffset(magic.b_offset));
+    info.set(FsTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
     info.set(FsTag::FsSize(sb.fssize()));
     info.set(FsTag::FsLastBlock(sb.dblocks.get()));
     info.set(FsTag::FsBlockSize(u64::from(sb.blocksize)));
     info.set(FsTag::BlockSize(u64::from(sb.sectsize)));
+    info.set(FsTag::ExternalLog(sb.logstart.get() == 0));
 
     return Ok(info);
 }
+
+pub const XFS_LOG_MINSZ: Option<u64> = None;
+pub const XFS_LOG_MAGICS: Option<&'static [Magic]> = None;
+
+/// Header of a log record, only the fields checked are named.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct XlogRecHeader {
+    magicno: U32<BigEndian>,
+    cycle: U32<BigEndian>,
+    version: U32<BigEndian>,
+    len: U32<BigEndian>,
+    dummy: [U32<BigEndian>; 71],
+    fmt: U32<BigEndian>,
+    fs_uuid: [u8; 16],
+}
+
+impl XlogRecHeader {
+    const MAGIC: [u8; 4] = [0xFE, 0xED, 0xBA, 0xBE];
+    const VERSION_OKBITS: u32 = 0x1 | 0x2;
+    const FMT_LINUX_LE: u32 = 1;
+
+    /// Records are looked for in each sector of the first 256 KiB.
+    const SECTOR_SIZE: usize = 512;
+    const SEARCH_SIZE: u64 = 256 * 1024;
+
+    fn is_valid(&self) -> bool {
+        let version = self.version.get();
+
+        return self.magicno.to_bytes() == XlogRecHeader::MAGIC
+            && version != 0
+            && version & !XlogRecHeader::VERSION_OKBITS == 0
+            && self.len.get() != 0
+            && self.len.get() <= i32::MAX as u32
+            && self.fmt.get() == XlogRecHeader::FMT_LINUX_LE;
+    }
+}
+
+/// Probes for an XFS log kept on a separate device, which has no superblock
+/// of its own, by looking for a valid log record header.
+pub fn probe_xfs_log<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    _: ProbeFlags,
+    offset: u64,
+    _: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let end = reader.seek(SeekFrom::End(0))?;
+    let len = end.saturating_sub(offset).min(XlogRecHeader::SEARCH_SIZE) as usize;
+
+    let buf = reader.read_vec_at(offset, len)?;
+
+    for (i, sector) in buf.chunks_exact(XlogRecHeader::SECTOR_SIZE).enumerate() {
+        // A data device, possibly shifted by some sectors.
+        if sector.starts_with(b"XFSB") {
+            break;
+        }
+
+        let Ok((hdr, _)) = XlogRecHeader::ref_from_prefix(sector) else {
+            continue;
+        };
+
+        if !hdr.is_valid() {
+            continue;
+        }
+
+        let mag_offset = (i * XlogRecHeader::SECTOR_SIZE) as u64;
+
+        let mut info = FsInfo::new();
+
+        info.set(FsTag::FsType(FsType::XfsExternalLog));
+        info.set(FsTag::ExtLogId(Uuid::from_bytes(hdr.fs_uuid)));
+        info.set(FsTag::Usage(Usage::Other));
+        info.set(FsTag::Magic(XlogRecHeader::MAGIC.to_vec()));
+        info.set(FsTag::MagicOffset {
+            relative: mag_offset,
+            absolute: offset + mag_offset,
+        });
+
+        return Ok(info);
+    }
+
+    return Err(Error::UnableToLocateMagicSignature);
+}
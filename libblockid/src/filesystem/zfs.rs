@@ -0,0 +1,212 @@
+use fat_volume_id::id64::VolumeId64;
+
+use crate::{
+    error::Error,
+    filesystem::{FsId, FsInfo, FsTag, FsType},
+    io::{BlockIo, Reader, SeekFrom},
+    nvlist::{NvList, NvListError},
+    probe::{Endianness, Magic, ProbeFlags, Usage},
+    std::{fmt, str::Utf8Error},
+    util::{decode_utf8_from, decode_utf8_lossy_from},
+};
+
+/*
+ * https://github.com/openzfs/zfs/blob/master/include/sys/vdev_impl.h
+ * https://github.com/openzfs/zfs/blob/master/include/sys/uberblock_impl.h
+ */
+
+#[derive(Debug, Clone)]
+pub enum ZfsError {
+    Utf8Error(Utf8Error),
+    NvListError(NvListError),
+    MissingUberblock,
+    MissingVdevGuid,
+    UnsupportedVersion(u64),
+}
+
+impl fmt::Display for ZfsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ZfsError::Utf8Error(e) => write!(f, "Pool name contains invalid UTF-8: {e}"),
+            ZfsError::NvListError(e) => write!(f, "Invalid vdev label: {e}"),
+            ZfsError::MissingUberblock => write!(f, "Vdev label has no uberblock"),
+            ZfsError::MissingVdevGuid => write!(f, "Vdev label has no vdev GUID"),
+            ZfsError::UnsupportedVersion(v) => write!(f, "Unsupported pool version: {v}"),
+        }
+    }
+}
+
+impl From<NvListError> for ZfsError {
+    fn from(e: NvListError) -> Self {
+        ZfsError::NvListError(e)
+    }
+}
+
+impl<E: fmt::Debug> From<ZfsError> for Error<E> {
+    fn from(e: ZfsError) -> Self {
+        Error::Zfs(e)
+    }
+}
+
+/// Size of each of the four vdev labels, two at the start and two at the
+/// end of the device.
+const LABEL_SIZE: u64 = 256 * 1024;
+const LABEL_COUNT: u64 = 4;
+
+const NVLIST_OFFSET: u64 = 16 * 1024;
+const NVLIST_SIZE: usize = 112 * 1024;
+
+const UBERBLOCK_RING_OFFSET: u64 = 128 * 1024;
+const UBERBLOCK_RING_SIZE: usize = 128 * 1024;
+/// Smallest uberblock slot, larger sector sizes use fewer, larger slots
+/// which still start on a multiple of this.
+const UBERBLOCK_SLOT_SIZE: usize = 1024;
+const UBERBLOCK_MAGIC: u64 = 0x00bab10c;
+
+/// Pool version at which numbered versions were replaced by feature flags.
+const SPA_VERSION_FEATURES: u64 = 5000;
+
+pub const ZFS_MINSZ: Option<u64> = Some(LABEL_SIZE * LABEL_COUNT);
+pub const ZFS_MAGICS: Option<&'static [Magic]> = None;
+
+/// Looks for an uberblock in the ring of a label, written in the byte order
+/// of the machine that last wrote the pool, returning its offset within the
+/// ring.
+fn find_uberblock(ring: &[u8]) -> Option<(usize, Endianness)> {
+    for (i, slot) in ring.chunks_exact(UBERBLOCK_SLOT_SIZE).enumerate() {
+        let magic = slot[..8].try_into().ok()?;
+        let version = slot[8..16].try_into().ok()?;
+
+        let endianness = if u64::from_le_bytes(magic) == UBERBLOCK_MAGIC {
+            Endianness::Little
+        } else if u64::from_be_bytes(magic) == UBERBLOCK_MAGIC {
+            Endianness::Big
+        } else {
+            continue;
+        };
+
+        let version = match endianness {
+            Endianness::Little => u64::from_le_bytes(version),
+            Endianness::Big => u64::from_be_bytes(version),
+        };
+
+        if version != 0 && version <= SPA_VERSION_FEATURES {
+            return Some((i * UBERBLOCK_SLOT_SIZE, endianness));
+        }
+    }
+
+    return None;
+}
+
+fn probe_label<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    label_offset: u64,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let ring = reader.read_vec_at(
+        offset + label_offset + UBERBLOCK_RING_OFFSET,
+        UBERBLOCK_RING_SIZE,
+    )?;
+    let (ub_offset, endianness) = find_uberblock(&ring).ok_or(ZfsError::MissingUberblock)?;
+
+    let buf = reader.read_vec_at(offset + label_offset + NVLIST_OFFSET, NVLIST_SIZE)?;
+    let label = NvList::from_xdr(&buf).map_err(ZfsError::from)?;
+
+    let version = label
+        .get_u64("version")
+        .map_err(ZfsError::from)?
+        .unwrap_or_default();
+    if version == 0 || version > SPA_VERSION_FEATURES {
+        return Err(ZfsError::UnsupportedVersion(version).into());
+    }
+
+    let vdev_guid = label
+        .get_u64("guid")
+        .map_err(ZfsError::from)?
+        .ok_or(ZfsError::MissingVdevGuid)?;
+
+    // Spares and cache devices are labelled without a pool.
+    let pool_guid = label.get_u64("pool_guid").map_err(ZfsError::from)?;
+
+    let pool_name = match label.get_str("name").map_err(ZfsError::from)? {
+        Some(name) if flags.contains(ProbeFlags::FailOnInvalidUTF) => {
+            Some(decode_utf8_from(name).map_err(ZfsError::Utf8Error)?)
+        }
+        Some(name) => Some(decode_utf8_lossy_from(name)),
+        None => None,
+    };
+
+    let ashift = label
+        .get_nvlist("vdev_tree")
+        .map_err(ZfsError::from)?
+        .map(|tree| tree.get_u64("ashift"))
+        .transpose()
+        .map_err(ZfsError::from)?
+        .flatten();
+
+    let mag_offset = label_offset + UBERBLOCK_RING_OFFSET + ub_offset as u64;
+    let magic = match endianness {
+        Endianness::Little => UBERBLOCK_MAGIC.to_le_bytes(),
+        Endianness::Big => UBERBLOCK_MAGIC.to_be_bytes(),
+    };
+
+    let mut info = FsInfo::new();
+
+    info.set(FsTag::FsType(FsType::Zfs));
+    if let Some(name) = pool_name {
+        info.set(FsTag::Label(name));
+    }
+    if let Some(guid) = pool_guid {
+        info.set(FsTag::FsId(FsId::VolumeId64(VolumeId64::from_bytes(
+            guid.to_le_bytes(),
+        ))));
+    }
+    info.set(FsTag::SubMemberId(FsId::VolumeId64(
+        VolumeId64::from_bytes(vdev_guid.to_le_bytes()),
+    )));
+    info.set(FsTag::Usage(Usage::Raid));
+    info.set(FsTag::Version(version.to_string()));
+    info.set(FsTag::Magic(magic.to_vec()));
+    info.set(FsTag::MagicOffset {
+        relative: mag_offset,
+        absolute: offset + mag_offset,
+    });
+    if let Some(ashift) = ashift.filter(|a| (9..=16).contains(a)) {
+        info.set(FsTag::FsBlockSize(1 << ashift));
+    }
+    info.set(FsTag::Endianness(endianness));
+
+    return Ok(info);
+}
+
+/// Probes for a member of a ZFS pool by looking through its four vdev
+/// labels, any of which may have been overwritten, for the first valid one.
+pub fn probe_zfs<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    _: Magic,
+) -> Result<FsInfo, Error<IO::Error>> {
+    let end = reader.seek(SeekFrom::End(0))?;
+    // Labels at the end are placed from the size rounded down to a whole
+    // label.
+    let size = end.saturating_sub(offset) / LABEL_SIZE * LABEL_SIZE;
+
+    if size < LABEL_SIZE * LABEL_COUNT {
+        return Err(Error::UnableToLocateMagicSignature);
+    }
+
+    let labels = [0, LABEL_SIZE, size - 2 * LABEL_SIZE, size - LABEL_SIZE];
+    let mut last_err = Error::UnableToLocateMagicSignature;
+
+    for label_offset in labels {
+        match probe_label(reader, flags, offset, label_offset) {
+            Ok(info) => return Ok(info),
+            Err(Error::Io(e)) => return Err(Error::Io(e)),
+            Err(e) => last_err = e,
+        }
+    }
+
+    return Err(last_err);
+}
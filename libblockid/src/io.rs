@@ -26,34 +26,69 @@ pub trait BlockIo: crate::io::block::Io {}
 pub trait BlockIo: crate::io::ioctl::Ioctl {}
 
 /// Reader type used to expose functions provided by [`BlockIo`]
+///
+/// The reader can be bounded to an end offset, in which case the device
+/// appears to end there, used when probing inside a partition.
 #[derive(Debug)]
-pub struct Reader<IO: BlockIo>(IO);
+pub struct Reader<IO: BlockIo> {
+    io: IO,
+    end: Option<u64>,
+}
 
 #[allow(dead_code)]
 impl<IO: BlockIo> Reader<IO> {
     pub const fn new(reader: IO) -> Self {
-        Self(reader)
+        Self {
+            io: reader,
+            end: None,
+        }
+    }
+
+    /// Bounds the reader to end at `end` bytes from the start of the device,
+    /// returning the previous bound.
+    pub(crate) fn set_end(&mut self, end: Option<u64>) -> Option<u64> {
+        crate::std::mem::replace(&mut self.end, end)
+    }
+
+    /// Returns the underlying device.
+    pub(crate) fn io(&self) -> &IO {
+        &self.io
+    }
+
+    fn check_bounds(&self, offset: u64, len: usize) -> Result<(), Error<IO::Error>> {
+        if let Some(end) = self.end
+            && offset.saturating_add(len as u64) > end
+        {
+            return Err(Error::OffsetExceedsDeviceSize);
+        }
+
+        Ok(())
     }
 
     #[inline]
     pub fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error<IO::Error>> {
-        self.0.read(buf)
+        self.io.read(buf)
     }
 
     pub fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> Result<(), Error<IO::Error>> {
-        self.0.seek(SeekFrom::Start(offset))?;
-        self.0.read_exact(buf)?;
+        self.check_bounds(offset, buf.len())?;
+        self.io.seek(SeekFrom::Start(offset))?;
+        self.io.read_exact(buf)?;
         Ok(())
     }
 
     #[inline]
     pub fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Error<IO::Error>> {
-        self.0.read_exact(buf)
+        self.io.read_exact(buf)
     }
 
-    #[inline]
     pub fn seek(&mut self, pos: SeekFrom) -> Result<u64, Error<IO::Error>> {
-        self.0.seek(pos)
+        match (pos, self.end) {
+            (SeekFrom::End(n), Some(end)) => {
+                self.io.seek(SeekFrom::Start(end.saturating_add_signed(n)))
+            }
+            _ => self.io.seek(pos),
+        }
     }
 
     pub fn read_exact_at<const S: usize>(
@@ -61,15 +96,13 @@ impl<IO: BlockIo> Reader<IO> {
         offset: u64,
     ) -> Result<[u8; S], Error<IO::Error>> {
         let mut buf = [0u8; S];
-        self.0.seek(SeekFrom::Start(offset))?;
-        self.0.read_exact(&mut buf)?;
+        self.read_at(offset, &mut buf)?;
         Ok(buf)
     }
 
     pub fn read_vec_at(&mut self, offset: u64, size: usize) -> Result<Vec<u8>, Error<IO::Error>> {
         let mut buf = vec![0u8; size];
-        self.0.seek(SeekFrom::Start(offset))?;
-        self.0.read_exact(&mut buf)?;
+        self.read_at(offset, &mut buf)?;
         Ok(buf)
     }
 
@@ -90,11 +123,17 @@ impl<IO: BlockIo> Reader<IO> {
 
     /// Searches through list of provided magics checking if they exist,
     /// returning the first found magic.
+    ///
+    /// Each [`Magic::b_offset`] is relative to `offset`, the position on the
+    /// device the probe starts at. Magics that would lie past the end of the
+    /// device are skipped.
     pub fn get_magic(
         &mut self,
+        offset: u64,
         magics: &'static [Magic],
     ) -> Result<Option<Magic>, Error<IO::Error>> {
         let mut buf = [0u8; 16];
+        let end = self.seek(SeekFrom::End(0))?;
 
         for mag in magics {
             debug_assert!(
@@ -102,9 +141,16 @@ impl<IO: BlockIo> Reader<IO> {
                 "Magic should not be greater then `buf`"
             );
 
-            self.read_at(mag.b_offset, &mut buf)?;
+            let buf = &mut buf[..mag.magic.len()];
+            let mag_offset = offset + mag.b_offset;
 
-            if &buf[..mag.magic.len()] == mag.magic {
+            if mag_offset + buf.len() as u64 > end {
+                continue;
+            }
+
+            self.read_at(mag_offset, buf)?;
+
+            if buf == mag.magic {
                 return Ok(Some(*mag));
             }
         }
@@ -115,36 +161,38 @@ impl<IO: BlockIo> Reader<IO> {
     #[cfg(feature = "os_calls")]
     #[inline]
     pub fn device_size(&self) -> Result<u64, Error<IO::Error>> {
-        self.0.device_size()
+        let size = self.io.device_size()?;
+
+        return Ok(self.end.map_or(size, |end| end.min(size)));
     }
 
     #[cfg(feature = "os_calls")]
     #[inline]
     pub fn logical_sector_size(&self) -> Result<u64, Error<IO::Error>> {
-        self.0.logical_sector_size()
+        self.io.logical_sector_size()
     }
 
     #[cfg(feature = "os_calls")]
     #[inline]
     pub fn physical_sector_size(&self) -> Result<u64, Error<IO::Error>> {
-        self.0.physical_sector_size()
+        self.io.physical_sector_size()
     }
 
     #[cfg(all(feature = "os_calls", any(target_os = "linux", target_os = "freebsd")))]
     #[inline]
     pub fn minimum_io_size(&self) -> Result<u64, Error<IO::Error>> {
-        self.0.minimum_io_size()
+        self.io.minimum_io_size()
     }
 
     #[cfg(all(feature = "os_calls", target_os = "linux"))]
     #[inline]
     pub fn optimal_io_size(&self) -> Result<u64, Error<IO::Error>> {
-        self.0.optimal_io_size()
+        self.io.optimal_io_size()
     }
 
     #[cfg(all(feature = "os_calls", any(target_os = "linux", target_os = "freebsd")))]
     #[inline]
     pub fn alignment_offset(&self) -> Result<crate::io::ioctl::AlignmentOffset, Error<IO::Error>> {
-        self.0.alignment_offset()
+        self.io.alignment_offset()
     }
 }
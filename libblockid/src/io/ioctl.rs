@@ -52,8 +52,27 @@ pub trait Ioctl: Io {
     fn alignment_offset(&self) -> Result<crate::io::ioctl::AlignmentOffset, Error<Self::Error>>;
 }
 
+/// Sector size assumed for disk images, which have no block device ioctls.
+const IMAGE_SECTOR_SIZE: u64 = 512;
+
+/// Returns the size of `file` if it is a regular file (a disk image) rather
+/// than a block device.
+fn image_size(file: &File) -> Result<Option<u64>, Error<<File as Io>::Error>> {
+    let stat = rustix::fs::fstat(file)?;
+
+    if rustix::fs::FileType::from_raw_mode(stat.st_mode) == rustix::fs::FileType::RegularFile {
+        return Ok(Some(stat.st_size as u64));
+    }
+
+    return Ok(None);
+}
+
 impl Ioctl for File {
     fn device_size(&self) -> Result<u64, Error<Self::Error>> {
+        if let Some(size) = image_size(self)? {
+            return Ok(size);
+        }
+
         #[cfg(target_os = "freebsd")]
         todo!();
 
@@ -72,6 +91,10 @@ impl Ioctl for File {
     }
 
     fn logical_sector_size(&self) -> Result<u64, Error<Self::Error>> {
+        if image_size(self)?.is_some() {
+            return Ok(IMAGE_SECTOR_SIZE);
+        }
+
         #[cfg(target_os = "freebsd")]
         todo!();
 
@@ -89,6 +112,10 @@ impl Ioctl for File {
     }
 
     fn physical_sector_size(&self) -> Result<u64, Error<Self::Error>> {
+        if image_size(self)?.is_some() {
+            return Ok(IMAGE_SECTOR_SIZE);
+        }
+
         #[cfg(target_os = "freebsd")]
         todo!();
 
@@ -12,17 +12,27 @@ extern crate alloc;
 pub mod error;
 pub mod filesystem;
 mod io;
+mod nvlist;
 pub mod partition;
 mod probe;
 mod util;
+#[cfg(feature = "os_calls")]
+mod wipe;
 
 #[cfg(feature = "no_std")]
 #[cfg_attr(docsrs, doc(cfg(feature = "no_std")))]
 pub use crate::io::no_std::path::{Path, PathBuf};
-pub use crate::probe::{Endianness, ProbeFlags, Usage};
+pub use crate::probe::{
+    Endianness, PartitionTree, ProbeAttempt, ProbeFlags, ProbeOutcome, ProbeReport, ProbeTree,
+    Signature, SignatureType, Signatures, Usage,
+};
 #[cfg(feature = "os_calls")]
 #[cfg_attr(docsrs, doc(cfg(feature = "os_calls")))]
-pub use crate::{io::ioctl::AlignmentOffset, util::fd_to_path};
+pub use crate::{
+    io::ioctl::AlignmentOffset,
+    util::fd_to_path,
+    wipe::{WipedRegion, Wiper},
+};
 
 #[cfg(feature = "os_calls")]
 pub type Probe = crate::probe::Probe<crate::io::File>;
@@ -0,0 +1,211 @@
+use crate::std::fmt;
+
+/*
+ * https://github.com/openzfs/zfs/blob/master/module/nvpair/nvpair.c
+ */
+
+#[derive(Debug, Clone)]
+pub enum NvListError {
+    UnsupportedEncoding(u8),
+    Truncated,
+    InvalidPairSize(u32),
+    InvalidValue(&'static str),
+}
+
+impl fmt::Display for NvListError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NvListError::UnsupportedEncoding(e) => write!(f, "Unsupported nvlist encoding: {e}"),
+            NvListError::Truncated => write!(f, "Nvlist runs past the end of its buffer"),
+            NvListError::InvalidPairSize(s) => write!(f, "Invalid nvpair size: {s}"),
+            NvListError::InvalidValue(name) => {
+                write!(f, "Nvpair \"{name}\" has an unexpected type")
+            }
+        }
+    }
+}
+
+/// Value of a pair, only the types needed to identify devices are decoded.
+#[derive(Debug, Clone, Copy)]
+pub enum NvValue<'a> {
+    Uint64(u64),
+    String(&'a [u8]),
+    NvList(NvList<'a>),
+    Other,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct NvPair<'a> {
+    pub name: &'a [u8],
+    pub value: NvValue<'a>,
+}
+
+/// XDR encoded name-value list, as written by `nvlist_pack`.
+///
+/// Everything is read from the borrowed buffer on demand, every length and
+/// offset is checked against it so a corrupt list fails to decode instead
+/// of panicking.
+#[derive(Debug, Clone, Copy)]
+pub struct NvList<'a> {
+    pairs: &'a [u8],
+}
+
+impl<'a> NvList<'a> {
+    const ENCODE_XDR: u8 = 1;
+    const HEADER_SIZE: usize = 4;
+    /// Version and flags words before the first pair.
+    const LIST_SIZE: usize = 8;
+
+    const TYPE_UINT64: u32 = 8;
+    const TYPE_STRING: u32 = 9;
+    const TYPE_NVLIST: u32 = 19;
+
+    /// Decodes a packed list, starting with the encoding header.
+    pub fn from_xdr(buf: &'a [u8]) -> Result<NvList<'a>, NvListError> {
+        let header = buf
+            .get(..NvList::HEADER_SIZE)
+            .ok_or(NvListError::Truncated)?;
+
+        if header[0] != NvList::ENCODE_XDR {
+            return Err(NvListError::UnsupportedEncoding(header[0]));
+        }
+
+        return NvList::embedded(&buf[NvList::HEADER_SIZE..]);
+    }
+
+    /// Decodes a list without the encoding header, as nested lists are
+    /// stored.
+    fn embedded(buf: &'a [u8]) -> Result<NvList<'a>, NvListError> {
+        let pairs = buf.get(NvList::LIST_SIZE..).ok_or(NvListError::Truncated)?;
+
+        return Ok(NvList { pairs });
+    }
+
+    pub fn iter(&self) -> NvPairs<'a> {
+        return NvPairs {
+            rest: self.pairs,
+            done: false,
+        };
+    }
+
+    /// Returns the first pair called `name`.
+    pub fn get(&self, name: &[u8]) -> Result<Option<NvValue<'a>>, NvListError> {
+        for pair in self.iter() {
+            let pair = pair?;
+
+            if pair.name == name {
+                return Ok(Some(pair.value));
+            }
+        }
+
+        return Ok(None);
+    }
+
+    pub fn get_u64(&self, name: &'static str) -> Result<Option<u64>, NvListError> {
+        match self.get(name.as_bytes())? {
+            Some(NvValue::Uint64(v)) => Ok(Some(v)),
+            Some(_) => Err(NvListError::InvalidValue(name)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_str(&self, name: &'static str) -> Result<Option<&'a [u8]>, NvListError> {
+        match self.get(name.as_bytes())? {
+            Some(NvValue::String(v)) => Ok(Some(v)),
+            Some(_) => Err(NvListError::InvalidValue(name)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_nvlist(&self, name: &'static str) -> Result<Option<NvList<'a>>, NvListError> {
+        match self.get(name.as_bytes())? {
+            Some(NvValue::NvList(v)) => Ok(Some(v)),
+            Some(_) => Err(NvListError::InvalidValue(name)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Iterator over the pairs of an [`NvList`], stops after the first error.
+#[derive(Debug, Clone)]
+pub struct NvPairs<'a> {
+    rest: &'a [u8],
+    done: bool,
+}
+
+impl<'a> NvPairs<'a> {
+    fn next_pair(&mut self) -> Result<Option<NvPair<'a>>, NvListError> {
+        let encode_size = read_u32(self.rest, 0)?;
+        let decode_size = read_u32(self.rest, 4)?;
+
+        // The list ends with a pair of zero sizes.
+        if encode_size == 0 && decode_size == 0 {
+            return Ok(None);
+        }
+
+        let pair_size = encode_size as usize;
+        if pair_size < 8 || !pair_size.is_multiple_of(4) {
+            return Err(NvListError::InvalidPairSize(encode_size));
+        }
+
+        let pair = self.rest.get(..pair_size).ok_or(NvListError::Truncated)?;
+        self.rest = &self.rest[pair_size..];
+
+        let (name, at) = read_bytes(pair, 8)?;
+        let value_type = read_u32(pair, at)?;
+        // The element count is not needed for the types decoded.
+        let at = at + 8;
+
+        let value = match value_type {
+            NvList::TYPE_UINT64 => NvValue::Uint64(read_u64(pair, at)?),
+            NvList::TYPE_STRING => NvValue::String(read_bytes(pair, at)?.0),
+            NvList::TYPE_NVLIST => NvValue::NvList(NvList::embedded(
+                pair.get(at..).ok_or(NvListError::Truncated)?,
+            )?),
+            _ => NvValue::Other,
+        };
+
+        return Ok(Some(NvPair { name, value }));
+    }
+}
+
+impl<'a> Iterator for NvPairs<'a> {
+    type Item = Result<NvPair<'a>, NvListError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let pair = self.next_pair().transpose();
+        if !matches!(pair, Some(Ok(_))) {
+            self.done = true;
+        }
+
+        return pair;
+    }
+}
+
+fn read_u32(buf: &[u8], at: usize) -> Result<u32, NvListError> {
+    let bytes = buf.get(at..at + 4).ok_or(NvListError::Truncated)?;
+
+    return Ok(u32::from_be_bytes(bytes.try_into().unwrap_or_default()));
+}
+
+fn read_u64(buf: &[u8], at: usize) -> Result<u64, NvListError> {
+    let bytes = buf.get(at..at + 8).ok_or(NvListError::Truncated)?;
+
+    return Ok(u64::from_be_bytes(bytes.try_into().unwrap_or_default()));
+}
+
+/// Reads a length prefixed byte string, returning it along with the offset
+/// of what follows its padding.
+fn read_bytes(buf: &[u8], at: usize) -> Result<(&[u8], usize), NvListError> {
+    let len = read_u32(buf, at)? as usize;
+    let start = at + 4;
+    let bytes = buf
+        .get(start..start.checked_add(len).ok_or(NvListError::Truncated)?)
+        .ok_or(NvListError::Truncated)?;
+
+    return Ok((bytes, start + len.next_multiple_of(4)));
+}
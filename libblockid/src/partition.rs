@@ -11,6 +11,7 @@ use crate::{
     io::{BlockIo, Reader},
     partition::{
         aix::{AIX_MAGICS, AIX_MINSZ, probe_aix},
+        bsd::{BSD_MAGICS, BSD_MINSZ, BsdPartitionType, probe_bsd},
         gpt::{GPT_MAGICS, GPT_MINSZ, probe_gpt},
         mbr::{MBR_MAGICS, MBR_MINSZ, MbrPartitionType, probe_mbr},
     },
@@ -22,6 +23,9 @@ use crate::{
 #[rustfmt::skip]
 pub const PT_DETECT_ORDER: &[(PtFilter, PtType)] = &[
     (PtFilter::SKIP_GPT, PtType::Gpt),
+    // The boot block in front of a disklabel carries an MBR signature, and
+    // inside a slice often a copy of the MBR partitions too.
+    (PtFilter::SKIP_BSD, PtType::Bsd),
     (PtFilter::SKIP_MBR, PtType::Mbr),
 ];
 
@@ -54,6 +58,8 @@ pub enum PtType {
     Mbr,
     /// [GUID Partition Table](https://en.wikipedia.org/wiki/GUID_Partition_Table).
     Gpt,
+    /// [BSD disklabel](https://en.wikipedia.org/wiki/BSD_disklabel), standalone or inside an MBR slice.
+    Bsd,
 }
 
 impl fmt::Display for PtType {
@@ -62,6 +68,7 @@ impl fmt::Display for PtType {
             PtType::Aix => write!(f, "aix"),
             PtType::Mbr => write!(f, "mbr"),
             PtType::Gpt => write!(f, "gpt"),
+            PtType::Bsd => write!(f, "bsd"),
         }
     }
 }
@@ -84,6 +91,11 @@ impl PtType {
                 magics: GPT_MAGICS,
                 probe: probe_gpt,
             },
+            PtType::Bsd => PtHandler {
+                minsz: BSD_MINSZ,
+                magics: BSD_MAGICS,
+                probe: probe_bsd,
+            },
         }
     }
 }
@@ -134,6 +146,8 @@ pub enum PartitionType {
     Mbr(MbrPartitionType),
     /// [Partition types GUIDs](https://en.wikipedia.org/wiki/GUID_Partition_Table#Partition_type_GUIDs) used in GPT partition table.
     Uuid(Uuid),
+    /// Filesystem types used in BSD disklabels.
+    Bsd(BsdPartitionType),
     /// Used for MAC partition table.
     String(String),
 }
@@ -146,6 +160,9 @@ pub enum PartitionId {
     Uuid(Uuid),
     /// A pseudo partition identifier used for MBR partition table.
     Mbr { disk: u32, part_no: u8 },
+    /// Letter a BSD disklabel partition is known by, as BSD disklabels have
+    /// no identifiers.
+    Bsd { letter: char },
 }
 
 impl PartitionId {
@@ -164,6 +181,13 @@ impl PartitionId {
             _ => None,
         }
     }
+
+    pub fn as_bsd(&self) -> Option<char> {
+        match self {
+            PartitionId::Bsd { letter } => Some(*letter),
+            _ => None,
+        }
+    }
 }
 
 /// The partition attributes of a specified partition table.
@@ -174,15 +198,17 @@ pub enum PartitionAttributes {
     Mbr(u8),
     /// Used in GPT partition tables.
     Gpt(u64),
+    /// Partition tables without attributes, such as BSD disklabels.
+    None,
 }
 
 /// Parsed partition infomation.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Partition {
-    /// Start of partition in bytes.
+    /// Start of partition in bytes, relative to the probe offset.
     pub start: u64,
-    /// End of partition in bytes.
+    /// End of partition in bytes (exclusive), relative to the probe offset.
     pub end: u64,
     /// The partition identifier of a specified partition table.
     pub partition_id: PartitionId,
@@ -196,6 +222,22 @@ pub struct Partition {
     pub attributes: PartitionAttributes,
 }
 
+impl Partition {
+    /// Size of partition in bytes.
+    pub fn size(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Returns `true` if the partition only holds other partitions, such as
+    /// an MBR extended partition, rather than data.
+    pub fn is_container(&self) -> bool {
+        match self.partition_type {
+            PartitionType::Mbr(t) => t.is_extended(),
+            _ => false,
+        }
+    }
+}
+
 #[non_exhaustive]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
@@ -209,8 +251,9 @@ pub enum PtTag {
     PTSize(u64),
     /// Partition table magic signature.
     Magic(Vec<u8>),
-    /// Partition table magic signature offset.
-    MagicOffset(u64),
+    /// Partition table magic signature offset, both relative to the probe
+    /// offset and absolute from the start of the device.
+    MagicOffset { relative: u64, absolute: u64 },
     /// List of partitions in the partition table.
     Partitions(Vec<Partition>),
 }
@@ -268,7 +311,14 @@ impl PtInfo {
 
     pub fn magic_offset(&self) -> Option<u64> {
         self.tags.iter().find_map(|t| match t {
-            PtTag::MagicOffset(t) => Some(*t),
+            PtTag::MagicOffset { relative, .. } => Some(*relative),
+            _ => None,
+        })
+    }
+
+    pub fn magic_absolute_offset(&self) -> Option<u64> {
+        self.tags.iter().find_map(|t| match t {
+            PtTag::MagicOffset { absolute, .. } => Some(*absolute),
             _ => None,
         })
     }
@@ -299,7 +349,7 @@ impl serde::Serialize for PtInfo {
                 PtTag::PtId(id) => match id {
                     PtId::Uuid(uuid) => map.serialize_entry("PT_ID", uuid)?,
                     PtId::Mbr { disk } => {
-                        map.serialize_entry("PT_ID", &format!("{:x}", disk))?;
+                        map.serialize_entry("PT_ID", &format!("{:08x}", disk))?;
                     }
                 },
                 PtTag::PTSize(sz) => {
@@ -308,8 +358,9 @@ impl serde::Serialize for PtInfo {
                 PtTag::Magic(mag) => {
                     map.serialize_entry("MAGIC", mag)?;
                 }
-                PtTag::MagicOffset(off) => {
-                    map.serialize_entry("MAGIC_OFFSET", off)?;
+                PtTag::MagicOffset { relative, absolute } => {
+                    map.serialize_entry("MAGIC_OFFSET", relative)?;
+                    map.serialize_entry("MAGIC_ABSOLUTE_OFFSET", absolute)?;
                 }
                 PtTag::Partitions(parts) => {
                     for part in parts {
@@ -322,9 +373,12 @@ impl serde::Serialize for PtInfo {
                             PartitionId::Mbr { disk, part_no } => {
                                 map.serialize_entry(
                                     &format!("PART{}_ID", part.part_no),
-                                    &format!("{:#x}{:x}", disk, part_no),
+                                    &format!("{:08x}-{:02x}", disk, part_no),
                                 )?;
                             }
+                            PartitionId::Bsd { letter } => {
+                                map.serialize_entry(&format!("PART{}_ID", part.part_no), letter)?;
+                            }
                         }
                         match &part.partition_type {
                             PartitionType::Mbr(byte) => {
@@ -333,6 +387,9 @@ impl serde::Serialize for PtInfo {
                             PartitionType::Uuid(uuid) => {
                                 map.serialize_entry(&format!("PART{}_TYPE", part.part_no), uuid)?;
                             }
+                            PartitionType::Bsd(byte) => {
+                                map.serialize_entry(&format!("PART{}_TYPE", part.part_no), byte)?;
+                            }
                             PartitionType::String(str) => {
                                 map.serialize_entry(&format!("PART{}_TYPE", part.part_no), str)?;
                             }
@@ -353,6 +410,7 @@ impl serde::Serialize for PtInfo {
                                     attr,
                                 )?;
                             }
+                            PartitionAttributes::None => {}
                         }
                     }
                 }
@@ -370,5 +428,6 @@ bitflags! {
         const SKIP_AIX = 1 << 0;
         const SKIP_MBR = 1 << 1;
         const SKIP_GPT = 1 << 2;
+        const SKIP_BSD = 1 << 3;
     }
 }
@@ -25,14 +25,17 @@ pub const AIX_MAGICS: Option<&'static [Magic]> = Some(&[Magic {
 pub fn probe_aix<IO: BlockIo>(
     _: &mut Reader<IO>,
     _: ProbeFlags,
-    _: u64,
+    offset: u64,
     _: Magic,
 ) -> Result<PtInfo, Error<IO::Error>> {
     let mut info = PtInfo::new();
 
     info.set(PtTag::PtType(PtType::Aix));
     info.set(PtTag::Magic(AIX_MAGIC.to_vec()));
-    info.set(PtTag::MagicOffset(0));
+    info.set(PtTag::MagicOffset {
+        relative: 0,
+        absolute: offset,
+    });
 
     return Ok(info);
 }
@@ -0,0 +1,282 @@
+use zerocopy::{
+    FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned,
+    byteorder::{LittleEndian, U16, U32},
+    transmute_ref,
+};
+
+use crate::{
+    error::Error,
+    io::{BlockIo, Reader},
+    partition::{
+        Partition, PartitionAttributes, PartitionId, PartitionType, PtInfo, PtTag, PtType,
+    },
+    probe::{Magic, ProbeFlags},
+    std::{fmt, mem::offset_of},
+};
+
+/*
+ * https://github.com/freebsd/freebsd-src/blob/main/sys/sys/disklabel.h
+ * https://github.com/util-linux/util-linux/blob/master/libblkid/src/partitions/bsd.c
+ */
+
+#[derive(Debug, Clone)]
+pub enum BsdError {
+    MissingSecondMagic,
+    TooManyPartitions(u16),
+    InvalidChecksum,
+    InvalidSectorSize(u32),
+    Overflow,
+}
+
+impl fmt::Display for BsdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BsdError::MissingSecondMagic => write!(f, "Disklabel is missing its second magic"),
+            BsdError::TooManyPartitions(n) => write!(f, "Too many partitions in disklabel: {n}"),
+            BsdError::InvalidChecksum => write!(f, "Invalid disklabel checksum"),
+            BsdError::InvalidSectorSize(s) => write!(f, "Invalid sector size: {s}"),
+            BsdError::Overflow => write!(f, "internal calculation overflowed"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> From<BsdError> for Error<E> {
+    fn from(e: BsdError) -> Self {
+        Error::Bsd(e)
+    }
+}
+
+const BSD_MAGIC: [u8; 4] = [0x57, 0x45, 0x56, 0x82];
+
+/// Most ports keep the label in the second sector, alpha and a few others
+/// put it in the boot sector itself.
+const BSD_LABEL_OFFSET: u64 = 512;
+const BSD_ALPHA_LABEL_OFFSET: u64 = 64;
+const BSD_OTHER_LABEL_OFFSET: u64 = 128;
+
+/// Largest partition count any of the BSDs use, NetBSD on some ports.
+const BSD_MAX_PARTITIONS: usize = 22;
+/// The raw partition `c`, covering the whole slice or disk.
+const BSD_RAW_PART: usize = 2;
+
+pub const BSD_MINSZ: Option<u64> = Some(1024);
+pub const BSD_MAGICS: Option<&'static [Magic]> = Some(&[
+    Magic {
+        magic: &BSD_MAGIC,
+        b_offset: BSD_LABEL_OFFSET,
+    },
+    Magic {
+        magic: &BSD_MAGIC,
+        b_offset: BSD_ALPHA_LABEL_OFFSET,
+    },
+    Magic {
+        magic: &BSD_MAGIC,
+        b_offset: BSD_OTHER_LABEL_OFFSET,
+    },
+]);
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct BsdDiskLabel {
+    magic: [u8; 4],
+    disk_type: U16<LittleEndian>,
+    subtype: U16<LittleEndian>,
+    typename: [u8; 16],
+    packname: [u8; 16],
+    secsize: U32<LittleEndian>,
+    nsectors: U32<LittleEndian>,
+    ntracks: U32<LittleEndian>,
+    ncylinders: U32<LittleEndian>,
+    secpercyl: U32<LittleEndian>,
+    secperunit: U32<LittleEndian>,
+    sparespertrack: U16<LittleEndian>,
+    sparespercyl: U16<LittleEndian>,
+    acylinders: U32<LittleEndian>,
+    rpm: U16<LittleEndian>,
+    interleave: U16<LittleEndian>,
+    trackskew: U16<LittleEndian>,
+    cylskew: U16<LittleEndian>,
+    headswitch: U32<LittleEndian>,
+    trkseek: U32<LittleEndian>,
+    flags: U32<LittleEndian>,
+    drivedata: [U32<LittleEndian>; 5],
+    spare: [U32<LittleEndian>; 5],
+    magic2: [u8; 4],
+    checksum: U16<LittleEndian>,
+    npartitions: U16<LittleEndian>,
+    bbsize: U32<LittleEndian>,
+    sbsize: U32<LittleEndian>,
+    partitions: [BsdPartitionEntry; BSD_MAX_PARTITIONS],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, FromBytes, IntoBytes, Unaligned, Immutable, KnownLayout)]
+struct BsdPartitionEntry {
+    size: U32<LittleEndian>,
+    offset: U32<LittleEndian>,
+    fsize: U32<LittleEndian>,
+    fstype: BsdPartitionType,
+    frag: u8,
+    cpg: U16<LittleEndian>,
+}
+
+impl BsdDiskLabel {
+    /// Verifies the label, which xors to zero over its header and the
+    /// partitions in use, checksum included.
+    fn verify_csum(&self) -> Result<(), BsdError> {
+        let len = offset_of!(BsdDiskLabel, partitions)
+            + usize::from(self.npartitions.get()) * size_of::<BsdPartitionEntry>();
+
+        let csum = self.as_bytes()[..len]
+            .chunks_exact(2)
+            .fold(0u16, |csum, word| {
+                csum ^ u16::from_le_bytes([word[0], word[1]])
+            });
+
+        if csum != 0 {
+            return Err(BsdError::InvalidChecksum);
+        }
+
+        return Ok(());
+    }
+}
+
+/// Filesystem type of a partition, from `p_fstype`.
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Ord,
+    PartialOrd,
+    Hash,
+    FromBytes,
+    IntoBytes,
+    Unaligned,
+    Immutable,
+    KnownLayout,
+)]
+pub struct BsdPartitionType(u8);
+
+#[allow(dead_code)]
+impl BsdPartitionType {
+    pub const UNUSED: Self = Self(0);
+    pub const SWAP: Self = Self(1);
+    pub const V6: Self = Self(2);
+    pub const V7: Self = Self(3);
+    pub const SYSV: Self = Self(4);
+    pub const V71K: Self = Self(5);
+    pub const V8: Self = Self(6);
+    pub const BSDFFS: Self = Self(7);
+    pub const MSDOS: Self = Self(8);
+    pub const BSDLFS: Self = Self(9);
+    pub const OTHER: Self = Self(10);
+    pub const HPFS: Self = Self(11);
+    pub const ISO9660: Self = Self(12);
+    pub const BOOT: Self = Self(13);
+    pub const VINUM: Self = Self(14);
+    pub const RAID: Self = Self(15);
+    pub const FS_16: Self = Self(16);
+    pub const EXT2FS: Self = Self(17);
+    pub const NTFS: Self = Self(18);
+    pub const CCD: Self = Self(20);
+    pub const JFS2: Self = Self(21);
+    pub const HAMMER: Self = Self(22);
+    pub const HAMMER2: Self = Self(23);
+    pub const UDF: Self = Self(24);
+    pub const EFS: Self = Self(26);
+    pub const ZFS: Self = Self(27);
+    pub const NANDFS: Self = Self(30);
+
+    pub fn from_byte(byte: u8) -> Self {
+        Self(byte)
+    }
+
+    pub fn as_byte(&self) -> u8 {
+        self.0
+    }
+}
+
+/// Partition offsets are in sectors, either from the start of the disk or,
+/// since FreeBSD 10, from the start of the slice holding the label. The raw
+/// partition `c` starts at the start of the slice in both cases, so offsets
+/// are made relative to it.
+pub fn probe_bsd<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    _: ProbeFlags,
+    offset: u64,
+    magic: Magic,
+) -> Result<PtInfo, Error<IO::Error>> {
+    let buf: [u8; size_of::<BsdDiskLabel>()] = reader.read_exact_at(offset + magic.b_offset)?;
+    let label: &BsdDiskLabel = transmute_ref!(&buf);
+
+    if label.magic2 != BSD_MAGIC {
+        return Err(BsdError::MissingSecondMagic.into());
+    }
+
+    let npartitions = label.npartitions.get();
+    if usize::from(npartitions) > BSD_MAX_PARTITIONS {
+        return Err(BsdError::TooManyPartitions(npartitions).into());
+    }
+
+    label.verify_csum()?;
+
+    let ssz = label.secsize.get();
+    if ssz < 512 || !ssz.is_power_of_two() {
+        return Err(BsdError::InvalidSectorSize(ssz).into());
+    }
+    let ssz = u64::from(ssz);
+
+    let entries = &label.partitions[..usize::from(npartitions)];
+    let base = entries
+        .get(BSD_RAW_PART)
+        .map_or(0, |raw| u64::from(raw.offset.get()));
+
+    let mut partitions: Vec<Partition> = Vec::new();
+
+    for (i, entry) in entries.iter().enumerate() {
+        let sectors = u64::from(entry.size.get());
+
+        if i == BSD_RAW_PART || sectors == 0 || entry.fstype == BsdPartitionType::UNUSED {
+            continue;
+        }
+
+        // Partitions from outside the slice, such as the raw disk partition
+        // `d` on some ports, can't be addressed from here.
+        let Some(start) = u64::from(entry.offset.get()).checked_sub(base) else {
+            continue;
+        };
+
+        let start = start.checked_mul(ssz).ok_or(BsdError::Overflow)?;
+        let size = sectors.checked_mul(ssz).ok_or(BsdError::Overflow)?;
+
+        partitions.push(Partition {
+            start,
+            end: start.checked_add(size).ok_or(BsdError::Overflow)?,
+            partition_id: PartitionId::Bsd {
+                letter: char::from(b'a' + i as u8),
+            },
+            partition_type: PartitionType::Bsd(entry.fstype),
+            part_no: i as u64 + 1,
+            partition_name: None,
+            attributes: PartitionAttributes::None,
+        });
+    }
+
+    let mut info = PtInfo::new();
+
+    info.set(PtTag::PtType(PtType::Bsd));
+    info.set(PtTag::Magic(BSD_MAGIC.to_vec()));
+    info.set(PtTag::MagicOffset {
+        relative: magic.b_offset,
+        absolute: offset + magic.b_offset,
+    });
+    if !partitions.is_empty() {
+        info.set(PtTag::Partitions(partitions));
+    }
+
+    return Ok(info);
+}
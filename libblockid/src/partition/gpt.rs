@@ -341,7 +341,9 @@ pub fn probe_gpt<IO: BlockIo>(
     #[cfg(feature = "os_calls")]
     let (header, entries_buf, lssz) = {
         let lssz = reader.logical_sector_size()?;
-        let last_lba = (reader.device_size()? / lssz) - 1;
+        let last_lba = (reader.device_size()?.saturating_sub(offset) / lssz)
+            .checked_sub(1)
+            .ok_or(Error::DeviceTooSmall)?;
 
         let (header, entries_buf) =
             match GptTable::get_header(reader, offset, GptTable::FIRST_LBA, last_lba, lssz) {
@@ -403,7 +405,7 @@ pub fn probe_gpt<IO: BlockIo>(
 
         partitions.push(Partition {
             start: start * lssz,
-            end: end * lssz,
+            end: (end + 1) * lssz,
             partition_id: PartitionId::Uuid(partition.unique_partition_guid.into()),
             partition_type: PartitionType::Uuid(partition.partition_type_guid.into()),
             part_no: i + 1,
@@ -420,7 +422,10 @@ pub fn probe_gpt<IO: BlockIo>(
         (u64::from(header.alternate_lba) + 1) * lssz,
     ));
     info.set(PtTag::Magic(GptTable::SIGNATURE_STR.to_vec()));
-    info.set(PtTag::MagicOffset(lssz));
+    info.set(PtTag::MagicOffset {
+        relative: lssz,
+        absolute: offset + lssz,
+    });
     if !partitions.is_empty() {
         info.set(PtTag::Partitions(partitions));
     }
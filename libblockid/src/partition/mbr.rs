@@ -7,7 +7,10 @@ use zerocopy::{
 
 use crate::{
     error::Error,
-    filesystem::{exfat::probe_is_exfat, ntfs::probe_is_ntfs, vfat::probe_is_vfat},
+    filesystem::{
+        bitlocker::probe_is_bitlocker, exfat::probe_is_exfat, ntfs::probe_is_ntfs,
+        vfat::probe_is_vfat,
+    },
     io::{BlockIo, Reader},
     partition::{
         PartitionAttributes, PtId, PtInfo, PtTag, PtType, Partition,
@@ -24,6 +27,7 @@ pub enum MbrError {
     ProbablyVFAT,
     ProbablyEXFAT,
     ProbablyNTFS,
+    ProbablyBitlocker,
     MissingBootIndicator,
     BadPrimaryExtendedOffset,
     MultipleExtendedPartitions,
@@ -39,6 +43,7 @@ impl fmt::Display for MbrError {
             MbrError::ProbablyVFAT => write!(f, "Partition table looks like VFAT"),
             MbrError::ProbablyEXFAT => write!(f, "Partition table looks like EXFAT"),
             MbrError::ProbablyNTFS => write!(f, "Partition table looks like NTFS"),
+            MbrError::ProbablyBitlocker => write!(f, "Partition table looks like BitLocker"),
             MbrError::MissingBootIndicator => {
                 write!(f, "Missing boot indicator in partition entry")
             }
@@ -67,6 +72,11 @@ impl<E: fmt::Debug> From<MbrError> for Error<E> {
 const MBR_MAG: &[u8] = b"\x55\xAA";
 const MBR_MAG_OFFSET: u64 = 510;
 
+/// Partition number given to the first logical partition.
+const MBR_FIRST_LOGICAL_PART_NO: u8 = 5;
+/// Maximum number of EBR links followed without finding a data partition.
+const MBR_MAX_EBR_LINKS: u32 = 100;
+
 pub const MBR_MINSZ: Option<u64> = Some(512);
 pub const MBR_MAGICS: Option<&'static [Magic]> = Some(&[Magic {
     magic: MBR_MAG,
@@ -101,18 +111,8 @@ pub struct MbrPartitionEntry {
 }
 
 impl MbrPartitionEntry {
-    fn is_empty(&self) -> bool {
-        Self::as_bytes(self) == [0u8; 16]
-    }
-
     fn is_extended(&self) -> bool {
-        self.sys_ind == MbrPartitionType::DOS_EXTENDED
-            || self.sys_ind == MbrPartitionType::W95_EXTENDED
-            || self.sys_ind == MbrPartitionType::LINUX_EXTENDED
-    }
-
-    fn flags(&self) -> MbrAttributes {
-        MbrAttributes::from_bits_truncate(self.boot_ind)
+        self.sys_ind.is_extended()
     }
 }
 
@@ -247,6 +247,11 @@ impl MbrPartitionType {
     pub fn as_byte(&self) -> u8 {
         self.0
     }
+
+    /// Returns `true` for the partition types used by extended partitions.
+    pub fn is_extended(&self) -> bool {
+        *self == Self::DOS_EXTENDED || *self == Self::W95_EXTENDED || *self == Self::LINUX_EXTENDED
+    }
 }
 
 bitflags! {
@@ -264,9 +269,9 @@ fn is_valid_mbr<IO: BlockIo>(
     pt: &MbrTable,
 ) -> Result<(), Error<IO::Error>> {
     for entry in pt.partition_entries {
-        let boot_ind = entry.flags();
-        if !boot_ind.contains(MbrAttributes::INACTIVE) && !boot_ind.contains(MbrAttributes::ACTIVE)
-        {
+        // INACTIVE is zero so any indicator contains it, only the two known
+        // values are valid.
+        if MbrAttributes::from_bits(entry.boot_ind).is_none() {
             return Err(MbrError::MissingBootIndicator.into());
         }
 
@@ -287,11 +292,142 @@ fn is_valid_mbr<IO: BlockIo>(
         return Err(MbrError::ProbablyNTFS.into());
     }
 
+    if probe_is_bitlocker(reader, offset)? {
+        return Err(MbrError::ProbablyBitlocker.into());
+    }
+
     // TODO - is_lvm(pr) && is_empty_mbr(data)
 
     Ok(())
 }
 
+/// Walks the chain of extended boot records (EBRs) starting at the primary
+/// extended partition `extend`, returning the logical partitions found.
+///
+/// Logical partitions are numbered from 5, the same as the Linux kernel. The
+/// walk stops on a malformed EBR, when an EBR is visited twice or after
+/// [`MBR_MAX_EBR_LINKS`] links without finding a data partition.
+fn parse_extended<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    offset: u64,
+    ssz: u64,
+    disk: u32,
+    extend: &MbrPartitionEntry,
+) -> Result<Vec<Partition>, Error<IO::Error>> {
+    let mut partitions: Vec<Partition> = Vec::new();
+
+    let ex_start = u64::from(extend.start_sect)
+        .checked_mul(ssz)
+        .ok_or(MbrError::Overflow)?;
+    let ex_size = u64::from(extend.nr_sects)
+        .checked_mul(ssz)
+        .ok_or(MbrError::Overflow)?;
+    let ex_end = ex_start.checked_add(ex_size).ok_or(MbrError::Overflow)?;
+
+    // Bad offset in primary extended partition, ignore it like libblkid does
+    if ex_start == 0 {
+        return Ok(partitions);
+    }
+
+    let mut cur_start = ex_start;
+    let mut cur_size = ex_size;
+    let mut part_no: u8 = MBR_FIRST_LOGICAL_PART_NO;
+    let mut visited: Vec<u64> = Vec::new();
+    let mut links_without_data = 0;
+
+    loop {
+        links_without_data += 1;
+        if links_without_data > MBR_MAX_EBR_LINKS || visited.contains(&cur_start) {
+            break;
+        }
+        visited.push(cur_start);
+
+        let buf: [u8; size_of::<MbrTable>()] = reader.read_exact_at(offset + cur_start)?;
+
+        let ebr: &MbrTable = transmute_ref!(&buf);
+
+        if ebr.boot_signature != MBR_MAG {
+            break;
+        }
+
+        /* Note From libblkid:
+         * Usually, the first entry is the real data partition,
+         * the 2nd entry is the next extended partition, or empty,
+         * and the 3rd and 4th entries are unused.
+         * However, DRDOS sometimes has the extended partition as
+         * the first entry (when the data partition is empty),
+         * and OS/2 seems to use all four entries.
+         */
+        for (i, entry) in ebr.partition_entries.iter().enumerate() {
+            // The start of a data partition is relative to the current EBR
+            let start = u64::from(entry.start_sect)
+                .checked_mul(ssz)
+                .ok_or(MbrError::Overflow)?;
+            let size = u64::from(entry.nr_sects)
+                .checked_mul(ssz)
+                .ok_or(MbrError::Overflow)?;
+            let abs_start = cur_start.checked_add(start).ok_or(MbrError::Overflow)?;
+            let abs_end = abs_start.checked_add(size).ok_or(MbrError::Overflow)?;
+
+            if size == 0 || entry.is_extended() {
+                continue;
+            }
+
+            // Extra checks to detect real data on the 3rd and 4th entries
+            if i >= 2
+                && (start.saturating_add(size) > cur_size
+                    || abs_start < ex_start
+                    || abs_end > ex_end)
+            {
+                continue;
+            }
+
+            // Avoid recursive non-empty links pointing at the same partition
+            if partitions.iter().any(|p| p.start == abs_start) {
+                continue;
+            }
+
+            partitions.push(Partition {
+                start: abs_start,
+                end: abs_end,
+                partition_id: PartitionId::Mbr { disk, part_no },
+                partition_type: PartitionType::Mbr(entry.sys_ind),
+                part_no: u64::from(part_no),
+                partition_name: None,
+                attributes: PartitionAttributes::Mbr(entry.boot_ind),
+            });
+
+            links_without_data = 0;
+            part_no = match part_no.checked_add(1) {
+                Some(t) => t,
+                None => return Ok(partitions),
+            };
+        }
+
+        // The first nested extended partition links to the next EBR, the
+        // start of which is relative to the primary extended partition
+        let next = ebr
+            .partition_entries
+            .iter()
+            .find(|entry| entry.nr_sects != 0 && entry.is_extended());
+
+        match next {
+            Some(entry) if entry.start_sect != 0 => {
+                cur_start = u64::from(entry.start_sect)
+                    .checked_mul(ssz)
+                    .and_then(|start| ex_start.checked_add(start))
+                    .ok_or(MbrError::Overflow)?;
+                cur_size = u64::from(entry.nr_sects)
+                    .checked_mul(ssz)
+                    .ok_or(MbrError::Overflow)?;
+            }
+            _ => break,
+        }
+    }
+
+    Ok(partitions)
+}
+
 /// When `os_calls` is unavailable parsing will default to 512 byte logical
 /// sector size as MBR does not provide enough information to figure out the
 /// partition table sector size from its header content alone.
@@ -319,6 +455,8 @@ pub fn probe_mbr<IO: BlockIo>(
     #[cfg(not(feature = "os_calls"))]
     const ssz: u64 = 512;
 
+    let disk = u32::from_le_bytes(mbr_pt.disk_id);
+
     let mut partitions: Vec<Partition> = Vec::new();
 
     let primary = mbr_pt.partition_entries;
@@ -345,35 +483,35 @@ pub fn probe_mbr<IO: BlockIo>(
             } else {
                 return Err(MbrError::MultipleExtendedPartitions.into());
             }
-            continue;
         }
 
         partitions.push(Partition {
             start,
-            end: start.checked_add(start).ok_or(MbrError::Overflow)?,
-            partition_id: PartitionId::Mbr {
-                disk: u32::from_le_bytes(mbr_pt.disk_id),
-                part_no,
-            },
+            end: start.checked_add(size).ok_or(MbrError::Overflow)?,
+            partition_id: PartitionId::Mbr { disk, part_no },
             partition_type: PartitionType::Mbr(part.sys_ind),
             part_no: u64::from(part_no),
             partition_name: None,
             attributes: PartitionAttributes::Mbr(part.boot_ind),
         });
+
+        part_no += 1;
     }
 
     if let Some(extend) = extended {
-        todo!()
+        let logical = parse_extended(reader, offset, ssz, disk, &extend)?;
+        partitions.extend(logical);
     }
 
     let mut info = PtInfo::new();
 
     info.set(PtTag::PtType(PtType::Mbr));
-    info.set(PtTag::PtId(PtId::Mbr {
-        disk: u32::from_le_bytes(mbr_pt.disk_id),
-    }));
+    info.set(PtTag::PtId(PtId::Mbr { disk }));
     info.set(PtTag::Magic(MBR_MAG.to_vec()));
-    info.set(PtTag::MagicOffset(MBR_MAG_OFFSET));
+    info.set(PtTag::MagicOffset {
+        relative: MBR_MAG_OFFSET,
+        absolute: offset + MBR_MAG_OFFSET,
+    });
     if !partitions.is_empty() {
         info.set(PtTag::Partitions(partitions));
     }
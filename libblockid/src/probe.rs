@@ -3,8 +3,9 @@ use bitflags::bitflags;
 use crate::{
     error::Error,
     filesystem::{FS_DETECT_ORDER, FsFilter, FsInfo, FsType},
-    io::{BlockIo, Reader},
-    partition::{PT_DETECT_ORDER, PtFilter, PtInfo, PtType},
+    io::{BlockIo, Reader, SeekFrom},
+    partition::{PT_DETECT_ORDER, Partition, PtFilter, PtInfo, PtType},
+    std::fmt,
 };
 
 /// Describes the intended usage of a superblock.
@@ -21,6 +22,8 @@ pub enum Usage {
     Raid,
     /// Manages an encrypted volume or backing store.
     Crypto,
+    /// Holds data belonging to another device, such as an external log.
+    Other,
 }
 
 /// The byte order used to represent multi-byte values.
@@ -58,6 +61,11 @@ bitflags! {
     pub struct ProbeFlags: u64 {
         /// Return an error if a UTF string encountered during probing is Invalid.
         const FailOnInvalidUTF = 1 << 0;
+        /// Run every filesystem prober instead of stopping at the first match,
+        /// returning [`Error::Ambivalent`] if signatures of incompatible types
+        /// are found on the device. A RAID member found first is returned
+        /// without running the rest.
+        const FailOnAmbivalent = 1 << 1;
     }
 }
 
@@ -67,6 +75,10 @@ pub fn probe_filesystem<IO: BlockIo>(
     offset: u64,
     filter: FsFilter,
 ) -> Result<FsInfo, Error<IO::Error>> {
+    let ambivalent = flags.contains(ProbeFlags::FailOnAmbivalent);
+    let mut found: Vec<FsType> = Vec::new();
+    let mut first: Option<FsInfo> = None;
+
     for block in FS_DETECT_ORDER {
         if filter.contains(block.0) {
             continue;
@@ -77,7 +89,7 @@ pub fn probe_filesystem<IO: BlockIo>(
         #[cfg(feature = "os_calls")]
         {
             if let Some(minsz) = handle.minsz
-                && reader.device_size()? < minsz
+                && reader.device_size()?.saturating_sub(offset) < minsz
             {
                 continue;
             }
@@ -86,14 +98,14 @@ pub fn probe_filesystem<IO: BlockIo>(
         #[cfg(not(feature = "os_calls"))]
         {
             if let Some(minsz) = handle.minsz
-                && reader.seek(crate::io::SeekFrom::End(0))? < minsz
+                && reader.seek(SeekFrom::End(0))?.saturating_sub(offset) < minsz
             {
                 continue;
             }
         }
 
         let magic = match handle.magics {
-            Some(magics) => match reader.get_magic(magics)? {
+            Some(magics) => match reader.get_magic(offset, magics)? {
                 Some(magic) => magic,
                 None => continue,
             },
@@ -101,7 +113,14 @@ pub fn probe_filesystem<IO: BlockIo>(
         };
 
         match (handle.probe)(reader, flags, offset, magic) {
-            Ok(t) => return Ok(t),
+            Ok(t) if !ambivalent => return Ok(t),
+            // Like libblkid, nothing is looked for past a RAID member, the
+            // contents of the array often show through on each member.
+            Ok(t) if found.is_empty() && t.usage() == Some(Usage::Raid) => return Ok(t),
+            Ok(t) => {
+                found.push(block.1);
+                first.get_or_insert(t);
+            }
             Err(e) => {
                 if let Error::Io(_) = e {
                     return Err(e);
@@ -109,7 +128,16 @@ pub fn probe_filesystem<IO: BlockIo>(
             }
         };
     }
-    return Err(Error::ProbesExhausted);
+
+    if found
+        .iter()
+        .enumerate()
+        .any(|(i, a)| found[i + 1..].iter().any(|b| !a.is_compatible(*b)))
+    {
+        return Err(Error::Ambivalent(found));
+    }
+
+    return first.ok_or(Error::ProbesExhausted);
 }
 
 pub fn search_for_filesystem<IO: BlockIo>(
@@ -123,7 +151,7 @@ pub fn search_for_filesystem<IO: BlockIo>(
     #[cfg(feature = "os_calls")]
     {
         if let Some(minsz) = handle.minsz
-            && reader.device_size()? < minsz
+            && reader.device_size()?.saturating_sub(offset) < minsz
         {
             return Err(Error::DeviceTooSmall);
         }
@@ -132,14 +160,14 @@ pub fn search_for_filesystem<IO: BlockIo>(
     #[cfg(not(feature = "os_calls"))]
     {
         if let Some(minsz) = handle.minsz
-            && reader.seek(crate::io::SeekFrom::End(0))? < minsz
+            && reader.seek(SeekFrom::End(0))?.saturating_sub(offset) < minsz
         {
             return Err(Error::DeviceTooSmall);
         }
     }
 
     let magic = match handle.magics {
-        Some(magics) => match reader.get_magic(magics)? {
+        Some(magics) => match reader.get_magic(offset, magics)? {
             Some(magic) => magic,
             None => return Err(Error::UnableToLocateMagicSignature),
         },
@@ -165,7 +193,7 @@ pub fn probe_part_table<IO: BlockIo>(
         #[cfg(feature = "os_calls")]
         {
             if let Some(minsz) = handle.minsz
-                && reader.device_size()? < minsz
+                && reader.device_size()?.saturating_sub(offset) < minsz
             {
                 continue;
             }
@@ -174,14 +202,14 @@ pub fn probe_part_table<IO: BlockIo>(
         #[cfg(not(feature = "os_calls"))]
         {
             if let Some(minsz) = handle.minsz
-                && reader.seek(crate::io::SeekFrom::End(0))? < minsz
+                && reader.seek(SeekFrom::End(0))?.saturating_sub(offset) < minsz
             {
                 continue;
             }
         }
 
         let magic = match handle.magics {
-            Some(magics) => match reader.get_magic(magics)? {
+            Some(magics) => match reader.get_magic(offset, magics)? {
                 Some(magic) => magic,
                 None => continue,
             },
@@ -211,7 +239,7 @@ pub fn search_for_part_table<IO: BlockIo>(
     #[cfg(feature = "os_calls")]
     {
         if let Some(minsz) = handle.minsz
-            && reader.device_size()? < minsz
+            && reader.device_size()?.saturating_sub(offset) < minsz
         {
             return Err(Error::DeviceTooSmall);
         }
@@ -220,14 +248,14 @@ pub fn search_for_part_table<IO: BlockIo>(
     #[cfg(not(feature = "os_calls"))]
     {
         if let Some(minsz) = handle.minsz
-            && reader.seek(crate::io::SeekFrom::End(0))? < minsz
+            && reader.seek(SeekFrom::End(0))?.saturating_sub(offset) < minsz
         {
             return Err(Error::DeviceTooSmall);
         }
     }
 
     let magic = match handle.magics {
-        Some(magics) => match reader.get_magic(magics)? {
+        Some(magics) => match reader.get_magic(offset, magics)? {
             Some(magic) => magic,
             None => return Err(Error::UnableToLocateMagicSignature),
         },
@@ -237,6 +265,430 @@ pub fn search_for_part_table<IO: BlockIo>(
     (handle.probe)(reader, flags, offset, magic)
 }
 
+/// A signature found by [`Probe::probe_all_signatures`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+#[derive(Debug)]
+pub enum Signature {
+    /// A filesystem superblock.
+    Filesystem(FsInfo),
+    /// A partition table.
+    PartTable(PtInfo),
+}
+
+/// The handler a [`Signatures`] result was returned by.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SignatureType {
+    Filesystem(FsType),
+    PartTable(PtType),
+}
+
+impl fmt::Display for SignatureType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureType::Filesystem(fs_type) => write!(f, "{}", fs_type),
+            SignatureType::PartTable(pt_type) => write!(f, "{}", pt_type),
+        }
+    }
+}
+
+/// What running a handler resulted in, see [`ProbeReport`].
+#[derive(Debug)]
+pub enum ProbeOutcome<E: fmt::Debug> {
+    /// The handler was skipped by the filter given.
+    Filtered,
+    /// The device is smaller than the minimum the handler requires.
+    DeviceTooSmall,
+    /// None of the handler's magics were found.
+    MagicNotFound,
+    /// The handler accepted the device.
+    Found(Signature),
+    /// The handler rejected the device with the given error.
+    Rejected(Error<E>),
+}
+
+/// A single handler run by [`Probe::probe_report`].
+#[derive(Debug)]
+pub struct ProbeAttempt<E: fmt::Debug> {
+    /// The handler that was run.
+    pub kind: SignatureType,
+    /// The magic that matched, `None` if none did or the handler has no
+    /// magic to search for.
+    pub magic: Option<Magic>,
+    /// What the handler made of the device.
+    pub outcome: ProbeOutcome<E>,
+}
+
+impl<E: fmt::Debug> ProbeAttempt<E> {
+    const fn filtered(kind: SignatureType) -> Self {
+        Self {
+            kind,
+            magic: None,
+            outcome: ProbeOutcome::Filtered,
+        }
+    }
+}
+
+/// Every filesystem and partition table handler run on a device, in
+/// [`FS_DETECT_ORDER`] then [`PT_DETECT_ORDER`] order, explaining why each
+/// one did or did not match.
+#[derive(Debug)]
+pub struct ProbeReport<E: fmt::Debug> {
+    pub attempts: Vec<ProbeAttempt<E>>,
+}
+
+impl<E: fmt::Debug> ProbeReport<E> {
+    /// Returns the filesystem of the first [`ProbeOutcome::Found`] attempt.
+    ///
+    /// This is what [`probe_filesystem`] returns unless
+    /// [`ProbeFlags::FailOnAmbivalent`] is set and signatures of incompatible
+    /// types were found, in which case it returns [`Error::Ambivalent`]
+    /// instead.
+    pub fn filesystem(&self) -> Option<&FsInfo> {
+        self.attempts.iter().find_map(|a| match &a.outcome {
+            ProbeOutcome::Found(Signature::Filesystem(info)) => Some(info),
+            _ => None,
+        })
+    }
+
+    /// Returns the partition table [`probe_part_table`] would return.
+    pub fn part_table(&self) -> Option<&PtInfo> {
+        self.attempts.iter().find_map(|a| match &a.outcome {
+            ProbeOutcome::Found(Signature::PartTable(info)) => Some(info),
+            _ => None,
+        })
+    }
+}
+
+/// Returns the handler at `index` in [`FS_DETECT_ORDER`] followed by
+/// [`PT_DETECT_ORDER`].
+fn detect_order(index: usize) -> Option<SignatureType> {
+    FS_DETECT_ORDER
+        .iter()
+        .map(|(_, fs_type)| SignatureType::Filesystem(*fs_type))
+        .chain(
+            PT_DETECT_ORDER
+                .iter()
+                .map(|(_, pt_type)| SignatureType::PartTable(*pt_type)),
+        )
+        .nth(index)
+}
+
+/// Runs a single handler at `offset`, recording how far it got.
+fn attempt<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    kind: SignatureType,
+) -> ProbeAttempt<IO::Error> {
+    let (minsz, magics) = match kind {
+        SignatureType::Filesystem(fs_type) => {
+            let handle = fs_type.fs_handler::<IO>();
+            (handle.minsz, handle.magics)
+        }
+        SignatureType::PartTable(pt_type) => {
+            let handle = pt_type.pt_handler::<IO>();
+            (handle.minsz, handle.magics)
+        }
+    };
+
+    let mut attempt = ProbeAttempt {
+        kind,
+        magic: None,
+        outcome: ProbeOutcome::MagicNotFound,
+    };
+
+    #[cfg(feature = "os_calls")]
+    let size = reader.device_size();
+    #[cfg(not(feature = "os_calls"))]
+    let size = reader.seek(SeekFrom::End(0));
+
+    match size {
+        Ok(size) => {
+            if let Some(minsz) = minsz
+                && size.saturating_sub(offset) < minsz
+            {
+                attempt.outcome = ProbeOutcome::DeviceTooSmall;
+                return attempt;
+            }
+        }
+        Err(e) => {
+            attempt.outcome = ProbeOutcome::Rejected(e);
+            return attempt;
+        }
+    }
+
+    let magic = match magics.map(|magics| reader.get_magic(offset, magics)) {
+        Some(Ok(Some(magic))) => {
+            attempt.magic = Some(magic);
+            magic
+        }
+        Some(Ok(None)) => return attempt,
+        Some(Err(e)) => {
+            attempt.outcome = ProbeOutcome::Rejected(e);
+            return attempt;
+        }
+        None => Magic::EMPTY_MAGIC,
+    };
+
+    let result = match kind {
+        SignatureType::Filesystem(fs_type) => {
+            (fs_type.fs_handler::<IO>().probe)(reader, flags, offset, magic)
+                .map(Signature::Filesystem)
+        }
+        SignatureType::PartTable(pt_type) => {
+            (pt_type.pt_handler::<IO>().probe)(reader, flags, offset, magic)
+                .map(Signature::PartTable)
+        }
+    };
+
+    attempt.outcome = match result {
+        Ok(signature) => ProbeOutcome::Found(signature),
+        // Handlers without fixed magics search for their own.
+        Err(Error::UnableToLocateMagicSignature) => ProbeOutcome::MagicNotFound,
+        Err(e) => ProbeOutcome::Rejected(e),
+    };
+
+    return attempt;
+}
+
+/// Runs every filesystem and partition table handler at `offset`, see
+/// [`ProbeReport`].
+pub fn probe_report<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    fs_filter: FsFilter,
+    pt_filter: PtFilter,
+) -> ProbeReport<IO::Error> {
+    let mut attempts = Vec::new();
+
+    for (flag, fs_type) in FS_DETECT_ORDER {
+        let kind = SignatureType::Filesystem(*fs_type);
+
+        attempts.push(if fs_filter.contains(*flag) {
+            ProbeAttempt::filtered(kind)
+        } else {
+            attempt(reader, flags, offset, kind)
+        });
+    }
+
+    for (flag, pt_type) in PT_DETECT_ORDER {
+        let kind = SignatureType::PartTable(*pt_type);
+
+        attempts.push(if pt_filter.contains(*flag) {
+            ProbeAttempt::filtered(kind)
+        } else {
+            attempt(reader, flags, offset, kind)
+        });
+    }
+
+    return ProbeReport { attempts };
+}
+
+/// Iterator over every signature on a device, returned by
+/// [`Probe::probe_all_signatures`].
+///
+/// Runs each filesystem handler in [`FS_DETECT_ORDER`] followed by each
+/// partition table handler in [`PT_DETECT_ORDER`]. Handlers whose magic is
+/// not found, or that need a larger device, are skipped, as are failing
+/// handlers that have no magic to search for. Every other handler yields its
+/// result, including the error of one that found its magic but failed to
+/// validate the rest of the structure.
+#[derive(Debug)]
+pub struct Signatures<'a, IO: BlockIo> {
+    reader: &'a mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    index: usize,
+}
+
+impl<IO: BlockIo> Iterator for Signatures<'_, IO> {
+    type Item = (SignatureType, Result<Signature, Error<IO::Error>>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(kind) = detect_order(self.index) {
+            self.index += 1;
+
+            let attempt = attempt(self.reader, self.flags, self.offset, kind);
+
+            match attempt.outcome {
+                ProbeOutcome::Found(signature) => return Some((kind, Ok(signature))),
+                // Without a magic to go by, a failing handler only means that
+                // its structure is not present.
+                ProbeOutcome::Rejected(e)
+                    if attempt.magic.is_some() || matches!(e, Error::Io(_)) =>
+                {
+                    return Some((kind, Err(e)));
+                }
+                _ => continue,
+            }
+        }
+
+        return None;
+    }
+}
+
+/// Returns an iterator running every filesystem and partition table handler
+/// at `offset`, see [`Signatures`].
+pub fn probe_all_signatures<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+) -> Signatures<'_, IO> {
+    Signatures {
+        reader,
+        flags,
+        offset,
+        index: 0,
+    }
+}
+
+/// Maximum depth of nested partition tables followed by [`probe_all`].
+const MAX_PROBE_DEPTH: usize = 4;
+
+/// Filesystem and partition table found in a region of a device, as returned
+/// by [`Probe::probe_all`].
+///
+/// A probe that fails other than by finding nothing is recorded in the
+/// region it was run on, the rest of the device is still probed.
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(bound = ""))]
+#[derive(Debug)]
+pub struct ProbeTree<E: fmt::Debug> {
+    /// Start of the region in bytes from the start of the device.
+    pub offset: u64,
+    /// Size of the region in bytes.
+    pub size: u64,
+    /// Filesystem found at the start of the region, if any.
+    pub filesystem: Option<FsInfo>,
+    /// Why probing for a filesystem failed, such as [`Error::Ambivalent`]
+    /// under [`ProbeFlags::FailOnAmbivalent`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "serialize_error"
+        )
+    )]
+    pub fs_error: Option<Error<E>>,
+    /// Partition table found at the start of the region, if any.
+    pub part_table: Option<PtInfo>,
+    /// Why probing for a partition table failed.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            skip_serializing_if = "Option::is_none",
+            serialize_with = "serialize_error"
+        )
+    )]
+    pub pt_error: Option<Error<E>>,
+    /// Contents of each partition listed in `part_table`.
+    pub partitions: Vec<PartitionTree<E>>,
+}
+
+/// A partition and what was found inside of it.
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(bound = ""))]
+#[derive(Debug)]
+pub struct PartitionTree<E: fmt::Debug> {
+    /// The partition as listed in its partition table.
+    pub partition: Partition,
+    /// Contents of the partition, `None` when the partition was not probed
+    /// because it only holds other partitions, lies outside of the device or
+    /// is nested too deep.
+    pub contents: Option<ProbeTree<E>>,
+}
+
+/// Errors are written as their message.
+#[cfg(feature = "serde")]
+fn serialize_error<E: fmt::Debug, S: serde::Serializer>(
+    error: &Option<Error<E>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match error {
+        Some(e) => serializer.collect_str(e),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Probes the region from `offset` to `end` for a filesystem and partition
+/// table, descending into each partition with the reader bounded to it.
+fn probe_tree<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+    end: u64,
+    depth: usize,
+) -> Result<ProbeTree<IO::Error>, Error<IO::Error>> {
+    let (filesystem, fs_error) = match probe_filesystem(reader, flags, offset, FsFilter::empty()) {
+        Ok(info) => (Some(info), None),
+        Err(Error::ProbesExhausted) => (None, None),
+        Err(Error::Io(e)) => return Err(Error::Io(e)),
+        Err(e) => (None, Some(e)),
+    };
+
+    let (part_table, pt_error) = match probe_part_table(reader, flags, offset, PtFilter::empty()) {
+        Ok(info) => (Some(info), None),
+        Err(Error::ProbesExhausted) => (None, None),
+        Err(Error::Io(e)) => return Err(Error::Io(e)),
+        Err(e) => (None, Some(e)),
+    };
+
+    let mut partitions = Vec::new();
+
+    for partition in part_table
+        .iter()
+        .flat_map(|pt| pt.partitions().unwrap_or_default())
+    {
+        let start = offset.saturating_add(partition.start);
+        let part_end = offset.saturating_add(partition.end);
+
+        let contents = if depth + 1 >= MAX_PROBE_DEPTH
+            || partition.is_container()
+            || start >= part_end
+            || part_end > end
+        {
+            None
+        } else {
+            let prev = reader.set_end(Some(part_end));
+            let tree = probe_tree(reader, flags, start, part_end, depth + 1);
+            reader.set_end(prev);
+
+            Some(tree?)
+        };
+
+        partitions.push(PartitionTree {
+            partition: partition.clone(),
+            contents,
+        });
+    }
+
+    return Ok(ProbeTree {
+        offset,
+        size: end - offset,
+        filesystem,
+        fs_error,
+        part_table,
+        pt_error,
+        partitions,
+    });
+}
+
+/// Probes for a partition table and filesystem at `offset`, then probes
+/// each partition found for its own filesystem and nested partition table.
+pub fn probe_all<IO: BlockIo>(
+    reader: &mut Reader<IO>,
+    flags: ProbeFlags,
+    offset: u64,
+) -> Result<ProbeTree<IO::Error>, Error<IO::Error>> {
+    let end = reader.seek(SeekFrom::End(0))?;
+
+    if offset >= end {
+        return Err(Error::OffsetExceedsDeviceSize);
+    }
+
+    probe_tree(reader, flags, offset, end, 0)
+}
+
 /// Probe for detecting filesystems and partition tables on a block device.
 #[derive(Debug)]
 pub struct Probe<IO: BlockIo> {
@@ -258,7 +710,7 @@ impl<IO: BlockIo> Probe<IO> {
     pub fn new(reader: IO, flags: ProbeFlags, offset: u64) -> Result<Probe<IO>, Error<IO::Error>> {
         let mut io = Reader::new(reader);
 
-        if offset >= io.seek(crate::io::SeekFrom::End(0))? {
+        if offset >= io.seek(SeekFrom::End(0))? {
             return Err(Error::OffsetExceedsDeviceSize);
         }
 
@@ -270,12 +722,15 @@ impl<IO: BlockIo> Probe<IO> {
     }
 
     #[inline]
-    pub fn probe_filesystem(&mut self, filter: BlockFilter) -> Result<BlockInfo, Error<IO::Error>> {
+    pub fn probe_filesystem(&mut self, filter: FsFilter) -> Result<FsInfo, Error<IO::Error>> {
         probe_filesystem(&mut self.reader, self.flags, self.offset, filter)
     }
 
     #[inline]
-    pub fn search_for_filesystem(&mut self, filesystem: FsType) -> Result<BlockInfo, Error<IO::Error>> {
+    pub fn search_for_filesystem(
+        &mut self,
+        filesystem: FsType,
+    ) -> Result<FsInfo, Error<IO::Error>> {
         search_for_filesystem(&mut self.reader, self.flags, self.offset, filesystem)
     }
 
@@ -294,6 +749,38 @@ impl<IO: BlockIo> Probe<IO> {
     ) -> Result<PtInfo, Error<IO::Error>> {
         search_for_part_table(&mut self.reader, self.flags, self.offset, part_table)
     }
+
+    /// Probes the partition table and every partition's filesystem, see
+    /// [`ProbeTree`].
+    #[inline]
+    pub fn probe_all(&mut self) -> Result<ProbeTree<IO::Error>, Error<IO::Error>> {
+        probe_all(&mut self.reader, self.flags, self.offset)
+    }
+
+    /// Returns an iterator over every filesystem and partition table
+    /// signature found, see [`Signatures`].
+    #[inline]
+    pub fn probe_all_signatures(&mut self) -> Signatures<'_, IO> {
+        probe_all_signatures(&mut self.reader, self.flags, self.offset)
+    }
+
+    /// Runs every filesystem and partition table handler not skipped by the
+    /// filters, recording why each one did or did not match, see
+    /// [`ProbeReport`].
+    #[inline]
+    pub fn probe_report(
+        &mut self,
+        fs_filter: FsFilter,
+        pt_filter: PtFilter,
+    ) -> ProbeReport<IO::Error> {
+        probe_report(
+            &mut self.reader,
+            self.flags,
+            self.offset,
+            fs_filter,
+            pt_filter,
+        )
+    }
 }
 
 #[cfg(feature = "os_calls")]
@@ -394,6 +881,40 @@ impl Probe<crate::io::File> {
         search_for_part_table(&mut self.reader, self.flags, self.offset, part_table)
     }
 
+    /// Probes the partition table and every partition's filesystem, see
+    /// [`ProbeTree`].
+    #[inline]
+    pub fn probe_all(
+        &mut self,
+    ) -> Result<ProbeTree<crate::io::IoError>, Error<crate::io::IoError>> {
+        probe_all(&mut self.reader, self.flags, self.offset)
+    }
+
+    /// Returns an iterator over every filesystem and partition table
+    /// signature found, see [`Signatures`].
+    #[inline]
+    pub fn probe_all_signatures(&mut self) -> Signatures<'_, crate::io::File> {
+        probe_all_signatures(&mut self.reader, self.flags, self.offset)
+    }
+
+    /// Runs every filesystem and partition table handler not skipped by the
+    /// filters, recording why each one did or did not match, see
+    /// [`ProbeReport`].
+    #[inline]
+    pub fn probe_report(
+        &mut self,
+        fs_filter: FsFilter,
+        pt_filter: PtFilter,
+    ) -> ProbeReport<crate::io::IoError> {
+        probe_report(
+            &mut self.reader,
+            self.flags,
+            self.offset,
+            fs_filter,
+            pt_filter,
+        )
+    }
+
     #[inline]
     pub fn device_size(&self) -> Result<u64, Error<crate::io::IoError>> {
         self.reader.device_size()
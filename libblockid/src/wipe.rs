@@ -0,0 +1,385 @@
+use rustix::io::pwrite;
+
+use crate::{
+    error::Error,
+    filesystem::{
+        FS_DETECT_ORDER, FsFilter, FsInfo, FsType,
+        btrfs::{MAGIC_OFFSET, SUPER_OFFSETS},
+        luks::{LUKS1_MAGIC, LUKS2_MAGIC, SECONDARY_OFFSETS},
+    },
+    io::{File, IoError, Reader},
+    partition::{PT_DETECT_ORDER, PtFilter, PtInfo, PtType},
+    probe::{ProbeFlags, Signature, SignatureType, Signatures, probe_all_signatures},
+};
+
+/// Maximum number of times [`Wiper::wipe_all`] probes the device again after
+/// erasing signatures, in case erasing one exposed another.
+const MAX_WIPE_PASSES: usize = 8;
+
+/// Signature ending a FAT boot sector or MBR.
+const BOOT_SIGNATURE: [u8; 2] = [0x55, 0xAA];
+
+/// Called with each region before it is erased.
+type BeforeWipe<'a> = &'a mut dyn FnMut(&WipedRegion) -> Result<(), Error<IoError>>;
+
+/// A signature erased by a [`Wiper`], or that would have been in dry-run mode.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct WipedRegion {
+    /// The type of signature the region belongs to.
+    pub kind: SignatureType,
+    /// Start of the region in bytes from the start of the device.
+    pub offset: u64,
+    /// Contents of the region before it was erased.
+    pub backup: Vec<u8>,
+}
+
+/// Erases filesystem and partition table signatures from a device.
+///
+/// Only the magic bytes recorded in [`FsInfo`] and [`PtInfo`] are zeroed,
+/// along with any copies of them kept elsewhere on the device, such as the
+/// GPT backup header and the LUKS2 secondary headers. Bytes are only zeroed
+/// if they still hold the expected magic.
+#[derive(Debug)]
+pub struct Wiper {
+    reader: Reader<File>,
+    flags: ProbeFlags,
+    offset: u64,
+    dry_run: bool,
+}
+
+impl Wiper {
+    /// Creates a new [`Wiper`] for a device opened for reading and writing.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn from_file(file: File, flags: ProbeFlags, offset: u64) -> Result<Wiper, Error<IoError>> {
+        let reader = Reader::new(file);
+
+        if offset >= reader.device_size()? {
+            return Err(Error::OffsetExceedsDeviceSize);
+        }
+
+        Ok(Self {
+            reader,
+            flags,
+            offset,
+            dry_run: false,
+        })
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn open<P: AsRef<std::path::Path>>(
+        path: P,
+        flags: ProbeFlags,
+        offset: u64,
+    ) -> Result<Wiper, Error<IoError>> {
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)?;
+
+        Self::from_file(file, flags, offset)
+    }
+
+    /// Creates a new [`Wiper`] for a device opened for reading and writing.
+    #[cfg(feature = "no_std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "no_std")))]
+    pub fn from_fd(
+        fd: rustix::fd::OwnedFd,
+        flags: ProbeFlags,
+        offset: u64,
+    ) -> Result<Wiper, Error<IoError>> {
+        let reader = Reader::new(fd.into());
+
+        if offset >= reader.device_size()? {
+            return Err(Error::OffsetExceedsDeviceSize);
+        }
+
+        Ok(Self {
+            reader,
+            flags,
+            offset,
+            dry_run: false,
+        })
+    }
+
+    #[cfg(feature = "no_std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "no_std")))]
+    pub fn open<P: rustix::path::Arg>(
+        path: P,
+        flags: ProbeFlags,
+        offset: u64,
+    ) -> Result<Wiper, Error<IoError>> {
+        let fd = rustix::fs::open(path, rustix::fs::OFlags::RDWR, rustix::fs::Mode::empty())?;
+
+        Self::from_fd(fd, flags, offset)
+    }
+
+    /// In dry-run mode regions are reported, but nothing is written to the
+    /// device.
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    #[inline]
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Returns an iterator over every signature on the device, see
+    /// [`Signatures`].
+    #[inline]
+    pub fn probe_all_signatures(&mut self) -> Signatures<'_, File> {
+        probe_all_signatures(&mut self.reader, self.flags, self.offset)
+    }
+
+    /// Zeroes `magic` at `offset` if it is still present, recording it in
+    /// `regions` after passing it to `before`.
+    fn wipe_magic(
+        &mut self,
+        kind: SignatureType,
+        offset: u64,
+        magic: &[u8],
+        regions: &mut Vec<WipedRegion>,
+        before: BeforeWipe,
+    ) -> Result<(), Error<IoError>> {
+        if regions.iter().any(|r| r.offset == offset)
+            || offset.saturating_add(magic.len() as u64) > self.reader.device_size()?
+        {
+            return Ok(());
+        }
+
+        let backup = self.reader.read_vec_at(offset, magic.len())?;
+
+        if backup != magic {
+            return Ok(());
+        }
+
+        let region = WipedRegion {
+            kind,
+            offset,
+            backup,
+        };
+
+        before(&region)?;
+
+        if !self.dry_run {
+            let zeroes = vec![0u8; magic.len()];
+            let mut written = 0;
+
+            while written < zeroes.len() {
+                written += pwrite(
+                    self.reader.io(),
+                    &zeroes[written..],
+                    offset + written as u64,
+                )?;
+            }
+        }
+
+        regions.push(region);
+
+        Ok(())
+    }
+
+    fn sync(&self) -> Result<(), Error<IoError>> {
+        if !self.dry_run {
+            rustix::fs::fsync(self.reader.io())?;
+        }
+
+        Ok(())
+    }
+
+    fn fs_regions(
+        &mut self,
+        info: &FsInfo,
+        regions: &mut Vec<WipedRegion>,
+        before: BeforeWipe,
+    ) -> Result<(), Error<IoError>> {
+        let (Some(fs_type), Some(magic), Some(relative), Some(absolute)) = (
+            info.fs_type(),
+            info.magic(),
+            info.magic_offset(),
+            info.magic_absolute_offset(),
+        ) else {
+            return Err(Error::UnableToLocateMagicSignature);
+        };
+
+        let kind = SignatureType::Filesystem(fs_type);
+        self.wipe_magic(kind, absolute, magic, regions, before)?;
+
+        let base = absolute - relative;
+
+        match fs_type {
+            // LUKS2 keeps a secondary copy of its header, found at one of the
+            // known offsets from the primary.
+            FsType::LUKS2 | FsType::LUKSOpal => {
+                self.wipe_magic(kind, base, &LUKS1_MAGIC, regions, before)?;
+
+                for secondary in SECONDARY_OFFSETS {
+                    self.wipe_magic(kind, base + secondary, &LUKS2_MAGIC, regions, before)?;
+                }
+            }
+            // Erasing only the primary superblock would leave the mirrors to
+            // be picked up in its place.
+            FsType::Btrfs => {
+                for sb_offset in SUPER_OFFSETS {
+                    self.wipe_magic(
+                        kind,
+                        base + sb_offset + MAGIC_OFFSET,
+                        magic,
+                        regions,
+                        before,
+                    )?;
+                }
+            }
+            // Left alone, the boot sector signature would be taken for an
+            // MBR once the FAT magic is gone.
+            FsType::Vfat => {
+                self.wipe_magic(kind, base + 510, &BOOT_SIGNATURE, regions, before)?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn pt_regions(
+        &mut self,
+        info: &PtInfo,
+        regions: &mut Vec<WipedRegion>,
+        before: BeforeWipe,
+    ) -> Result<(), Error<IoError>> {
+        let (Some(pt_type), Some(magic), Some(relative), Some(absolute)) = (
+            info.pt_type(),
+            info.magic(),
+            info.magic_offset(),
+            info.magic_absolute_offset(),
+        ) else {
+            return Err(Error::UnableToLocateMagicSignature);
+        };
+
+        let kind = SignatureType::PartTable(pt_type);
+        self.wipe_magic(kind, absolute, magic, regions, before)?;
+
+        // The GPT header sits in the second sector, the backup header in
+        // the last sector covered by the table, which should also be the
+        // last sector of the device.
+        if pt_type == PtType::Gpt {
+            let base = absolute - relative;
+            let lssz = relative;
+
+            // Left alone, the protective MBR would be picked up once the
+            // GPT headers are gone.
+            self.wipe_magic(kind, base + 510, &BOOT_SIGNATURE, regions, before)?;
+
+            if let Some(size) = info.pt_size() {
+                self.wipe_magic(
+                    kind,
+                    (base + size).saturating_sub(lssz),
+                    magic,
+                    regions,
+                    before,
+                )?;
+            }
+
+            let end = self.reader.device_size()?;
+            self.wipe_magic(kind, end.saturating_sub(lssz), magic, regions, before)?;
+        }
+
+        Ok(())
+    }
+
+    /// Erases the signature of a filesystem found on this device, returning
+    /// the regions erased.
+    pub fn wipe_filesystem(&mut self, info: &FsInfo) -> Result<Vec<WipedRegion>, Error<IoError>> {
+        let mut regions = Vec::new();
+
+        self.fs_regions(info, &mut regions, &mut |_| Ok(()))?;
+        self.sync()?;
+
+        Ok(regions)
+    }
+
+    /// Erases the signature of a partition table found on this device,
+    /// returning the regions erased.
+    pub fn wipe_part_table(&mut self, info: &PtInfo) -> Result<Vec<WipedRegion>, Error<IoError>> {
+        let mut regions = Vec::new();
+
+        self.pt_regions(info, &mut regions, &mut |_| Ok(()))?;
+        self.sync()?;
+
+        Ok(regions)
+    }
+
+    /// Erases every signature found on the device whose type is not skipped
+    /// by the filters, returning the regions erased.
+    ///
+    /// Outside of dry-run mode, the device is probed again after erasing
+    /// until no more signatures are found, as erasing one may uncover
+    /// another.
+    pub fn wipe_all(
+        &mut self,
+        fs_filter: FsFilter,
+        pt_filter: PtFilter,
+    ) -> Result<Vec<WipedRegion>, Error<IoError>> {
+        self.wipe_all_with(fs_filter, pt_filter, |_| Ok(()))
+    }
+
+    /// Like [`Wiper::wipe_all`], but calls `before` with each region before
+    /// it is erased, so its contents can be saved first. Nothing is erased
+    /// once `before` returns an error.
+    pub fn wipe_all_with<F>(
+        &mut self,
+        fs_filter: FsFilter,
+        pt_filter: PtFilter,
+        mut before: F,
+    ) -> Result<Vec<WipedRegion>, Error<IoError>>
+    where
+        F: FnMut(&WipedRegion) -> Result<(), Error<IoError>>,
+    {
+        let mut regions = Vec::new();
+
+        for _ in 0..MAX_WIPE_PASSES {
+            let mut found = Vec::new();
+
+            for (kind, result) in self.probe_all_signatures() {
+                let skip = match kind {
+                    SignatureType::Filesystem(fs_type) => FS_DETECT_ORDER
+                        .iter()
+                        .any(|(flag, t)| *t == fs_type && fs_filter.contains(*flag)),
+                    SignatureType::PartTable(pt_type) => PT_DETECT_ORDER
+                        .iter()
+                        .any(|(flag, t)| *t == pt_type && pt_filter.contains(*flag)),
+                };
+
+                match result {
+                    Ok(signature) if !skip => found.push(signature),
+                    Err(Error::Io(e)) => return Err(Error::Io(e)),
+                    _ => {}
+                }
+            }
+
+            let wiped = regions.len();
+
+            for signature in &found {
+                match signature {
+                    Signature::Filesystem(info) => {
+                        self.fs_regions(info, &mut regions, &mut before)?
+                    }
+                    Signature::PartTable(info) => {
+                        self.pt_regions(info, &mut regions, &mut before)?
+                    }
+                }
+            }
+
+            if self.dry_run || regions.len() == wiped {
+                break;
+            }
+        }
+
+        self.sync()?;
+
+        Ok(regions)
+    }
+}
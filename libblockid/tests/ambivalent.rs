@@ -0,0 +1,142 @@
+//! Probes images carrying more than one filesystem signature with
+//! `ProbeFlags::FailOnAmbivalent`.
+
+#![cfg(all(feature = "std", feature = "os_calls"))]
+
+mod common;
+
+use std::io;
+
+use libblockid::{
+    Probe, ProbeFlags,
+    error::Error,
+    filesystem::{FsFilter, FsInfo, FsType},
+};
+
+use common::{Image, OFFSETS};
+
+fn probe_fs(inner: &Image, offset: u64, flags: ProbeFlags) -> Result<FsInfo, Error<io::Error>> {
+    let img = Image::embed(inner, offset, 4096).write("ambivalent");
+    let mut probe = Probe::open(img.path(), flags, offset).unwrap();
+
+    probe.probe_filesystem(FsFilter::empty())
+}
+
+/// A FAT16 boot sector left behind under a newer ext4 superblock.
+fn stale_vfat() -> Image {
+    let mut img = common::fat16(b"FAT16VOLUME");
+    let ext = common::ext("ext4label", 0x0004, 0x0040);
+
+    img.put(1024, &ext.bytes()[1024..2048]);
+    img
+}
+
+#[test]
+fn first_match_without_flag() {
+    for offset in OFFSETS {
+        let info = probe_fs(&stale_vfat(), offset, ProbeFlags::empty()).unwrap();
+        assert_eq!(info.fs_type(), Some(FsType::Ext4));
+    }
+}
+
+#[test]
+fn stale_signature() {
+    for offset in OFFSETS {
+        match probe_fs(&stale_vfat(), offset, ProbeFlags::FailOnAmbivalent) {
+            Err(Error::Ambivalent(types)) => assert_eq!(types, [FsType::Ext4, FsType::Vfat]),
+            other => panic!("expected ambivalent result at {offset:#x}, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn single_signature() {
+    for offset in OFFSETS {
+        let info = probe_fs(
+            &common::xfs("xfslabel"),
+            offset,
+            ProbeFlags::FailOnAmbivalent,
+        )
+        .unwrap();
+        assert_eq!(info.fs_type(), Some(FsType::Xfs));
+    }
+}
+
+#[test]
+fn compatible_signatures() {
+    for offset in OFFSETS {
+        // ext2 and ext4 both accept a superblock with only META_BG set.
+        let info = probe_fs(
+            &common::ext("metabg", 0, 0x0010),
+            offset,
+            ProbeFlags::FailOnAmbivalent,
+        )
+        .unwrap();
+        assert_eq!(info.fs_type(), Some(FsType::Ext2));
+
+        // An Opal header is a LUKS2 header too.
+        let info = probe_fs(
+            &common::luks2(b"HW-OPAL"),
+            offset,
+            ProbeFlags::FailOnAmbivalent,
+        )
+        .unwrap();
+        assert_eq!(info.fs_type(), Some(FsType::LUKS2));
+    }
+}
+
+#[test]
+fn udf_bridge() {
+    for offset in OFFSETS {
+        let info = probe_fs(
+            &common::udf("UDF Label", true),
+            offset,
+            ProbeFlags::FailOnAmbivalent,
+        )
+        .unwrap();
+        assert_eq!(info.fs_type(), Some(FsType::Udf));
+    }
+}
+
+#[test]
+fn bitlocker_to_go() {
+    // The header of a BitLocker To Go volume is a valid FAT32 boot sector.
+    for offset in OFFSETS {
+        let info = probe_fs(
+            &common::bitlocker_togo(),
+            offset,
+            ProbeFlags::FailOnAmbivalent,
+        )
+        .unwrap();
+        assert_eq!(info.fs_type(), Some(FsType::Bitlocker));
+    }
+}
+
+#[test]
+fn nothing_found() {
+    let img = Image::new(1 << 20);
+
+    assert!(matches!(
+        probe_fs(&img, 0, ProbeFlags::FailOnAmbivalent),
+        Err(Error::ProbesExhausted)
+    ));
+}
+
+#[test]
+fn raid_member() {
+    // A mirror member with its superblock at the end shows the filesystem of
+    // the array at its start.
+    let mut member = common::md1(0, "host:md0");
+    member.put(
+        0,
+        &common::ext("ext4label", 0x0004, 0x0040).bytes()[..64 << 10],
+    );
+
+    for offset in OFFSETS {
+        let img = Image::embed(&member, offset, 0).write("ambivalent");
+        let mut probe = Probe::open(img.path(), ProbeFlags::FailOnAmbivalent, offset).unwrap();
+
+        let info = probe.probe_filesystem(FsFilter::empty()).unwrap();
+        assert_eq!(info.fs_type(), Some(FsType::LinuxRaidMember));
+    }
+}
@@ -0,0 +1,1618 @@
+#![allow(dead_code)]
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crc::{Algorithm, CRC_32_ISCSI, CRC_32_ISO_HDLC, CRC_64_ECMA_182, CRC_64_WE, Crc};
+
+/// Probe offsets every format is placed at inside a larger image.
+pub const OFFSETS: [u64; 3] = [0x10000, 0x100000, 0x700200];
+
+/// Byte buffer holding a synthetic on-disk structure.
+#[derive(Debug, Clone)]
+pub struct Image(Vec<u8>);
+
+impl Image {
+    pub fn new(size: usize) -> Self {
+        Self(vec![0u8; size])
+    }
+
+    pub fn len(&self) -> u64 {
+        self.0.len() as u64
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn bytes_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+
+    pub fn put(&mut self, at: u64, bytes: &[u8]) -> &mut Self {
+        let at = at as usize;
+        self.0[at..at + bytes.len()].copy_from_slice(bytes);
+        self
+    }
+
+    pub fn put_u8(&mut self, at: u64, v: u8) -> &mut Self {
+        self.put(at, &[v])
+    }
+
+    pub fn put_le16(&mut self, at: u64, v: u16) -> &mut Self {
+        self.put(at, &v.to_le_bytes())
+    }
+
+    pub fn put_le32(&mut self, at: u64, v: u32) -> &mut Self {
+        self.put(at, &v.to_le_bytes())
+    }
+
+    pub fn put_le64(&mut self, at: u64, v: u64) -> &mut Self {
+        self.put(at, &v.to_le_bytes())
+    }
+
+    pub fn put_be16(&mut self, at: u64, v: u16) -> &mut Self {
+        self.put(at, &v.to_be_bytes())
+    }
+
+    pub fn put_be32(&mut self, at: u64, v: u32) -> &mut Self {
+        self.put(at, &v.to_be_bytes())
+    }
+
+    pub fn put_be64(&mut self, at: u64, v: u64) -> &mut Self {
+        self.put(at, &v.to_be_bytes())
+    }
+
+    /// Places `inner` at `offset` inside a zeroed image that is `slack` bytes
+    /// larger than needed.
+    pub fn embed(inner: &Image, offset: u64, slack: u64) -> Image {
+        let mut outer = Image::new((offset + inner.len() + slack) as usize);
+        outer.put(offset, inner.bytes());
+        outer
+    }
+
+    /// Writes the image to a temporary file which is removed on drop.
+    pub fn write(&self, name: &str) -> TempImage {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let path = std::env::temp_dir().join(format!(
+            "libblockid-{}-{}-{}.img",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed),
+            name
+        ));
+
+        fs::write(&path, &self.0).expect("failed to write test image");
+
+        TempImage(path)
+    }
+}
+
+#[derive(Debug)]
+pub struct TempImage(PathBuf);
+
+impl TempImage {
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempImage {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+pub fn utf16le(s: &str) -> Vec<u8> {
+    s.encode_utf16().flat_map(u16::to_le_bytes).collect()
+}
+
+pub fn crc32(bytes: &[u8]) -> u32 {
+    Crc::<u32>::new(&CRC_32_ISO_HDLC).checksum(bytes)
+}
+
+pub fn crc32c(bytes: &[u8]) -> u32 {
+    Crc::<u32>::new(&CRC_32_ISCSI).checksum(bytes)
+}
+
+pub const UUID: [u8; 16] = [
+    0x6f, 0x1d, 0x5a, 0x3c, 0x2b, 0x4e, 0x4c, 0x8a, 0x9d, 0x10, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77,
+];
+pub const UUID_STR: &str = "6f1d5a3c-2b4e-4c8a-9d10-223344556677";
+
+/// ext2/3/4 superblock, `compat`/`incompat` select the variant.
+pub fn ext(label: &str, compat: u32, incompat: u32) -> Image {
+    let mut img = Image::new(1 << 20);
+    let sb = 1024;
+
+    img.put_le32(sb, 128) // s_inodes_count
+        .put_le32(sb + 0x04, 1024) // s_blocks_count_lo
+        .put_le32(sb + 0x14, 0) // s_first_data_block
+        .put_le32(sb + 0x18, 0) // s_log_block_size (1 KiB blocks)
+        .put(sb + 0x38, &[0x53, 0xEF])
+        .put_le32(sb + 0x4C, 1) // s_rev_level
+        .put_le32(sb + 0x5C, compat)
+        .put_le32(sb + 0x60, incompat)
+        .put(sb + 0x68, &UUID)
+        .put(sb + 0x78, label.as_bytes());
+
+    img
+}
+
+/// FAT16 with a volume label stored in the root directory.
+pub fn fat16(label: &[u8; 11]) -> Image {
+    let mut img = Image::new(4 << 20);
+
+    img.put(0, &[0xEB, 0x3C, 0x90])
+        .put(3, b"MSWIN4.1")
+        .put_le16(11, 512) // sector size
+        .put_u8(13, 4) // sectors per cluster
+        .put_le16(14, 1) // reserved sectors
+        .put_u8(16, 2) // fats
+        .put_le16(17, 512) // root dir entries
+        .put_le16(19, 8192) // sectors
+        .put_u8(21, 0xF8)
+        .put_le16(22, 8) // fat length
+        .put_u8(38, 0x29)
+        .put(39, &[0x78, 0x56, 0x34, 0x12])
+        .put(43, b"NO NAME    ")
+        .put(54, b"FAT16   ")
+        .put(510, &[0x55, 0xAA]);
+
+    // Root directory starts after the reserved sector and both FATs.
+    let root = (1 + 2 * 8) * 512;
+    img.put(root, label).put_u8(root + 11, 0x08);
+
+    img
+}
+
+/// FAT32 with FSInfo and a volume label stored in the root cluster.
+pub fn fat32(label: &[u8; 11]) -> Image {
+    let mut img = Image::new(4 << 20);
+
+    img.put(0, &[0xEB, 0x58, 0x90])
+        .put(3, b"MSWIN4.1")
+        .put_le16(11, 512)
+        .put_u8(13, 1)
+        .put_le16(14, 32)
+        .put_u8(16, 2)
+        .put_le16(17, 0)
+        .put_le16(19, 0)
+        .put_u8(21, 0xF8)
+        .put_le16(22, 0)
+        .put_le32(32, 8192) // total sectors
+        .put_le32(36, 64) // fat32 length
+        .put_le32(44, 2) // root cluster
+        .put_le16(48, 1) // fsinfo sector
+        .put_u8(66, 0x29)
+        .put(67, &[0xEF, 0xBE, 0xAD, 0xDE])
+        .put(71, b"NO NAME    ")
+        .put(82, b"FAT32   ")
+        .put(510, &[0x55, 0xAA]);
+
+    img.put(512, b"RRaA").put(512 + 484, b"rrAa");
+
+    // End of chain for the root cluster.
+    img.put_le32(32 * 512 + 2 * 4, 0x0FFFFFFF);
+
+    let root = (32 + 2 * 64) * 512;
+    img.put(root, label).put_u8(root + 11, 0x08);
+
+    img
+}
+
+/// NTFS with a `$Volume` MFT record holding the volume name.
+pub fn ntfs(label: &str) -> Image {
+    let mut img = Image::new(4 << 20);
+    let cluster = 8 * 512;
+    let mft = 4 * cluster;
+    let record = 1024;
+
+    img.put(0, &[0xEB, 0x52, 0x90])
+        .put(3, b"NTFS    ")
+        .put_le16(11, 512)
+        .put_u8(13, 8)
+        .put_u8(21, 0xF8)
+        .put_le64(40, 8192) // number of sectors
+        .put_le64(48, 4) // MFT cluster
+        .put_le64(56, 8) // MFT mirror cluster
+        .put_u8(64, 0xF6) // 2^10 byte MFT records
+        .put_u8(68, 0x01)
+        .put(72, &[0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88])
+        .put(510, &[0x55, 0xAA]);
+
+    img.put(mft, b"FILE");
+
+    let vol = mft + 3 * record;
+    let name = utf16le(label);
+    let attr = vol + 56;
+    let attr_len = (24 + name.len() as u32 + 7) & !7;
+
+    img.put(vol, b"FILE")
+        .put_le16(vol + 20, 56) // attrs offset
+        .put_le32(vol + 24, 56 + attr_len + 8) // bytes in use
+        .put_le32(vol + 28, record as u32) // bytes allocated
+        .put_le32(attr, 0x60)
+        .put_le32(attr + 4, attr_len)
+        .put_le32(attr + 16, name.len() as u32)
+        .put_le16(attr + 20, 24)
+        .put(attr + 24, &name)
+        .put_le32(attr + attr_len as u64, 0xFFFFFFFF);
+
+    img
+}
+
+fn exfat_checksum(sectors: &[u8]) -> u32 {
+    let mut checksum: u32 = 0;
+
+    for (i, byte) in sectors.iter().enumerate() {
+        if i == 106 || i == 107 || i == 112 {
+            continue;
+        }
+        checksum = checksum.rotate_right(1).wrapping_add(u32::from(*byte));
+    }
+
+    checksum
+}
+
+/// exFAT with a checksummed boot region and a label entry in the root
+/// directory.
+pub fn exfat(label: &str) -> Image {
+    let mut img = Image::new(4 << 20);
+
+    img.put(0, &[0xEB, 0x76, 0x90])
+        .put(3, b"EXFAT   ")
+        .put_le64(72, 8192) // volume length
+        .put_le32(80, 24) // fat offset
+        .put_le32(84, 8) // fat length
+        .put_le32(88, 32) // cluster heap offset
+        .put_le32(92, 1000) // cluster count
+        .put_le32(96, 2) // root cluster
+        .put(100, &[0x01, 0x02, 0x03, 0x04])
+        .put_u8(104, 0)
+        .put_u8(105, 1)
+        .put_u8(108, 9) // bytes per sector shift
+        .put_u8(109, 3) // sectors per cluster shift
+        .put_u8(110, 1) // number of fats
+        .put(510, &[0x55, 0xAA]);
+
+    let checksum = exfat_checksum(&img.bytes()[..11 * 512]);
+    for i in 0..128 {
+        img.put_le32(11 * 512 + i * 4, checksum);
+    }
+
+    // Root cluster ends its chain.
+    img.put_le32(24 * 512 + 2 * 4, 0xFFFFFFFF);
+
+    let name = utf16le(label);
+    let root = 32 * 512;
+    img.put_u8(root, 0x83)
+        .put_u8(root + 1, label.chars().count() as u8)
+        .put(root + 2, &name);
+
+    img
+}
+
+/// cramfs version 2 of 96 KiB, covering more than one checksum chunk.
+pub fn cramfs(label: &str, little_endian: bool) -> Image {
+    let mut img = Image::new(96 << 10);
+    let put32 = |img: &mut Image, at, v: u32| {
+        if little_endian {
+            img.put_le32(at, v);
+        } else {
+            img.put_be32(at, v);
+        }
+    };
+
+    put32(&mut img, 0, 0x28CD3D45);
+    put32(&mut img, 4, 96 << 10); // size
+    put32(&mut img, 8, 0x3); // FSID_VERSION_2 | SORTED_DIRS
+    img.put(16, b"Compressed ROMFS");
+    put32(&mut img, 40, 1); // blocks
+    put32(&mut img, 44, 1); // files
+    img.put(48, label.as_bytes());
+
+    for i in (4096..img.len()).step_by(4096) {
+        img.put_u8(i, i as u8 ^ 0x5A);
+    }
+
+    let crc = crc32(img.bytes());
+    put32(&mut img, 32, crc);
+
+    img
+}
+
+/// VxFS version 6 superblock, at 1024 when little-endian or 8192 when
+/// big-endian.
+pub fn vxfs(little_endian: bool) -> Image {
+    let mut img = Image::new(16 << 10);
+    let sb = if little_endian { 1024 } else { 8192 };
+    let put32 = |img: &mut Image, at, v: u32| {
+        if little_endian {
+            img.put_le32(at, v);
+        } else {
+            img.put_be32(at, v);
+        }
+    };
+
+    put32(&mut img, sb, 0xA501FCF5);
+    put32(&mut img, sb + 4, 6); // version
+    put32(&mut img, sb + 32, 1024); // bsize
+    put32(&mut img, sb + 36, 2048); // size
+    put32(&mut img, sb + 40, 2048); // dsize
+
+    img
+}
+
+/// squashfs version 4 superblock, xz compressed.
+pub fn squashfs() -> Image {
+    let mut img = Image::new(8 << 10);
+
+    img.put(0, b"hsqs")
+        .put_le32(4, 1) // inodes
+        .put_le32(12, 128 << 10) // block_size
+        .put_le16(20, 4) // compression
+        .put_le16(22, 17) // block_log
+        .put_le16(28, 4) // s_major
+        .put_le16(40, 8 << 10); // bytes_used
+
+    img
+}
+
+/// squashfs version 3.1 superblock, created on a big-endian machine.
+pub fn squashfs3_big_endian() -> Image {
+    let mut img = Image::new(8 << 10);
+
+    img.put(0, b"sqsh")
+        .put_be32(4, 1) // inodes
+        .put_be16(28, 3) // s_major
+        .put_be16(30, 1) // s_minor
+        .put_be16(34, 16) // block_log
+        .put_be32(51, 64 << 10) // block_size
+        .put_be64(63, 8 << 10); // bytes_used
+
+    img
+}
+
+/// EROFS superblock with 4 KiB blocks and a superblock checksum.
+pub fn erofs(label: &str) -> Image {
+    let mut img = Image::new(8 << 10);
+    let sb = 1024;
+
+    img.put_le32(sb, 0xE0F5E1E2)
+        .put_le32(sb + 8, 0x1) // feature_compat: SB_CHKSUM
+        .put_u8(sb + 12, 12) // blkszbits
+        .put_le32(sb + 36, 2) // blocks
+        .put(sb + 48, &UUID)
+        .put(sb + 64, label.as_bytes());
+
+    let crc = !crc32c(&img.bytes()[1024..4096]);
+    img.put_le32(sb + 4, crc);
+
+    img
+}
+
+/// btrfs superblock copy at `sb`, checksummed with crc32c.
+pub fn btrfs_super(img: &mut Image, sb: u64, label: &str) {
+    img.put(sb + 0x20, &UUID) // fsid
+        .put_le64(sb + 0x30, sb) // bytenr
+        .put(sb + 0x40, b"_BHRfS_M")
+        .put_le64(sb + 0x48, 7) // generation
+        .put_le64(sb + 0x70, 256 << 20) // total_bytes
+        .put_le64(sb + 0x88, 2) // num_devices
+        .put_le32(sb + 0x90, 4096) // sectorsize
+        .put_le32(sb + 0x94, 16384) // nodesize
+        .put_le64(sb + 0xc9, 1) // dev_item.devid
+        .put(sb + 0xc9 + 0x42, &DEV_UUID) // dev_item.uuid
+        .put(sb + 0x12b, label.as_bytes());
+
+    let range = (sb + 0x20) as usize..(sb + 0x1000) as usize;
+    let crc = crc32c(&img.bytes()[range]);
+    img.put_le32(sb, crc);
+}
+
+pub const DEV_UUID: [u8; 16] = [
+    0x1c, 0x2d, 0x3e, 0x4f, 0x50, 0x61, 0x42, 0x83, 0x94, 0xa5, 0xb6, 0xc7, 0xd8, 0xe9, 0xfa, 0x0b,
+];
+pub const DEV_UUID_STR: &str = "1c2d3e4f-5061-4283-94a5-b6c7d8e9fa0b";
+
+/// btrfs filesystem with only the primary superblock.
+pub fn btrfs(label: &str) -> Image {
+    let mut img = Image::new(0x11000);
+    btrfs_super(&mut img, 0x10000, label);
+    img
+}
+
+/// Version 1 swap area of 16 pages of `page_size`.
+pub fn swap(label: &str, page_size: u64, big_endian: bool) -> Image {
+    let mut img = Image::new(16 * page_size as usize);
+
+    if big_endian {
+        img.put_be32(1024, 1).put_be32(1028, 15);
+    } else {
+        img.put_le32(1024, 1).put_le32(1028, 15);
+    }
+    img.put(1036, &UUID)
+        .put(1052, label.as_bytes())
+        .put(page_size - 10, b"SWAPSPACE2");
+
+    img
+}
+
+/// crc32 as used by f2fs, seeded with the magic and not inverted.
+fn f2fs_crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xF2F52010u32;
+
+    for b in bytes {
+        crc ^= u32::from(*b);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ if crc & 1 != 0 { 0xEDB88320 } else { 0 };
+        }
+    }
+
+    crc
+}
+
+/// f2fs superblock with 4 KiB blocks, 2 segments and a superblock checksum.
+pub fn f2fs(label: &str) -> Image {
+    let mut img = Image::new(8 << 10);
+    let sb = 1024;
+
+    img.put_le32(sb, 0xF2F52010)
+        .put_le16(sb + 4, 1) // major_ver
+        .put_le16(sb + 6, 16) // minor_ver
+        .put_le32(sb + 8, 9) // log_sectorsize
+        .put_le32(sb + 12, 3) // log_sectors_per_block
+        .put_le32(sb + 16, 12) // log_blocksize
+        .put_le32(sb + 20, 9) // log_blocks_per_seg
+        .put_le32(sb + 32, 3068) // checksum_offset
+        .put_le64(sb + 36, 1024) // block_count
+        .put_le32(sb + 48, 2) // segment_count
+        .put(sb + 108, &UUID)
+        .put(sb + 124, &utf16le(label))
+        .put_le32(sb + 2180, 0x0800); // feature: SB_CHKSUM
+
+    let crc = f2fs_crc32(&img.bytes()[1024..1024 + 3068]);
+    img.put_le32(sb + 3068, crc);
+
+    img
+}
+
+/// bcachefs superblock for member 1 of 2, with a crc32c checksum and a
+/// members field.
+pub fn bcachefs(label: &str) -> Image {
+    bcachefs_csum(label, 1)
+}
+
+/// bcachefs superblock as [`bcachefs`], checksummed with `csum_type`: 1 and 2
+/// for the nonzero crc32c and crc64, 5 and 6 for the zero seeded ones.
+pub fn bcachefs_csum(label: &str, csum_type: u64) -> Image {
+    let mut img = Image::new(16 << 10);
+    let sb = 4096;
+    let fields = sb + 752;
+
+    img.put_le16(sb + 16, (1 << 10) | 7) // version
+        .put_le16(sb + 18, 9) // version_min
+        .put(
+            sb + 24,
+            b"\xc6\x85\x73\xf6\x66\xce\x90\xa9\xd9\x6a\x60\xcf\x80\x3d\xf7\xef",
+        )
+        .put(sb + 40, &[0x11; 16]) // uuid
+        .put(sb + 56, &UUID) // user_uuid
+        .put(sb + 72, label.as_bytes())
+        .put_le64(sb + 104, 8) // offset
+        .put_le16(sb + 120, 8) // block_size
+        .put_u8(sb + 122, 1) // dev_idx
+        .put_u8(sb + 123, 2) // nr_devices
+        .put_le32(sb + 124, 18) // u64s
+        .put_le64(sb + 144, csum_type << 2) // flags[0]: csum_type
+        .put_u8(sb + 240 + 17, 7); // layout.sb_max_size_bits
+
+    // members_v2 field holding two 64 byte members.
+    img.put_le32(fields, 18)
+        .put_le32(fields + 4, 11)
+        .put_le16(fields + 8, 64);
+    img.put(fields + 16, &[0x22; 16])
+        .put(fields + 80, &DEV_UUID)
+        .put_le64(fields + 80 + 16, 1024) // nbuckets
+        .put_le16(fields + 80 + 26, 256); // bucket_size
+
+    const CRC_32C_ZERO: Algorithm<u32> = Algorithm {
+        init: 0,
+        xorout: 0,
+        ..CRC_32_ISCSI
+    };
+
+    let data = &img.bytes()[(sb + 16) as usize..(fields + 144) as usize];
+    let csum = match csum_type {
+        1 => u64::from(crc32c(data)),
+        2 => Crc::<u64>::new(&CRC_64_WE).checksum(data),
+        5 => u64::from(Crc::<u32>::new(&CRC_32C_ZERO).checksum(data)),
+        6 => Crc::<u64>::new(&CRC_64_ECMA_182).checksum(data),
+        _ => 0,
+    };
+    img.put_le64(sb, csum);
+
+    img
+}
+
+pub const HFS_VOLUME_ID: [u8; 8] = [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef];
+
+/// Classic HFS volume of 16 4 KiB allocation blocks.
+pub fn hfs(label: &str) -> Image {
+    let mut img = Image::new(72 << 10);
+    let mdb = 1024;
+
+    img.put(mdb, b"BD")
+        .put_be16(mdb + 18, 16) // nm_al_blks
+        .put_be32(mdb + 20, 4096) // al_blk_size
+        .put_be16(mdb + 28, 4) // al_bl_st
+        .put_u8(mdb + 36, label.len() as u8)
+        .put(mdb + 37, label.as_bytes())
+        .put(mdb + 116, &HFS_VOLUME_ID);
+
+    img
+}
+
+/// HFS+ or HFSX volume of 16 4 KiB blocks, with a catalog B-tree of 4 KiB
+/// nodes in blocks 4 to 7 whose first leaf holds the root folder.
+pub fn hfsplus(label: &str, hfsx: bool) -> Image {
+    let mut img = Image::new(64 << 10);
+    let vh = 1024;
+
+    if hfsx {
+        img.put(vh, b"HX").put_be16(vh + 2, 5);
+    } else {
+        img.put(vh, b"H+").put_be16(vh + 2, 4);
+    }
+    img.put_be32(vh + 40, 4096) // blocksize
+        .put_be32(vh + 44, 16) // total_blocks
+        .put(vh + 104, &HFS_VOLUME_ID)
+        .put_be64(vh + 272, 4 * 4096) // cat_file.total_size
+        .put_be32(vh + 284, 4) // cat_file.total_blocks
+        .put_be32(vh + 288, 4) // cat_file.extents[0].start_block
+        .put_be32(vh + 292, 4); // cat_file.extents[0].block_count
+
+    let header = 4 * 4096;
+    img.put_u8(header + 8, 1) // kind: header node
+        .put_be16(header + 10, 3)
+        .put_be32(header + 14 + 10, 1) // first_leaf
+        .put_be16(header + 14 + 18, 4096); // node_size
+
+    let name: Vec<u8> = label.encode_utf16().flat_map(u16::to_be_bytes).collect();
+    let leaf = 5 * 4096;
+    img.put_u8(leaf + 8, 0xFF) // kind: leaf node
+        .put_be16(leaf + 10, 1) // num_recs
+        .put_be16(leaf + 14, 6 + name.len() as u16) // key_len
+        .put_be32(leaf + 16, 1) // parent_id
+        .put_be16(leaf + 20, name.len() as u16 / 2)
+        .put(leaf + 22, &name);
+
+    img
+}
+
+/// HFS wrapper around an embedded HFS+ volume starting at its first
+/// allocation block.
+pub fn hfs_wrapper(label: &str) -> Image {
+    let mut img = hfs("Wrapper");
+
+    img.put(1024 + 124, b"H+")
+        .put_be16(1024 + 126, 1) // embed_startblock
+        .put_be16(1024 + 128, 16) // embed_blockcount
+        .put(4 * 512 + 4096, hfsplus(label, false).bytes());
+
+    img
+}
+
+/// Space padded ISO9660 `a`/`d`-characters.
+fn iso_str(s: &str, len: usize) -> Vec<u8> {
+    let mut v = s.as_bytes().to_vec();
+    v.resize(len, b' ');
+    v
+}
+
+fn iso_descriptor(img: &mut Image, at: u64, vd_type: u8) {
+    img.put_u8(at, vd_type)
+        .put(at + 1, b"CD001")
+        .put_u8(at + 6, 1);
+}
+
+/// ISO9660 primary volume descriptor at `at` for a 32 block volume.
+fn iso9660_pvd(img: &mut Image, at: u64, label: &str) {
+    iso_descriptor(img, at, 1);
+    img.put(at + 8, &iso_str("LINUX", 32)) // system_id
+        .put(at + 40, &iso_str(label, 32)) // volume_id
+        .put_le32(at + 80, 32) // space_size
+        .put_be32(at + 84, 32)
+        .put_le16(at + 128, 2048) // logical_block_size
+        .put_be16(at + 130, 2048)
+        .put(at + 318, &iso_str("PUBLISHER", 128))
+        .put(at + 574, &iso_str("MKISOFS", 128)) // application_id
+        .put(at + 813, b"2024010212345600") // created
+        .put(at + 830, b"0000000000000000"); // modified
+}
+
+/// reiserfs with 4 KiB blocks, `magic` picks the format and where the
+/// superblock is placed.
+pub fn reiserfs(label: &str, magic: &[u8]) -> Image {
+    let sb = if magic == b"ReIsErFs" {
+        8 << 10
+    } else {
+        64 << 10
+    };
+    let mut img = Image::new(128 << 10);
+
+    img.put_le32(sb, 32) // block_count
+        .put_le32(sb + 12, 18) // journal_block
+        .put_le16(sb + 44, 4096) // blocksize
+        .put(sb + 52, magic)
+        .put(sb + 84, &UUID)
+        .put(sb + 100, label.as_bytes());
+
+    img
+}
+
+/// JFS of 1 MiB with 4 KiB blocks on 512 byte sectors.
+pub fn jfs(label: &str) -> Image {
+    let sb = 32 << 10;
+    let mut img = Image::new(64 << 10);
+
+    img.put(sb, b"JFS1")
+        .put_le32(sb + 4, 2) // version
+        .put_le64(sb + 8, 2048) // size in sectors
+        .put_le32(sb + 16, 4096) // bsize
+        .put_le16(sb + 20, 12) // l2bsize
+        .put_le16(sb + 22, 3) // l2bfactor
+        .put_le32(sb + 24, 512) // pbsize
+        .put_le16(sb + 28, 9) // l2pbsize
+        .put_le32(sb + 36, 0x800) // flag: inline log
+        .put(sb + 136, &UUID)
+        .put(sb + 152, label.as_bytes());
+
+    img
+}
+
+/// Checksums a nilfs2 superblock copy at `sb`, crc32 seeded with its
+/// `crc_seed` and without the usual inversions.
+fn nilfs2_csum(img: &mut Image, sb: u64) {
+    let seed = u32::from_le_bytes(img.bytes()[sb as usize + 12..][..4].try_into().unwrap());
+    let crc = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+    let mut digest = crc.digest_with_initial(seed.reverse_bits());
+
+    img.put_le32(sb + 16, 0);
+    digest.update(&img.bytes()[sb as usize..][..1024]);
+    let sum = !digest.finalize();
+    img.put_le32(sb + 16, sum);
+}
+
+/// nilfs2 of 128 KiB with 4 KiB blocks, with the secondary superblock in
+/// the last 4 KiB.
+pub fn nilfs2(label: &str) -> Image {
+    let mut img = Image::new(128 << 10);
+
+    for (sb, cno) in [(1024, 7), ((124 << 10), 5)] {
+        img.put_le32(sb, 2) // rev_level
+            .put(sb + 6, &[0x34, 0x34])
+            .put_le16(sb + 8, 1024) // bytes
+            .put_le32(sb + 12, 0x12345678) // crc_seed
+            .put_le32(sb + 20, 2) // log_block_size
+            .put_le64(sb + 32, 128 << 10) // dev_size
+            .put_le64(sb + 56, cno) // last_cno
+            .put(sb + 152, &UUID)
+            .put(sb + 168, label.as_bytes());
+        nilfs2_csum(&mut img, sb);
+    }
+
+    img
+}
+
+/// Minix filesystem of 64 blocks, `version` 1, 2 or 3.
+pub fn minix(version: u8, big_endian: bool) -> Image {
+    let mut img = Image::new(64 << 10);
+    let sb = 1024;
+
+    let put16 = |img: &mut Image, at: u64, v: u16| {
+        let bytes = if big_endian {
+            v.to_be_bytes()
+        } else {
+            v.to_le_bytes()
+        };
+        img.put(sb + at, &bytes);
+    };
+    let put32 = |img: &mut Image, at: u64, v: u32| {
+        let bytes = if big_endian {
+            v.to_be_bytes()
+        } else {
+            v.to_le_bytes()
+        };
+        img.put(sb + at, &bytes);
+    };
+
+    if version == 3 {
+        put32(&mut img, 0, 32); // ninodes
+        put16(&mut img, 6, 1); // imap_blocks
+        put16(&mut img, 8, 1); // zmap_blocks
+        put16(&mut img, 10, 8); // firstdatazone
+        put32(&mut img, 20, 64); // zones
+        put16(&mut img, 24, 0x4D5A);
+        put16(&mut img, 28, 1024); // blocksize
+    } else {
+        put16(&mut img, 0, 32); // ninodes
+        put16(&mut img, 2, 64); // nzones
+        put16(&mut img, 4, 1); // imap_blocks
+        put16(&mut img, 6, 1); // zmap_blocks
+        put16(&mut img, 8, 8); // firstdatazone
+        put16(&mut img, 16, if version == 2 { 0x2478 } else { 0x138F });
+        put16(&mut img, 18, 1); // state: valid
+        put32(&mut img, 20, 64); // zones
+    }
+
+    img
+}
+
+pub const UFS_ID: [u32; 2] = [0x65a1b2c3, 0x1d2e3f40];
+
+/// UFS1 or UFS2 filesystem of 64 2 KiB fragments, the superblock at 8 KiB or
+/// 64 KiB respectively.
+pub fn ufs(label: &str, ufs2: bool, big_endian: bool) -> Image {
+    let mut img = Image::new(128 << 10);
+    let sb = if ufs2 { 64 << 10 } else { 8 << 10 };
+
+    let put32 = |img: &mut Image, at: u64, v: u32| {
+        let bytes = if big_endian {
+            v.to_be_bytes()
+        } else {
+            v.to_le_bytes()
+        };
+        img.put(sb + at, &bytes);
+    };
+
+    put32(&mut img, 48, 16384); // bsize
+    put32(&mut img, 52, 2048); // fsize
+    put32(&mut img, 144, UFS_ID[0]);
+    put32(&mut img, 148, UFS_ID[1]);
+    if ufs2 {
+        let size = if big_endian {
+            64u64.to_be_bytes()
+        } else {
+            64u64.to_le_bytes()
+        };
+        img.put(sb + 680, label.as_bytes()).put(sb + 1080, &size);
+        put32(&mut img, 1372, 0x19540119);
+    } else {
+        put32(&mut img, 36, 64); // old_size
+        put32(&mut img, 1372, 0x00011954);
+    }
+
+    img
+}
+
+fn md_csum(bytes: &[u8], big_endian: bool) -> u32 {
+    let sum: u64 = bytes
+        .chunks_exact(4)
+        .map(|w| {
+            let w = [w[0], w[1], w[2], w[3]];
+            u64::from(if big_endian {
+                u32::from_be_bytes(w)
+            } else {
+                u32::from_le_bytes(w)
+            })
+        })
+        .sum();
+
+    (sum as u32).wrapping_add((sum >> 32) as u32)
+}
+
+/// 1 MiB member of a Linux MD array with a 1.x superblock, `minor` picking
+/// its location.
+pub fn md1(minor: u32, name: &str) -> Image {
+    let mut img = Image::new(1 << 20);
+    let sb = match minor {
+        0 => (((img.len() >> 9) - 16) & !7) << 9,
+        1 => 0,
+        _ => 4096,
+    };
+
+    img.put_le32(sb, 0xa92b4efc)
+        .put_le32(sb + 4, 1)
+        .put(sb + 16, &UUID)
+        .put(sb + 32, name.as_bytes())
+        .put_le64(sb + 144, sb >> 9)
+        .put(sb + 168, &DEV_UUID)
+        .put_le32(sb + 220, 2); // max_dev
+
+    let csum = md_csum(&img.bytes()[sb as usize..sb as usize + 256 + 4], false);
+    img.put_le32(sb + 216, csum);
+
+    img
+}
+
+/// 1 MiB member of a Linux MD array with a 0.90 superblock in the last 64
+/// KiB.
+pub fn md0(big_endian: bool) -> Image {
+    let mut img = Image::new(1 << 20);
+    let sb = img.len() - (64 << 10);
+
+    let words: [(u64, u32); 7] = [
+        (0, 0xa92b4efc),
+        (2, 90),         // minor_version
+        (5, 0x6f1d5a3c), // set_uuid0
+        (13, 0x2b4e4c8a),
+        (14, 0x9d102233),
+        (15, 0x44556677),
+        (10, 2), // raid_disks
+    ];
+    for (i, v) in words {
+        if big_endian {
+            img.put_be32(sb + i * 4, v);
+        } else {
+            img.put_le32(sb + i * 4, v);
+        }
+    }
+
+    let csum = md_csum(&img.bytes()[sb as usize..sb as usize + 4096], big_endian);
+    if big_endian {
+        img.put_be32(sb + 152, csum);
+    } else {
+        img.put_le32(sb + 152, csum);
+    }
+
+    img
+}
+
+/// crc32 seeded with LVM's initial value, without the usual inversions.
+fn lvm_crc(bytes: &[u8]) -> u32 {
+    let crc = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+    let mut digest = crc.digest_with_initial(0xf597a6cf_u32.reverse_bits());
+    digest.update(bytes);
+    !digest.finalize()
+}
+
+pub const LVM_PV_UUID: &[u8; 32] = b"aB3dE6fG8hIj0KlM2nOp4QrS6tUv8wXy";
+pub const LVM_PV_UUID_STR: &str = "aB3dE6-fG8h-Ij0K-lM2n-Op4Q-rS6t-Uv8wXy";
+
+/// 1 MiB LVM2 physical volume labelled in the second sector, with a
+/// metadata area at 4 KiB holding the text of volume group `vg`.
+pub fn lvm2(vg: &str) -> Image {
+    let mut img = Image::new(1 << 20);
+    let (label, mda) = (512, 4096);
+
+    img.put(label, b"LABELONE")
+        .put_le64(label + 8, 1) // sector_xl
+        .put_le32(label + 20, 32) // offset_xl
+        .put(label + 24, b"LVM2 001")
+        .put(label + 32, LVM_PV_UUID)
+        .put_le64(label + 64, 1 << 20) // device_size_xl
+        .put_le64(label + 72, 64 << 10) // data area, then the end of the list
+        .put_le64(label + 104, mda) // metadata area
+        .put_le64(label + 112, 60 << 10);
+    let crc = lvm_crc(&img.bytes()[label as usize + 20..][..512 - 20]);
+    img.put_le32(label + 16, crc);
+
+    let text = format!("{vg} {{\nid = \"xxxxxx\"\nseqno = 1\n}}\n");
+    img.put(mda + 4, b" LVM2 x[5A%r0N*>")
+        .put_le32(mda + 20, 1) // version
+        .put_le64(mda + 24, mda) // start
+        .put_le64(mda + 32, 60 << 10) // size
+        .put_le64(mda + 40, 512) // raw_locns[0]
+        .put_le64(mda + 48, text.len() as u64)
+        .put(mda + 512, text.as_bytes());
+    let crc = lvm_crc(&img.bytes()[mda as usize + 4..][..512 - 4]);
+    img.put_le32(mda, crc);
+
+    img
+}
+
+/// Offset of the sector `n` back from the end of `img`, the last being 1.
+fn sector_from_end(img: &Image, n: u64) -> u64 {
+    img.len() - n * 512
+}
+
+pub const ISW_FAMILY_NUM: u32 = 0x5eed1234;
+
+/// 1 MiB Intel Matrix Storage Manager member, with 736 bytes of metadata
+/// continuing from the anchor into the sector in front of it.
+pub fn isw() -> Image {
+    let mut img = Image::new(1 << 20);
+    let (sb, ext) = (sector_from_end(&img, 2), sector_from_end(&img, 3));
+
+    img.put(sb, b"Intel Raid ISM Cfg Sig. 1.3.00")
+        .put_le32(sb + 36, 736) // mpb_size
+        .put_le32(sb + 40, ISW_FAMILY_NUM)
+        .put_le32(sb + 44, 3) // generation_num
+        .put(sb + 216, b"ISWSERIAL0001") // disk[0].serial
+        .put(ext + 16, b"Volume0");
+
+    let mut mpb = img.bytes()[sb as usize..][..512].to_vec();
+    mpb.extend_from_slice(&img.bytes()[ext as usize..][..224]);
+    let sum = mpb.chunks_exact(4).fold(0u32, |sum, w| {
+        sum.wrapping_add(u32::from_le_bytes(w.try_into().unwrap()))
+    });
+    img.put_le32(sb + 32, sum);
+
+    img
+}
+
+pub const DDF_GUID: [u8; 24] =
+    *b"Linux-MD\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f\x10";
+pub const DDF_GUID_STR: &str = "4c696e75782d4d440102030405060708090a0b0c0d0e0f10";
+
+/// 1 MiB SNIA DDF member, the anchor in the last sector pointing to the
+/// primary header in sector 32.
+pub fn ddf(big_endian: bool) -> Image {
+    let mut img = Image::new(1 << 20);
+    let anchor = sector_from_end(&img, 1);
+
+    for sb in [anchor, 32 * 512] {
+        if big_endian {
+            img.put_be32(sb, 0xde11de11)
+                .put_be64(sb + 96, 32) // primary_lba
+                .put_be64(sb + 104, u64::MAX); // secondary_lba
+        } else {
+            img.put_le32(sb, 0xde11de11)
+                .put_le64(sb + 96, 32)
+                .put_le64(sb + 104, u64::MAX);
+        }
+        img.put_be32(sb + 4, 0xffffffff) // crc
+            .put(sb + 8, &DDF_GUID)
+            .put(sb + 32, b"01.02.00")
+            .put_u8(sb + 112, if sb == anchor { 0 } else { 1 });
+
+        let crc = crc32(&img.bytes()[sb as usize..][..512]);
+        if big_endian {
+            img.put_be32(sb + 4, crc);
+        } else {
+            img.put_le32(sb + 4, crc);
+        }
+    }
+
+    img
+}
+
+/// 1 MiB LSI MegaRAID member.
+pub fn lsi() -> Image {
+    let mut img = Image::new(1 << 20);
+    let sb = sector_from_end(&img, 1);
+
+    img.put(sb, b"$XIDE$");
+
+    img
+}
+
+/// 1 MiB Promise FastTrack member, with the metadata 63 sectors from the
+/// end.
+pub fn promise() -> Image {
+    let mut img = Image::new(1 << 20);
+    let sb = sector_from_end(&img, 63);
+
+    img.put(sb, b"Promise Technology, Inc.");
+
+    img
+}
+
+/// 1 MiB Nvidia MediaShield member.
+pub fn nvidia() -> Image {
+    let mut img = Image::new(1 << 20);
+    let sb = sector_from_end(&img, 2);
+
+    img.put(sb, b"NVIDIA  ")
+        .put_le32(sb + 8, 30) // size in words
+        .put_le16(sb + 16, 100) // version
+        .put_le32(sb + 20, 0x12345678);
+
+    let sum = img.bytes()[sb as usize..][..120]
+        .chunks_exact(4)
+        .fold(0u32, |sum, w| {
+            sum.wrapping_add(u32::from_le_bytes(w.try_into().unwrap()))
+        });
+    img.put_le32(sb + 12, sum.wrapping_neg());
+
+    img
+}
+
+/// 1 MiB Silicon Image Medley member.
+pub fn silicon() -> Image {
+    let mut img = Image::new(1 << 20);
+    let sb = sector_from_end(&img, 1);
+
+    img.put(sb + 0x2e, b"SIL3112")
+        .put_le32(sb + 0x60, 0x2f000000)
+        .put_le16(sb + 0x108, 1) // minor_ver
+        .put_le16(sb + 0x10a, 2); // major_ver
+
+    let sum = img.bytes()[sb as usize..][..0x13e]
+        .chunks_exact(2)
+        .fold(0u16, |sum, w| {
+            sum.wrapping_add(u16::from_le_bytes(w.try_into().unwrap()))
+        });
+    img.put_le16(sb + 0x13e, sum.wrapping_neg());
+
+    img
+}
+
+/// 1 MiB VIA V-RAID member.
+pub fn via() -> Image {
+    let mut img = Image::new(1 << 20);
+    let sb = sector_from_end(&img, 1);
+
+    img.put_le16(sb, 0xaa55)
+        .put_u8(sb + 2, 2) // version_number
+        .put_le16(sb + 3, 0b11) // disk_bit_mask
+        .put_le32(sb + 6, 0x800); // capacity_low
+
+    let sum = img.bytes()[sb as usize..][..50]
+        .iter()
+        .fold(0u8, |sum, &b| sum.wrapping_add(b));
+    img.put_u8(sb + 50, sum);
+
+    img
+}
+
+/// 1 MiB JMicron JMB36x member.
+pub fn jmicron() -> Image {
+    let mut img = Image::new(1 << 20);
+    let sb = sector_from_end(&img, 1);
+
+    img.put(sb, b"JM")
+        .put_le16(sb + 2, 0x0102) // version
+        .put_le32(sb + 0x10, 0x5eed1234) // identity
+        .put(sb + 0x20, b"jm_array")
+        .put_u8(sb + 0x30, 1); // mode
+
+    let sum = img.bytes()[sb as usize..][..128]
+        .chunks_exact(2)
+        .fold(0u16, |sum, w| {
+            sum.wrapping_add(u16::from_le_bytes(w.try_into().unwrap()))
+        });
+    img.put_le16(sb + 4, sum.wrapping_neg());
+
+    img
+}
+
+/// 1 MiB Adaptec HostRAID member.
+pub fn adaptec() -> Image {
+    let mut img = Image::new(1 << 20);
+    let sb = sector_from_end(&img, 1);
+
+    img.put_be32(sb, 0x37fc4d1e)
+        .put_u8(sb + 0x3f, 8) // resver
+        .put(sb + 0x100, b"DPTM");
+
+    img
+}
+
+pub enum NvValue<'a> {
+    U64(u64),
+    Str(&'a str),
+    List(Vec<u8>),
+}
+
+fn xdr_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend((bytes.len() as u32).to_be_bytes());
+    out.extend(bytes);
+    out.resize(out.len().next_multiple_of(4), 0);
+}
+
+/// Packs `pairs` as an XDR nvlist, without the encoding header.
+pub fn nvlist(pairs: &[(&str, NvValue)]) -> Vec<u8> {
+    let mut out = vec![0, 0, 0, 0, 0, 0, 0, 1]; // version, NV_UNIQUE_NAME
+
+    for (name, value) in pairs {
+        let mut pair = Vec::new();
+        xdr_bytes(&mut pair, name.as_bytes());
+
+        let (value_type, data) = match value {
+            NvValue::U64(v) => (8u32, v.to_be_bytes().to_vec()),
+            NvValue::Str(v) => {
+                let mut data = Vec::new();
+                xdr_bytes(&mut data, v.as_bytes());
+                (9, data)
+            }
+            NvValue::List(v) => (19, v.clone()),
+        };
+        pair.extend(value_type.to_be_bytes());
+        pair.extend(1u32.to_be_bytes()); // nelem
+        pair.extend(data);
+
+        let size = (pair.len() as u32 + 8).to_be_bytes();
+        out.extend(size);
+        out.extend(size);
+        out.extend(pair);
+    }
+
+    out.extend([0; 8]);
+    out
+}
+
+pub const ZFS_POOL_GUID: u64 = 0x1122334455667788;
+pub const ZFS_VDEV_GUID: u64 = 0x99aabbccddeeff00;
+
+/// ZFS pool member of 1 MiB, made of its four vdev labels with an uberblock
+/// in the third slot of each. `leading` writes the two labels at the start.
+pub fn zfs(pool: &str, leading: bool) -> Image {
+    let tree = nvlist(&[
+        ("type", NvValue::Str("disk")),
+        ("guid", NvValue::U64(ZFS_VDEV_GUID)),
+        ("ashift", NvValue::U64(12)),
+    ]);
+    let label = nvlist(&[
+        ("version", NvValue::U64(5000)),
+        ("name", NvValue::Str(pool)),
+        ("state", NvValue::U64(0)),
+        ("pool_guid", NvValue::U64(ZFS_POOL_GUID)),
+        ("guid", NvValue::U64(ZFS_VDEV_GUID)),
+        ("vdev_tree", NvValue::List(tree)),
+    ]);
+
+    let mut img = Image::new(1 << 20);
+    let labels: &[u64] = if leading {
+        &[0, 256 << 10, 512 << 10, 768 << 10]
+    } else {
+        &[512 << 10, 768 << 10]
+    };
+
+    for &l in labels {
+        img.put(l + (16 << 10), &[1, 1, 0, 0]) // NV_ENCODE_XDR, little endian
+            .put(l + (16 << 10) + 4, &label)
+            .put_le64(l + (130 << 10), 0x00bab10c) // ub_magic
+            .put_le64(l + (130 << 10) + 8, 5000); // ub_version
+    }
+
+    img
+}
+
+pub const ISO9660_UUID: &str = "2024-01-02-12-34-56-00";
+
+/// ISO9660 volume, with a Joliet descriptor labelled `joliet` if given.
+pub fn iso9660(label: &str, joliet: Option<&str>) -> Image {
+    let mut img = Image::new(64 << 10);
+    let mut at = 0x8000;
+
+    iso9660_pvd(&mut img, at, label);
+    at += 2048;
+
+    if let Some(joliet) = joliet {
+        let mut id: Vec<u8> = joliet.encode_utf16().flat_map(u16::to_be_bytes).collect();
+        id.resize(32, 0);
+        for c in id.chunks_mut(2).filter(|c| c == &[0, 0]) {
+            c[1] = b' ';
+        }
+
+        iso_descriptor(&mut img, at, 2);
+        img.put(at + 40, &id).put(at + 88, b"%/E");
+        at += 2048;
+    }
+
+    iso_descriptor(&mut img, at, 255);
+
+    img
+}
+
+/// Writes a UDF descriptor tag at `sector` of 2048 bytes.
+fn udf_tag(img: &mut Image, sector: u64, ident: u16) {
+    let at = sector * 2048;
+
+    img.put_le16(at, ident)
+        .put_le16(at + 2, 2) // descriptor_version
+        .put_le32(at + 12, sector as u32);
+
+    let sum = img.bytes()[at as usize..at as usize + 16]
+        .iter()
+        .fold(0u8, |acc, b| acc.wrapping_add(*b));
+    img.put_u8(at + 4, sum);
+}
+
+/// UDF 2.01 volume with 2048 byte sectors, after an ISO9660 volume
+/// descriptor set if `bridge` is set.
+pub fn udf(label: &str, bridge: bool) -> Image {
+    let mut img = Image::new(257 * 2048);
+    let mut at = 0x8000;
+
+    if bridge {
+        iso9660_pvd(&mut img, at, "ISOLABEL");
+        iso_descriptor(&mut img, at + 2048, 255);
+        at += 2 * 2048;
+    }
+
+    for id in [b"BEA01", b"NSR02", b"TEA01"] {
+        img.put(at + 1, id).put_u8(at + 6, 1);
+        at += 2048;
+    }
+
+    // Anchor pointing at a main volume descriptor sequence at sector 32.
+    img.put_le32(256 * 2048 + 16, 3 * 2048)
+        .put_le32(256 * 2048 + 20, 32);
+    udf_tag(&mut img, 256, 2);
+
+    let pvd = 32 * 2048;
+    img.put_u8(pvd + 24, 8)
+        .put(pvd + 25, b"UDFVOL")
+        .put_u8(pvd + 55, 7) // volume_id
+        .put_u8(pvd + 72, 8)
+        .put(pvd + 73, b"4f1b2c3d5e6f7a8bUDF")
+        .put_u8(pvd + 199, 20); // volume_set_id
+    udf_tag(&mut img, 32, 1);
+
+    let id: Vec<u8> = label.encode_utf16().flat_map(u16::to_be_bytes).collect();
+    let lvd = 33 * 2048;
+    img.put_u8(lvd + 84, 16)
+        .put(lvd + 85, &id)
+        .put_u8(lvd + 211, id.len() as u8 + 1) // logical_volume_id
+        .put_le32(lvd + 212, 2048) // logical_block_size
+        .put(lvd + 217, b"*OSTA UDF Compliant")
+        .put_le16(lvd + 240, 0x0201); // udf_revision
+    udf_tag(&mut img, 33, 6);
+
+    udf_tag(&mut img, 34, 8);
+
+    img
+}
+
+/// XFS v4 superblock.
+pub fn xfs(label: &str) -> Image {
+    let mut img = Image::new(1 << 20);
+
+    img.put(0, b"XFSB")
+        .put_be32(4, 4096) // blocksize
+        .put_be64(8, 4000) // dblocks
+        .put(32, &UUID)
+        .put_be64(48, 2048) // logstart
+        .put_be32(80, 1) // rextsize
+        .put_be32(84, 1000) // agblocks
+        .put_be32(88, 4) // agcount
+        .put_be32(96, 512) // logblocks
+        .put_be16(100, 4) // versionnum
+        .put_be16(102, 512) // sectsize
+        .put_be16(104, 512) // inodesize
+        .put_be16(106, 8) // inopblock
+        .put(108, label.as_bytes())
+        .put_u8(120, 12) // blocklog
+        .put_u8(121, 9) // sectlog
+        .put_u8(122, 9) // inodelog
+        .put_u8(123, 3) // inopblog
+        .put_u8(127, 25); // imax_pct
+
+    img
+}
+
+/// XFS external log device, holding a log record header for the
+/// filesystem with [`UUID`] in its third sector.
+pub fn xfs_log() -> Image {
+    let mut img = Image::new(1 << 20);
+    let rec = 1024;
+
+    img.put(rec, &[0xFE, 0xED, 0xBA, 0xBE])
+        .put_be32(rec + 4, 1) // cycle
+        .put_be32(rec + 8, 2) // version
+        .put_be32(rec + 12, 512) // len
+        .put_be32(rec + 300, 1) // fmt
+        .put(rec + 304, &UUID);
+
+    img
+}
+
+fn fletcher64(buf: &[u8]) -> u64 {
+    let mut lo: u64 = 0;
+    let mut hi: u64 = 0;
+
+    for word in buf.chunks_exact(4) {
+        lo = lo.wrapping_add(u64::from(u32::from_le_bytes(word.try_into().unwrap())));
+        hi = hi.wrapping_add(lo);
+    }
+
+    let csum_lo = !((lo.wrapping_add(hi)) % 0xFFFFFFFF) as u32;
+    let csum_hi = !((lo.wrapping_add(u64::from(csum_lo))) % 0xFFFFFFFF) as u32;
+
+    (u64::from(csum_hi) << 32) | u64::from(csum_lo)
+}
+
+/// APFS container superblock.
+pub fn apfs() -> Image {
+    let mut img = Image::new(1 << 20);
+
+    img.put_le64(8, 1) // oid
+        .put_le16(24, 1) // container superblock
+        .put(32, b"NXSB")
+        .put_le32(36, 4096)
+        .put_le64(40, 256)
+        .put(72, &UUID);
+
+    let csum = fletcher64(&img.bytes()[8..4096]);
+    img.put_le64(0, csum);
+
+    img
+}
+
+pub fn luks1() -> Image {
+    let mut img = Image::new(2 << 20);
+
+    img.put(0, b"LUKS\xba\xbe")
+        .put_be16(6, 1)
+        .put(8, b"aes")
+        .put(168, UUID_STR.as_bytes());
+
+    img
+}
+
+fn luks2_header(img: &mut Image, at: u64, magic: &[u8; 6], subsystem: &[u8]) {
+    img.put(at, magic)
+        .put_be16(at + 6, 2)
+        .put_be64(at + 8, 0x4000)
+        .put(at + 168, UUID_STR.as_bytes())
+        .put(at + 208, subsystem)
+        .put_be64(at + 256, at);
+}
+
+/// LUKS2 with both the primary and the first secondary header.
+pub fn luks2(subsystem: &[u8]) -> Image {
+    let mut img = Image::new(4 << 20);
+
+    luks2_header(&mut img, 0, b"LUKS\xba\xbe", subsystem);
+    luks2_header(&mut img, 0x4000, b"SKUL\xba\xbe", subsystem);
+
+    img
+}
+
+/// LUKS2 whose primary header has been wiped.
+pub fn luks2_secondary_only() -> Image {
+    let mut img = Image::new(4 << 20);
+
+    luks2_header(&mut img, 0x8000, b"SKUL\xba\xbe", b"");
+
+    img
+}
+
+pub const VERITY_SALT: [u8; 4] = [0xde, 0xad, 0xbe, 0xef];
+
+pub fn verity() -> Image {
+    let mut img = Image::new(1 << 20);
+
+    img.put(0, b"verity\0\0")
+        .put_le32(8, 1) // version
+        .put_le32(12, 1) // hash type
+        .put(16, &UUID)
+        .put(32, b"sha256")
+        .put_le32(64, 4096) // data block size
+        .put_le32(68, 4096) // hash block size
+        .put_le64(72, 256)
+        .put_le16(80, VERITY_SALT.len() as u16)
+        .put(88, &VERITY_SALT);
+
+    img
+}
+
+/// 4 KiB blocks with 32 byte tags, 2048 sectors of data.
+pub fn integrity() -> Image {
+    let mut img = Image::new(1 << 20);
+
+    img.put(0, b"integrt\0")
+        .put_u8(8, 5) // version
+        .put_u8(9, 15) // log2 interleave sectors
+        .put_le16(10, 32)
+        .put_le32(12, 8) // journal sections
+        .put_le64(16, 2048)
+        .put_u8(28, 3); // log2 sectors per block
+
+    img
+}
+
+/// The FVE volume GUID is stored little-endian, the first three fields of
+/// [`UUID`] read swapped.
+pub const BDE_VOLUME_GUID_STR: &str = "3c5a1d6f-4e2b-8a4c-9d10-223344556677";
+/// Identifier every BitLocker volume header carries.
+const BDE_IDENTIFIER: [u8; 16] = [
+    0x3b, 0xd6, 0x67, 0x49, 0x29, 0x2e, 0xd8, 0x4a, 0x83, 0x99, 0xf6, 0xa3, 0x39, 0xe3, 0xd0, 0x01,
+];
+
+fn fve_block(img: &mut Image, at: u64, version: u16, offsets: [u64; 3]) {
+    img.put(at, b"-FVE-FS-")
+        .put_le16(at + 8, 64)
+        .put_le16(at + 10, version)
+        .put_le64(at + 32, offsets[0])
+        .put_le64(at + 40, offsets[1])
+        .put_le64(at + 48, offsets[2])
+        .put_le32(at + 64, 0x300) // metadata size
+        .put_le32(at + 68, 1)
+        .put_le32(at + 72, 48)
+        .put_le32(at + 76, 0x300)
+        .put(at + 80, &UUID);
+
+    if version >= 2 {
+        img.put_le64(at + 16, 1 << 20);
+    }
+}
+
+/// Vista volume, the first FVE metadata block is at cluster 16.
+pub fn bitlocker_vista() -> Image {
+    let mut img = Image::new(1 << 20);
+
+    img.put(0, b"\xeb\x52\x90-FVE-FS-")
+        .put_le16(11, 512)
+        .put_u8(13, 8)
+        .put_le64(0x38, 16)
+        .put(510, &[0x55, 0xAA]);
+
+    fve_block(&mut img, 0x10000, 1, [0x10000, 0, 0]);
+
+    img
+}
+
+pub const BDE_WIN7_FVE_OFFSETS: [u64; 3] = [0x10000, 0x20000, 0x30000];
+
+pub fn bitlocker_win7() -> Image {
+    let mut img = Image::new(1 << 20);
+    let offsets = BDE_WIN7_FVE_OFFSETS;
+
+    img.put(0, b"\xeb\x58\x90-FVE-FS-")
+        .put_le16(11, 512)
+        .put_u8(13, 8)
+        .put(160, &BDE_IDENTIFIER)
+        .put_le64(176, offsets[0])
+        .put_le64(184, offsets[1])
+        .put_le64(192, offsets[2])
+        .put(510, &[0x55, 0xAA]);
+
+    for at in offsets {
+        fve_block(&mut img, at, 2, offsets);
+    }
+
+    img
+}
+
+/// BitLocker To Go over the FAT32 boot sector of [`fat32`].
+pub fn bitlocker_togo() -> Image {
+    let mut img = fat32(b"NO NAME    ");
+    let offsets = [0x200000, 0x280000, 0x300000];
+
+    img.put(424, &BDE_IDENTIFIER)
+        .put_le64(440, offsets[0])
+        .put_le64(448, offsets[1])
+        .put_le64(456, offsets[2]);
+
+    for at in offsets {
+        fve_block(&mut img, at, 2, offsets);
+    }
+
+    img
+}
+
+fn mbr_entry(img: &mut Image, at: u64, sys_ind: u8, start: u32, size: u32) {
+    img.put_u8(at + 4, sys_ind)
+        .put_le32(at + 8, start)
+        .put_le32(at + 12, size);
+}
+
+/// Writes an MBR at the start of `img` with `(sys_ind, start, size)` entries
+/// in 512 byte sectors.
+pub fn mbr_table(img: &mut Image, entries: &[(u8, u32, u32)]) {
+    img.put(440, &[0xEF, 0xBE, 0xAD, 0xDE])
+        .put(510, &[0x55, 0xAA]);
+
+    for (i, (sys_ind, start, size)) in entries.iter().enumerate() {
+        mbr_entry(img, 446 + i as u64 * 16, *sys_ind, *start, *size);
+    }
+}
+
+/// Writes an EBR at `sector` holding a logical partition and an optional
+/// link, both relative as stored on disk.
+pub fn ebr(img: &mut Image, sector: u64, logical: (u8, u32, u32), next: Option<(u32, u32)>) {
+    let at = sector * 512;
+
+    img.put(at + 510, &[0x55, 0xAA]);
+    mbr_entry(img, at + 446, logical.0, logical.1, logical.2);
+    if let Some((start, size)) = next {
+        mbr_entry(img, at + 462, 0x05, start, size);
+    }
+}
+
+/// MBR with one primary partition and an extended partition holding two
+/// logical partitions.
+pub fn mbr() -> Image {
+    let mut img = Image::new(1 << 20);
+
+    mbr_table(&mut img, &[(0x83, 1, 127), (0x05, 128, 1024)]);
+    ebr(&mut img, 128, (0x83, 1, 255), Some((256, 256)));
+    ebr(&mut img, 128 + 256, (0x83, 1, 255), None);
+
+    img
+}
+
+pub const GPT_PART_GUID: [u8; 16] = [
+    0xaf, 0x3d, 0xc6, 0x0f, 0x83, 0x84, 0x72, 0x47, 0x8e, 0x79, 0x3d, 0x69, 0xd8, 0x47, 0x7d, 0xe4,
+];
+
+/// Writes a protective MBR and a primary GPT header to `img`, sized for 512
+/// byte sectors, with `(first_lba, last_lba, name)` partitions.
+pub fn gpt_table(img: &mut Image, parts: &[(u64, u64, &str)]) {
+    let sectors = img.len() / 512;
+
+    mbr_table(img, &[(0xEE, 1, (sectors - 1) as u32)]);
+
+    let entries = 2 * 512;
+    for (i, (first, last, name)) in parts.iter().enumerate() {
+        let entry = entries + i as u64 * 128;
+        let mut uuid = UUID;
+        uuid[15] = i as u8;
+
+        img.put(entry, &GPT_PART_GUID)
+            .put(entry + 16, &uuid)
+            .put_le64(entry + 32, *first)
+            .put_le64(entry + 40, *last)
+            .put(entry + 56, &utf16le(name));
+    }
+
+    let entries_crc = crc32(&img.bytes()[entries as usize..(entries + 128 * 128) as usize]);
+
+    let hdr = 512;
+    img.put(hdr, b"EFI PART")
+        .put_le32(hdr + 8, 0x00010000)
+        .put_le32(hdr + 12, 92)
+        .put_le64(hdr + 24, 1)
+        .put_le64(hdr + 32, sectors - 1)
+        .put_le64(hdr + 40, 34)
+        .put_le64(hdr + 48, sectors - 34)
+        .put(hdr + 56, &UUID)
+        .put_le64(hdr + 72, 2)
+        .put_le32(hdr + 80, 128)
+        .put_le32(hdr + 84, 128)
+        .put_le32(hdr + 88, entries_crc);
+
+    let hdr_crc = crc32(&img.bytes()[hdr as usize..hdr as usize + 92]);
+    img.put_le32(hdr + 16, hdr_crc);
+}
+
+/// GPT with a single partition.
+pub fn gpt() -> Image {
+    let mut img = Image::new(1 << 20);
+
+    gpt_table(&mut img, &[(34, 1000, "root")]);
+
+    img
+}
+
+pub fn aix() -> Image {
+    let mut img = Image::new(64 << 10);
+
+    img.put(0, &[0xC9, 0xC2, 0xD4, 0xC1]);
+
+    img
+}
+
+/// Writes a BSD disklabel to `img` at `at` with `(fstype, offset, size)`
+/// partitions in 512 byte sectors, lettered from `a`.
+pub fn bsd_label(img: &mut Image, at: u64, parts: &[(u8, u32, u32)]) {
+    img.put(at, &[0x57, 0x45, 0x56, 0x82])
+        .put_le32(at + 40, 512)
+        .put(at + 132, &[0x57, 0x45, 0x56, 0x82])
+        .put_le16(at + 138, parts.len() as u16)
+        .put_le32(at + 140, 8192);
+
+    for (i, (fstype, offset, size)) in parts.iter().enumerate() {
+        let entry = at + 148 + i as u64 * 16;
+        img.put_le32(entry, *size)
+            .put_le32(entry + 4, *offset)
+            .put_u8(entry + 12, *fstype);
+    }
+
+    let len = 148 + parts.len() * 16;
+    let csum = img.bytes()[at as usize..at as usize + len]
+        .chunks_exact(2)
+        .fold(0, |csum, w| csum ^ u16::from_le_bytes([w[0], w[1]]));
+    img.put_le16(at + 136, csum);
+}
+
+/// Standalone BSD disklabel with a UFS partition `a` and swap partition `b`.
+pub fn bsd() -> Image {
+    let mut img = Image::new(1 << 20);
+
+    bsd_label(
+        &mut img,
+        512,
+        &[(7, 16, 1008), (1, 1024, 512), (0, 0, 2048)],
+    );
+
+    img
+}
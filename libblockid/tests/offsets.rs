@@ -0,0 +1,1237 @@
+//! Probes every supported format placed at non-zero offsets inside a larger
+//! image, checking that all reads are relative to the probe offset.
+
+#![cfg(all(feature = "std", feature = "os_calls"))]
+
+mod common;
+
+use fat_volume_id::{id32::VolumeId32, id64::VolumeId64};
+use libblockid::{
+    Endianness, Probe, ProbeFlags, Usage,
+    error::Error,
+    filesystem::{FsFilter, FsId, FsInfo, FsType},
+    partition::{PtFilter, PtInfo, PtType},
+};
+
+use common::{Image, OFFSETS};
+
+/// Where the magic of a format is expected.
+#[derive(Debug, Clone, Copy)]
+enum At {
+    /// Bytes from the probe offset.
+    Start(u64),
+    /// Bytes before the end of the device, for formats found from the device
+    /// size. The image is embedded without slack.
+    End(u64),
+}
+
+/// A filesystem image and what probing it at any offset should report.
+struct Case {
+    name: &'static str,
+    image: fn() -> Image,
+    fs_type: FsType,
+    label: Option<&'static str>,
+    magic: At,
+    /// Further assertions on what was probed.
+    check: fn(&FsInfo),
+}
+
+fn uuid(id: Option<FsId>) -> Option<String> {
+    id.and_then(|id| id.as_uuid()).map(|u| u.to_string())
+}
+
+fn volume_id64(id: u64) -> Option<FsId> {
+    Some(FsId::VolumeId64(VolumeId64::from_bytes(id.to_le_bytes())))
+}
+
+fn hfs_volume_id() -> Option<FsId> {
+    volume_id64(u64::from_be_bytes(common::HFS_VOLUME_ID))
+}
+
+fn ufs_id() -> Option<FsId> {
+    volume_id64(u64::from(common::UFS_ID[0]) << 32 | u64::from(common::UFS_ID[1]))
+}
+
+fn version(info: &FsInfo) -> Option<&str> {
+    info.version().map(String::as_str)
+}
+
+fn assert_md_ids(info: &FsInfo, member: bool) {
+    assert_eq!(uuid(info.fs_id()), Some(common::UUID_STR.to_string()));
+    assert_eq!(
+        uuid(info.sub_member_id()),
+        member.then(|| common::DEV_UUID_STR.to_string())
+    );
+    assert_eq!(info.usage(), Some(Usage::Raid));
+}
+
+fn assert_bitlocker(info: &FsInfo, magic: &[u8], version: &str, size: Option<u64>) {
+    assert_eq!(info.magic(), Some(magic));
+    assert_eq!(
+        uuid(info.fs_id()),
+        Some(common::BDE_VOLUME_GUID_STR.to_string())
+    );
+    assert_eq!(info.usage(), Some(Usage::Crypto));
+    assert_eq!(info.version().map(String::as_str), Some(version));
+    assert_eq!(info.fs_size(), size);
+}
+
+fn bitlocker_win7_damaged() -> Image {
+    let mut img = common::bitlocker_win7();
+    img.put(common::BDE_WIN7_FVE_OFFSETS[0], &[0; 8]);
+    img
+}
+
+#[rustfmt::skip]
+const FILESYSTEMS: &[Case] = &[
+    Case {
+        name: "ext2",
+        image: || common::ext("ext2label", 0, 0),
+        fs_type: FsType::Ext2,
+        label: Some("ext2label"),
+        magic: At::Start(0x438),
+        check: |_| {},
+    },
+    Case {
+        name: "ext3",
+        image: || common::ext("ext3label", 0x0004, 0),
+        fs_type: FsType::Ext3,
+        label: Some("ext3label"),
+        magic: At::Start(0x438),
+        check: |_| {},
+    },
+    Case {
+        name: "ext4",
+        image: || common::ext("ext4label", 0x0004, 0x0040),
+        fs_type: FsType::Ext4,
+        label: Some("ext4label"),
+        magic: At::Start(0x438),
+        check: |_| {},
+    },
+    Case {
+        name: "jbd",
+        image: || common::ext("", 0, 0x0008),
+        fs_type: FsType::Jbd,
+        label: None,
+        magic: At::Start(0x438),
+        check: |_| {},
+    },
+    Case {
+        name: "vfat_fat16",
+        image: || common::fat16(b"FAT16VOLUME"),
+        fs_type: FsType::Vfat,
+        label: Some("FAT16VOLUME"),
+        magic: At::Start(0x36),
+        check: |_| {},
+    },
+    Case {
+        name: "vfat_fat32",
+        image: || common::fat32(b"FAT32VOLUME"),
+        fs_type: FsType::Vfat,
+        label: Some("FAT32VOLUME"),
+        magic: At::Start(0x52),
+        check: |_| {},
+    },
+    Case {
+        name: "ntfs",
+        image: || common::ntfs("ntfslabel"),
+        fs_type: FsType::Ntfs,
+        label: Some("ntfslabel"),
+        magic: At::Start(3),
+        check: |_| {},
+    },
+    Case {
+        name: "exfat",
+        image: || common::exfat("exlabel"),
+        fs_type: FsType::Exfat,
+        label: Some("exlabel"),
+        magic: At::Start(3),
+        check: |_| {},
+    },
+    Case {
+        name: "xfs",
+        image: || common::xfs("xfslabel"),
+        fs_type: FsType::Xfs,
+        label: Some("xfslabel"),
+        magic: At::Start(0),
+        check: |info| assert_eq!(info.external_log(), Some(false)),
+    },
+    Case {
+        name: "xfs_external_log",
+        image: || {
+            let mut img = common::xfs("xfslabel");
+            img.put_be64(48, 0); // logstart
+            img
+        },
+        fs_type: FsType::Xfs,
+        label: Some("xfslabel"),
+        magic: At::Start(0),
+        check: |info| assert_eq!(info.external_log(), Some(true)),
+    },
+    Case {
+        name: "xfs_log",
+        image: common::xfs_log,
+        fs_type: FsType::XfsExternalLog,
+        label: None,
+        magic: At::Start(1024),
+        check: |info| {
+            assert_eq!(
+                info.ext_log_id().map(|u| u.to_string()),
+                Some(common::UUID_STR.to_string())
+            );
+        },
+    },
+    Case {
+        name: "apfs",
+        image: common::apfs,
+        fs_type: FsType::Apfs,
+        label: None,
+        magic: At::Start(32),
+        check: |_| {},
+    },
+    Case {
+        name: "btrfs",
+        image: || common::btrfs("btrfslabel"),
+        fs_type: FsType::Btrfs,
+        label: Some("btrfslabel"),
+        magic: At::Start(0x10040),
+        check: |info| {
+            assert_eq!(uuid(info.fs_id()), Some(common::UUID_STR.to_string()));
+            assert_eq!(uuid(info.sub_member_id()), Some(common::DEV_UUID_STR.to_string()));
+            assert_eq!(version(info), Some("7"));
+            assert_eq!(info.fs_size(), Some(256 << 20));
+            assert_eq!(info.fs_block_size(), Some(16384));
+            assert_eq!(info.block_size(), Some(4096));
+        },
+    },
+    Case {
+        // The primary superblock fails its checksum, the first mirror is used.
+        name: "btrfs_mirror",
+        image: || {
+            let mut img = Image::new(0x4001000);
+            common::btrfs_super(&mut img, 0x10000, "btrfslabel");
+            common::btrfs_super(&mut img, 0x4000000, "btrfslabel");
+            img.put_u8(0x10000 + 0x200, 0xFF);
+            img
+        },
+        fs_type: FsType::Btrfs,
+        label: Some("btrfslabel"),
+        magic: At::Start(0x4000040),
+        check: |_| {},
+    },
+    Case {
+        // The primary superblock is wiped, the first mirror is found by its
+        // own magic.
+        name: "btrfs_wiped_primary",
+        image: || {
+            let mut img = Image::new(0x4001000);
+            common::btrfs_super(&mut img, 0x4000000, "btrfslabel");
+            img
+        },
+        fs_type: FsType::Btrfs,
+        label: Some("btrfslabel"),
+        magic: At::Start(0x4000040),
+        check: |info| assert_eq!(uuid(info.fs_id()), Some(common::UUID_STR.to_string())),
+    },
+    Case {
+        name: "cramfs",
+        image: || common::cramfs("cramfslabel", true),
+        fs_type: FsType::Cramfs,
+        label: Some("cramfslabel"),
+        magic: At::Start(0),
+        check: |info| {
+            assert_eq!(info.endianness(), Some(Endianness::Little));
+            assert_eq!(version(info), Some("2"));
+            assert_eq!(info.fs_size(), Some(96 << 10));
+        },
+    },
+    Case {
+        name: "cramfs_big_endian",
+        image: || common::cramfs("cramfslabel", false),
+        fs_type: FsType::Cramfs,
+        label: Some("cramfslabel"),
+        magic: At::Start(0),
+        check: |info| assert_eq!(info.endianness(), Some(Endianness::Big)),
+    },
+    Case {
+        name: "vxfs",
+        image: || common::vxfs(true),
+        fs_type: FsType::Vxfs,
+        label: None,
+        magic: At::Start(1024),
+        check: |info| {
+            assert_eq!(info.endianness(), Some(Endianness::Little));
+            assert_eq!(version(info), Some("6"));
+            assert_eq!(info.fs_size(), Some(2048 * 1024));
+        },
+    },
+    Case {
+        name: "vxfs_big_endian",
+        image: || common::vxfs(false),
+        fs_type: FsType::Vxfs,
+        label: None,
+        magic: At::Start(8192),
+        check: |info| assert_eq!(info.endianness(), Some(Endianness::Big)),
+    },
+    Case {
+        name: "squashfs",
+        image: common::squashfs,
+        fs_type: FsType::Squashfs,
+        label: None,
+        magic: At::Start(0),
+        check: |info| {
+            assert_eq!(version(info), Some("4.0"));
+            assert_eq!(info.fs_size(), Some(8 << 10));
+            assert_eq!(info.block_size(), Some(128 << 10));
+            assert_eq!(info.compression_id(), Some(4));
+        },
+    },
+    Case {
+        name: "squashfs3_big_endian",
+        image: common::squashfs3_big_endian,
+        fs_type: FsType::Squashfs,
+        label: None,
+        magic: At::Start(0),
+        check: |info| {
+            assert_eq!(info.endianness(), Some(Endianness::Big));
+            assert_eq!(version(info), Some("3.1"));
+            assert_eq!(info.fs_size(), Some(8 << 10));
+            assert_eq!(info.block_size(), Some(64 << 10));
+        },
+    },
+    Case {
+        name: "erofs",
+        image: || common::erofs("erofslabel"),
+        fs_type: FsType::Erofs,
+        label: Some("erofslabel"),
+        magic: At::Start(1024),
+        check: |info| {
+            assert_eq!(uuid(info.fs_id()), Some(common::UUID_STR.to_string()));
+            assert_eq!(info.fs_size(), Some(8 << 10));
+            assert_eq!(info.block_size(), Some(4096));
+        },
+    },
+    Case {
+        name: "f2fs",
+        image: || common::f2fs("f2fs läbel"),
+        fs_type: FsType::F2fs,
+        label: Some("f2fs läbel"),
+        magic: At::Start(1024),
+        check: |info| {
+            assert_eq!(uuid(info.fs_id()), Some(common::UUID_STR.to_string()));
+            assert_eq!(version(info), Some("1.16"));
+            assert_eq!(info.fs_size(), Some(4 << 20));
+            assert_eq!(info.block_size(), Some(512));
+        },
+    },
+    Case {
+        name: "bcachefs",
+        image: || common::bcachefs("bcachefslabel"),
+        fs_type: FsType::Bcachefs,
+        label: Some("bcachefslabel"),
+        magic: At::Start(4096 + 24),
+        check: |info| {
+            assert_eq!(uuid(info.fs_id()), Some(common::UUID_STR.to_string()));
+            assert_eq!(uuid(info.sub_member_id()), Some(common::DEV_UUID_STR.to_string()));
+            assert_eq!(
+                info.internal_id().map(|u| u.to_string()).as_deref(),
+                Some("11111111-1111-1111-1111-111111111111")
+            );
+            assert_eq!(version(info), Some("1.7"));
+            assert_eq!(info.fs_size(), Some(128 << 20));
+            assert_eq!(info.block_size(), Some(4096));
+        },
+    },
+    Case {
+        name: "swap",
+        image: || common::swap("swaplabel", 4096, false),
+        fs_type: FsType::Swap,
+        label: Some("swaplabel"),
+        magic: At::Start(4086),
+        check: |info| {
+            assert_eq!(uuid(info.fs_id()), Some(common::UUID_STR.to_string()));
+            assert_eq!(version(info), Some("1"));
+            assert_eq!(info.endianness(), Some(Endianness::Little));
+            assert_eq!(info.fs_size(), Some(64 << 10));
+            assert_eq!(info.block_size(), Some(4096));
+        },
+    },
+    Case {
+        name: "swap_64k_big_endian",
+        image: || common::swap("swaplabel", 65536, true),
+        fs_type: FsType::Swap,
+        label: Some("swaplabel"),
+        magic: At::Start(65526),
+        check: |info| {
+            assert_eq!(info.endianness(), Some(Endianness::Big));
+            assert_eq!(info.fs_size(), Some(1 << 20));
+            assert_eq!(info.block_size(), Some(65536));
+        },
+    },
+    Case {
+        name: "swsuspend",
+        image: || {
+            let mut img = common::swap("swaplabel", 4096, false);
+            img.put(4086, b"S1SUSPEND\0");
+            img
+        },
+        fs_type: FsType::Swsuspend,
+        label: Some("swaplabel"),
+        magic: At::Start(4086),
+        check: |info| assert_eq!(version(info), Some("s1suspend")),
+    },
+    Case {
+        name: "hfs",
+        image: || common::hfs("Classic"),
+        fs_type: FsType::Hfs,
+        label: Some("Classic"),
+        magic: At::Start(1024),
+        check: |info| {
+            assert_eq!(info.fs_id(), hfs_volume_id());
+            assert_eq!(info.fs_size(), Some(64 << 10));
+        },
+    },
+    Case {
+        name: "hfsplus",
+        image: || common::hfsplus("Macintosh HD", false),
+        fs_type: FsType::HfsPlus,
+        label: Some("Macintosh HD"),
+        magic: At::Start(1024),
+        check: |info| {
+            assert_eq!(info.fs_id(), hfs_volume_id());
+            assert_eq!(version(info), Some("4"));
+            assert_eq!(info.fs_size(), Some(64 << 10));
+        },
+    },
+    Case {
+        name: "hfsx",
+        image: || common::hfsplus("Dätä", true),
+        fs_type: FsType::Hfsx,
+        label: Some("Dätä"),
+        magic: At::Start(1024),
+        check: |info| assert_eq!(version(info), Some("5")),
+    },
+    Case {
+        name: "hfs_wrapper",
+        image: || common::hfs_wrapper("Embedded"),
+        fs_type: FsType::HfsPlus,
+        label: Some("Embedded"),
+        magic: At::Start(6144 + 1024),
+        check: |info| assert_eq!(info.fs_id(), hfs_volume_id()),
+    },
+    Case {
+        name: "reiserfs",
+        image: || common::reiserfs("reiserlabel", b"ReIsEr2Fs"),
+        fs_type: FsType::Reiserfs,
+        label: Some("reiserlabel"),
+        magic: At::Start((64 << 10) + 52),
+        check: |info| {
+            assert_eq!(uuid(info.fs_id()), Some(common::UUID_STR.to_string()));
+            assert_eq!(version(info), Some("3.6"));
+            assert_eq!(info.fs_size(), Some(128 << 10));
+        },
+    },
+    Case {
+        name: "reiserfs_jr",
+        image: || common::reiserfs("reiserlabel", b"ReIsEr3Fs"),
+        fs_type: FsType::Reiserfs,
+        label: Some("reiserlabel"),
+        magic: At::Start((64 << 10) + 52),
+        check: |info| assert_eq!(version(info), Some("JR")),
+    },
+    Case {
+        // Older formats have no label or UUID, whatever is there is ignored.
+        name: "reiserfs_3_5",
+        image: || common::reiserfs("reiserlabel", b"ReIsErFs"),
+        fs_type: FsType::Reiserfs,
+        label: None,
+        magic: At::Start((8 << 10) + 52),
+        check: |info| {
+            assert_eq!(info.fs_id(), None);
+            assert_eq!(version(info), Some("3.5"));
+        },
+    },
+    Case {
+        name: "jfs",
+        image: || common::jfs("jfslabel"),
+        fs_type: FsType::Jfs,
+        label: Some("jfslabel"),
+        magic: At::Start(32 << 10),
+        check: |info| {
+            assert_eq!(uuid(info.fs_id()), Some(common::UUID_STR.to_string()));
+            assert_eq!(version(info), Some("2"));
+            assert_eq!(info.fs_size(), Some(1 << 20));
+            assert_eq!(info.fs_block_size(), Some(4096));
+        },
+    },
+    Case {
+        name: "nilfs2",
+        image: || common::nilfs2("nilfslabel"),
+        fs_type: FsType::Nilfs2,
+        label: Some("nilfslabel"),
+        magic: At::Start(1024 + 6),
+        check: |info| {
+            assert_eq!(uuid(info.fs_id()), Some(common::UUID_STR.to_string()));
+            assert_eq!(version(info), Some("2.0"));
+            assert_eq!(info.fs_size(), Some(128 << 10));
+        },
+    },
+    Case {
+        // Corrupt the primary superblock, leaving the copy at the end.
+        name: "nilfs2_secondary_superblock",
+        image: || {
+            let mut img = common::nilfs2("nilfslabel");
+            img.put_u8(1024 + 168, b'X');
+            img
+        },
+        fs_type: FsType::Nilfs2,
+        label: Some("nilfslabel"),
+        magic: At::End((4 << 10) - 6),
+        check: |_| {},
+    },
+    Case {
+        name: "minix",
+        image: || common::minix(1, false),
+        fs_type: FsType::Minix,
+        label: None,
+        magic: At::Start(1024 + 0x10),
+        check: |info| {
+            assert_eq!(version(info), Some("1"));
+            assert_eq!(info.fs_size(), Some(64 << 10));
+            assert_eq!(info.endianness(), Some(Endianness::Little));
+        },
+    },
+    Case {
+        name: "minix2_big_endian",
+        image: || common::minix(2, true),
+        fs_type: FsType::Minix,
+        label: None,
+        magic: At::Start(1024 + 0x10),
+        check: |info| {
+            assert_eq!(version(info), Some("2"));
+            assert_eq!(info.endianness(), Some(Endianness::Big));
+        },
+    },
+    Case {
+        name: "minix3_big_endian",
+        image: || common::minix(3, true),
+        fs_type: FsType::Minix,
+        label: None,
+        magic: At::Start(1024 + 0x18),
+        check: |info| {
+            assert_eq!(version(info), Some("3"));
+            assert_eq!(info.fs_block_size(), Some(1024));
+            assert_eq!(info.endianness(), Some(Endianness::Big));
+        },
+    },
+    Case {
+        name: "ufs1",
+        image: || common::ufs("ignored", false, false),
+        fs_type: FsType::Ufs,
+        label: None,
+        magic: At::Start((8 << 10) + 1372),
+        check: |info| {
+            assert_eq!(info.fs_id(), ufs_id());
+            assert_eq!(version(info), Some("1"));
+            assert_eq!(info.fs_size(), Some(128 << 10));
+            assert_eq!(info.endianness(), Some(Endianness::Little));
+        },
+    },
+    Case {
+        name: "ufs2",
+        image: || common::ufs("ufslabel", true, false),
+        fs_type: FsType::Ufs,
+        label: Some("ufslabel"),
+        magic: At::Start((64 << 10) + 1372),
+        check: |info| {
+            assert_eq!(info.fs_id(), ufs_id());
+            assert_eq!(version(info), Some("2"));
+            assert_eq!(info.fs_size(), Some(128 << 10));
+            assert_eq!(info.fs_block_size(), Some(2048));
+        },
+    },
+    Case {
+        name: "ufs2_big_endian",
+        image: || common::ufs("ufslabel", true, true),
+        fs_type: FsType::Ufs,
+        label: Some("ufslabel"),
+        magic: At::Start((64 << 10) + 1372),
+        check: |info| {
+            assert_eq!(info.fs_id(), ufs_id());
+            assert_eq!(info.endianness(), Some(Endianness::Big));
+        },
+    },
+    Case {
+        name: "iso9660",
+        image: || common::iso9660("ISOLABEL", None),
+        fs_type: FsType::Iso9660,
+        label: Some("ISOLABEL"),
+        magic: At::Start(0x8001),
+        check: |info| {
+            assert_eq!(
+                info.fs_id()
+                    .and_then(|id| id.as_pseudo_uuid())
+                    .map(|id| id.to_string()),
+                Some(common::ISO9660_UUID.to_string())
+            );
+            assert_eq!(info.system_id().map(String::as_str), Some("LINUX"));
+            assert_eq!(info.publisher_id().map(String::as_str), Some("PUBLISHER"));
+            assert_eq!(info.application_id().map(String::as_str), Some("MKISOFS"));
+            assert_eq!(info.fs_size(), Some(64 << 10));
+            assert_eq!(info.block_size(), Some(2048));
+        },
+    },
+    Case {
+        name: "iso9660_joliet",
+        image: || common::iso9660("ISOLABEL", Some("Joliet Läbel")),
+        fs_type: FsType::Iso9660,
+        label: Some("Joliet Läbel"),
+        magic: At::Start(0x8001),
+        check: |_| {},
+    },
+    Case {
+        name: "udf",
+        image: || common::udf("UDF Label", false),
+        fs_type: FsType::Udf,
+        label: Some("UDF Label"),
+        magic: At::Start(0x8001),
+        check: |info| {
+            assert_eq!(
+                info.fs_id()
+                    .and_then(|id| id.as_pseudo_uuid())
+                    .map(|id| id.to_string()),
+                Some("4f1b2c3d5e6f7a8b".to_string())
+            );
+            assert_eq!(version(info), Some("2.01"));
+            assert_eq!(info.block_size(), Some(2048));
+        },
+    },
+    Case {
+        name: "udf_bridge",
+        image: || common::udf("UDF Label", true),
+        fs_type: FsType::Udf,
+        label: Some("UDF Label"),
+        magic: At::Start(0x8001),
+        check: |_| {},
+    },
+    Case {
+        name: "zfs",
+        image: || common::zfs("tank", true),
+        fs_type: FsType::Zfs,
+        label: Some("tank"),
+        magic: At::Start(130 << 10),
+        check: |info| {
+            assert_eq!(info.fs_id(), volume_id64(common::ZFS_POOL_GUID));
+            assert_eq!(info.sub_member_id(), volume_id64(common::ZFS_VDEV_GUID));
+            assert_eq!(version(info), Some("5000"));
+            assert_eq!(info.usage(), Some(Usage::Raid));
+            assert_eq!(info.fs_block_size(), Some(4096));
+        },
+    },
+    Case {
+        // A stale partition table left in front of the pool.
+        name: "zfs_trailing_labels",
+        image: || {
+            let mut img = common::zfs("tank", false);
+            common::mbr_table(&mut img, &[(0x83, 2048, 2048)]);
+            img
+        },
+        fs_type: FsType::Zfs,
+        label: Some("tank"),
+        magic: At::Start((512 + 130) << 10),
+        check: |info| assert_eq!(info.fs_id(), volume_id64(common::ZFS_POOL_GUID)),
+    },
+    Case {
+        name: "linux_raid_member_1_1",
+        image: || common::md1(1, "host:md0"),
+        fs_type: FsType::LinuxRaidMember,
+        label: Some("host:md0"),
+        magic: At::Start(0),
+        check: |info| {
+            assert_md_ids(info, true);
+            assert_eq!(version(info), Some("1.1"));
+        },
+    },
+    Case {
+        name: "linux_raid_member_1_2",
+        image: || common::md1(2, "host:md0"),
+        fs_type: FsType::LinuxRaidMember,
+        label: Some("host:md0"),
+        magic: At::Start(4096),
+        check: |info| assert_eq!(version(info), Some("1.2")),
+    },
+    Case {
+        name: "linux_raid_member_1_0",
+        image: || common::md1(0, "host:md0"),
+        fs_type: FsType::LinuxRaidMember,
+        label: Some("host:md0"),
+        magic: At::End(16 * 512),
+        check: |info| {
+            assert_md_ids(info, true);
+            assert_eq!(version(info), Some("1.0"));
+        },
+    },
+    Case {
+        name: "linux_raid_member_0_90",
+        image: || common::md0(false),
+        fs_type: FsType::LinuxRaidMember,
+        label: None,
+        magic: At::End(64 << 10),
+        check: |info| {
+            assert_md_ids(info, false);
+            assert_eq!(version(info), Some("0.90.0"));
+            assert_eq!(info.endianness(), Some(Endianness::Little));
+        },
+    },
+    Case {
+        name: "linux_raid_member_0_90_big_endian",
+        image: || common::md0(true),
+        fs_type: FsType::LinuxRaidMember,
+        label: None,
+        magic: At::End(64 << 10),
+        check: |info| {
+            assert_md_ids(info, false);
+            assert_eq!(info.endianness(), Some(Endianness::Big));
+        },
+    },
+    Case {
+        name: "isw_raid_member",
+        image: common::isw,
+        fs_type: FsType::IswRaidMember,
+        label: None,
+        magic: At::End(1024),
+        check: |info| {
+            assert_eq!(
+                info.fs_id().and_then(|id| id.as_volumeid32()),
+                Some(VolumeId32::from_u32(common::ISW_FAMILY_NUM))
+            );
+            assert_eq!(info.usage(), Some(Usage::Raid));
+            assert_eq!(version(info), Some("1.3.00"));
+        },
+    },
+    Case {
+        name: "ddf_raid_member",
+        image: || common::ddf(true),
+        fs_type: FsType::DdfRaidMember,
+        label: None,
+        magic: At::End(512),
+        check: |info| {
+            assert_eq!(info.usage(), Some(Usage::Raid));
+            assert_eq!(version(info), Some("01.02.00"));
+            assert_eq!(
+                info.fs_id()
+                    .and_then(|id| id.as_ddf())
+                    .map(|id| id.to_string()),
+                Some(common::DDF_GUID_STR.to_string())
+            );
+            assert_eq!(info.endianness(), Some(Endianness::Big));
+        },
+    },
+    Case {
+        name: "ddf_raid_member_le",
+        image: || common::ddf(false),
+        fs_type: FsType::DdfRaidMember,
+        label: None,
+        magic: At::End(512),
+        check: |info| {
+            assert_eq!(
+                info.fs_id()
+                    .and_then(|id| id.as_ddf())
+                    .map(|id| id.to_string()),
+                Some(common::DDF_GUID_STR.to_string())
+            );
+            assert_eq!(info.endianness(), Some(Endianness::Little));
+        },
+    },
+    Case {
+        name: "lsi_mega_raid_member",
+        image: common::lsi,
+        fs_type: FsType::LsiMegaRaidMember,
+        label: None,
+        magic: At::End(512),
+        check: |info| {
+            assert_eq!(info.usage(), Some(Usage::Raid));
+            assert_eq!(version(info), None);
+        },
+    },
+    Case {
+        name: "promise_fasttrack_raid_member",
+        image: common::promise,
+        fs_type: FsType::PromiseFasttrackRaidMember,
+        label: None,
+        magic: At::End(63 * 512),
+        check: |info| {
+            assert_eq!(info.usage(), Some(Usage::Raid));
+            assert_eq!(version(info), None);
+        },
+    },
+    Case {
+        name: "nvidia_raid_member",
+        image: common::nvidia,
+        fs_type: FsType::NvidiaRaidMember,
+        label: None,
+        magic: At::End(1024),
+        check: |info| {
+            assert_eq!(info.usage(), Some(Usage::Raid));
+            assert_eq!(version(info), Some("100"));
+        },
+    },
+    Case {
+        name: "silicon_medley_raid_member",
+        image: common::silicon,
+        fs_type: FsType::SiliconMedleyRaidMember,
+        label: None,
+        magic: At::End(512 - 0x60),
+        check: |info| {
+            assert_eq!(info.usage(), Some(Usage::Raid));
+            assert_eq!(version(info), Some("2.1"));
+        },
+    },
+    Case {
+        name: "via_raid_member",
+        image: common::via,
+        fs_type: FsType::ViaRaidMember,
+        label: None,
+        magic: At::End(512),
+        check: |info| {
+            assert_eq!(info.usage(), Some(Usage::Raid));
+            assert_eq!(version(info), Some("2"));
+        },
+    },
+    Case {
+        name: "jmicron_raid_member",
+        image: common::jmicron,
+        fs_type: FsType::JmicronRaidMember,
+        label: None,
+        magic: At::End(512),
+        check: |info| {
+            assert_eq!(info.usage(), Some(Usage::Raid));
+            assert_eq!(version(info), Some("1.2"));
+        },
+    },
+    Case {
+        name: "adaptec_raid_member",
+        image: common::adaptec,
+        fs_type: FsType::AdaptecRaidMember,
+        label: None,
+        magic: At::End(512),
+        check: |info| {
+            assert_eq!(info.usage(), Some(Usage::Raid));
+            assert_eq!(version(info), Some("8"));
+        },
+    },
+    Case {
+        name: "lvm2_member",
+        image: || common::lvm2("vg_data"),
+        fs_type: FsType::Lvm2Member,
+        label: None,
+        magic: At::Start(512),
+        check: |info| {
+            assert_eq!(
+                info.fs_id()
+                    .and_then(|id| id.as_lvm())
+                    .map(|id| id.to_string()),
+                Some(common::LVM_PV_UUID_STR.to_string())
+            );
+            assert_eq!(info.usage(), Some(Usage::Raid));
+            assert_eq!(version(info), Some("LVM2 001"));
+            assert_eq!(info.fs_size(), Some(1 << 20));
+            assert_eq!(info.volume_group().map(String::as_str), Some("vg_data"));
+        },
+    },
+    Case {
+        name: "luks1",
+        image: common::luks1,
+        fs_type: FsType::LUKS1,
+        label: None,
+        magic: At::Start(0),
+        check: |_| {},
+    },
+    Case {
+        name: "luks2",
+        image: || common::luks2(b""),
+        fs_type: FsType::LUKS2,
+        label: None,
+        magic: At::Start(0),
+        check: |_| {},
+    },
+    Case {
+        name: "luks2_secondary_header",
+        image: common::luks2_secondary_only,
+        fs_type: FsType::LUKS2,
+        label: None,
+        magic: At::Start(0x8000),
+        check: |_| {},
+    },
+    Case {
+        name: "dm_verity_hash",
+        image: common::verity,
+        fs_type: FsType::DmVerityHash,
+        label: None,
+        magic: At::Start(0),
+        check: |info| {
+            assert_eq!(uuid(info.fs_id()), Some(common::UUID_STR.to_string()));
+            assert_eq!(info.usage(), Some(Usage::Crypto));
+            assert_eq!(version(info), Some("1"));
+            assert_eq!(info.data_block_size(), Some(4096));
+            assert_eq!(info.hash_block_size(), Some(4096));
+            assert_eq!(info.salt(), Some(common::VERITY_SALT.as_slice()));
+        },
+    },
+    Case {
+        name: "dm_integrity",
+        image: common::integrity,
+        fs_type: FsType::DmIntegrity,
+        label: None,
+        magic: At::Start(0),
+        check: |info| {
+            assert_eq!(info.usage(), Some(Usage::Crypto));
+            assert_eq!(version(info), Some("5"));
+            assert_eq!(info.fs_size(), Some(2048 * 512));
+            assert_eq!(info.fs_last_block(), Some(256));
+            assert_eq!(info.fs_block_size(), Some(4096));
+            assert_eq!(info.tag_size(), Some(32));
+        },
+    },
+    Case {
+        name: "bitlocker_vista",
+        image: common::bitlocker_vista,
+        fs_type: FsType::Bitlocker,
+        label: None,
+        magic: At::Start(0),
+        check: |info| assert_bitlocker(info, b"\xeb\x52\x90-FVE-FS-", "1", None),
+    },
+    Case {
+        name: "bitlocker_win7",
+        image: common::bitlocker_win7,
+        fs_type: FsType::Bitlocker,
+        label: None,
+        magic: At::Start(0),
+        check: |info| assert_bitlocker(info, b"\xeb\x58\x90-FVE-FS-", "2", Some(1 << 20)),
+    },
+    Case {
+        // The first FVE metadata block is damaged, the second is used.
+        name: "bitlocker_win7_damaged",
+        image: bitlocker_win7_damaged,
+        fs_type: FsType::Bitlocker,
+        label: None,
+        magic: At::Start(0),
+        check: |info| assert_bitlocker(info, b"\xeb\x58\x90-FVE-FS-", "2", Some(1 << 20)),
+    },
+    Case {
+        name: "bitlocker_togo",
+        image: common::bitlocker_togo,
+        fs_type: FsType::Bitlocker,
+        label: None,
+        magic: At::Start(0),
+        check: |info| assert_bitlocker(info, b"\xeb\x58\x90MSWIN4.1", "2", Some(1 << 20)),
+    },
+];
+
+/// Label sector of `common::lvm2` up to its last non-zero byte, with the
+/// checksum worked out by LVM's own `calc_crc`.
+const LVM2_LABEL: &[u8] = &[
+    0x4c, 0x41, 0x42, 0x45, 0x4c, 0x4f, 0x4e, 0x45, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x20, 0x66, 0xdb, 0xf0, 0x20, 0x00, 0x00, 0x00, 0x4c, 0x56, 0x4d, 0x32, 0x20, 0x30, 0x30, 0x31,
+    0x61, 0x42, 0x33, 0x64, 0x45, 0x36, 0x66, 0x47, 0x38, 0x68, 0x49, 0x6a, 0x30, 0x4b, 0x6c, 0x4d,
+    0x32, 0x6e, 0x4f, 0x70, 0x34, 0x51, 0x72, 0x53, 0x36, 0x74, 0x55, 0x76, 0x38, 0x77, 0x58, 0x79,
+    0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0xf0,
+];
+
+fn search_fs(inner: &Image, offset: u64, fs_type: FsType) -> FsInfo {
+    let img = Image::embed(inner, offset, 4096).write("fs");
+    let mut probe = Probe::open(img.path(), ProbeFlags::empty(), offset).unwrap();
+
+    probe
+        .search_for_filesystem(fs_type)
+        .unwrap_or_else(|e| panic!("search at offset {offset:#x} failed: {e}"))
+}
+
+fn probe_pt(inner: &Image, offset: u64) -> PtInfo {
+    let img = Image::embed(inner, offset, 0).write("pt");
+    let mut probe = Probe::open(img.path(), ProbeFlags::empty(), offset).unwrap();
+
+    probe
+        .probe_part_table(PtFilter::empty())
+        .unwrap_or_else(|e| panic!("probe at offset {offset:#x} failed: {e}"))
+}
+
+#[test]
+fn filesystems() {
+    for case in FILESYSTEMS {
+        let inner = (case.image)();
+        let (slack, magic_off) = match case.magic {
+            At::Start(off) => (4096, off),
+            At::End(from_end) => (0, inner.len() - from_end),
+        };
+
+        for offset in OFFSETS {
+            let img = Image::embed(&inner, offset, slack).write(case.name);
+            let mut probe = Probe::open(img.path(), ProbeFlags::empty(), offset).unwrap();
+
+            let info = probe
+                .probe_filesystem(FsFilter::empty())
+                .unwrap_or_else(|e| panic!("{} at offset {offset:#x}: {e}", case.name));
+
+            assert_eq!(
+                info.fs_type(),
+                Some(case.fs_type),
+                "{} at offset {offset:#x}",
+                case.name
+            );
+            assert_eq!(
+                info.label().map(String::as_str),
+                case.label,
+                "{} at offset {offset:#x}",
+                case.name
+            );
+            assert_eq!(
+                info.magic_offset(),
+                Some(magic_off),
+                "{} at offset {offset:#x}",
+                case.name
+            );
+            assert_eq!(
+                info.magic_absolute_offset(),
+                Some(offset + magic_off),
+                "{} at offset {offset:#x}",
+                case.name
+            );
+
+            (case.check)(&info);
+        }
+    }
+}
+
+#[test]
+fn before_probe_offset() {
+    // Formats placed before the probe offset are not seen from it.
+    for case in FILESYSTEMS {
+        let inner = (case.image)();
+
+        for offset in OFFSETS.map(|o| inner.len() + o) {
+            let img = Image::embed(&inner, 0, offset - inner.len() + 4096).write(case.name);
+            let mut probe = Probe::open(img.path(), ProbeFlags::empty(), offset).unwrap();
+
+            let res = probe.probe_filesystem(FsFilter::empty());
+            assert!(
+                matches!(res, Err(Error::ProbesExhausted)),
+                "{} at offset {offset:#x}: {res:?}",
+                case.name
+            );
+        }
+    }
+}
+
+#[test]
+fn cramfs_oversized_size() {
+    // A stray cramfs header claiming to run past the end of the device in
+    // front of ext4, the header is rejected without reading up to its size.
+    let mut inner = common::ext("ext4label", 0x0004, 0x0040);
+    inner
+        .put(0, b"\x45\x3d\xcd\x28")
+        .put_le32(4, 0xFFFF_F000) // size
+        .put_le32(8, 0x1); // FSID_VERSION_2
+
+    for offset in OFFSETS {
+        let img = Image::embed(&inner, offset, 4096).write("cramfs");
+        let mut probe = Probe::open(img.path(), ProbeFlags::empty(), offset).unwrap();
+
+        let info = probe
+            .probe_filesystem(FsFilter::empty())
+            .unwrap_or_else(|e| panic!("probe at offset {offset:#x} failed: {e}"));
+        assert_eq!(info.fs_type(), Some(FsType::Ext4), "offset {offset:#x}");
+    }
+}
+
+#[test]
+fn udf_bridge_iso9660() {
+    for offset in OFFSETS {
+        let info = search_fs(&common::udf("UDF Label", true), offset, FsType::Iso9660);
+
+        assert_eq!(info.fs_type(), Some(FsType::Iso9660));
+        assert_eq!(info.label().map(String::as_str), Some("ISOLABEL"));
+        assert_eq!(info.magic_absolute_offset(), Some(offset + 0x8001));
+    }
+}
+
+#[test]
+fn bcachefs_checksum_types() {
+    for csum_type in [0, 1, 2, 5, 6] {
+        let img = common::bcachefs_csum("bcachefslabel", csum_type);
+
+        for offset in OFFSETS {
+            let info = search_fs(&img, offset, FsType::Bcachefs);
+
+            assert_eq!(info.label().map(String::as_str), Some("bcachefslabel"));
+        }
+    }
+}
+
+#[test]
+fn udf_label_compression() {
+    let lvd = 33 * 2048 + 84;
+
+    for (compression, label) in [(255, "UDF Label"), (9, "UDFVOL")] {
+        let mut img = common::udf("UDF Label", false);
+        img.put_u8(lvd, compression);
+
+        for offset in OFFSETS {
+            let info = search_fs(&img, offset, FsType::Udf);
+
+            assert_eq!(info.label().map(String::as_str), Some(label));
+        }
+    }
+}
+
+#[test]
+fn luks_opal() {
+    for offset in OFFSETS {
+        let info = search_fs(&common::luks2(b"HW-OPAL"), offset, FsType::LUKSOpal);
+        assert_eq!(info.fs_type(), Some(FsType::LUKSOpal));
+    }
+}
+
+#[test]
+fn lvm2_known_label() {
+    // The fixture checksums its label with a copy of the prober's crc, both
+    // are checked against a label with a known good checksum.
+    let fixture = common::lvm2("vg_data");
+    let label = &fixture.bytes()[512..1024];
+    assert_eq!(&label[..LVM2_LABEL.len()], LVM2_LABEL);
+    assert!(label[LVM2_LABEL.len()..].iter().all(|&b| b == 0));
+
+    let mut inner = Image::new(1 << 20);
+    inner.put(512, LVM2_LABEL);
+
+    for offset in OFFSETS {
+        let info = search_fs(&inner, offset, FsType::Lvm2Member);
+
+        assert_eq!(
+            info.fs_id()
+                .and_then(|id| id.as_lvm())
+                .map(|id| id.to_string()),
+            Some(common::LVM_PV_UUID_STR.to_string())
+        );
+        assert_eq!(info.magic_absolute_offset(), Some(offset + 512));
+        // The metadata area is blank.
+        assert_eq!(info.volume_group(), None);
+    }
+}
+
+#[test]
+fn bitlocker_not_mbr() {
+    let volumes = [
+        common::bitlocker_vista(),
+        common::bitlocker_win7(),
+        bitlocker_win7_damaged(),
+        common::bitlocker_togo(),
+    ];
+
+    for offset in OFFSETS {
+        for volume in &volumes {
+            // The boot sector ends in 0x55AA but is not an MBR.
+            let img = Image::embed(volume, offset, 0).write("pt");
+            let mut probe = Probe::open(img.path(), ProbeFlags::empty(), offset).unwrap();
+            assert!(probe.probe_part_table(PtFilter::empty()).is_err());
+        }
+    }
+}
+
+#[test]
+fn mbr() {
+    for offset in OFFSETS {
+        let info = probe_pt(&common::mbr(), offset);
+
+        assert_eq!(info.pt_type(), Some(PtType::Mbr));
+        assert_eq!(info.magic_offset(), Some(510));
+        assert_eq!(info.magic_absolute_offset(), Some(offset + 510));
+
+        let parts: Vec<(u64, u64, u64)> = info
+            .partitions()
+            .unwrap()
+            .iter()
+            .map(|p| (p.part_no, p.start, p.end))
+            .collect();
+
+        assert_eq!(
+            parts,
+            [
+                (1, 512, 128 * 512),
+                (2, 128 * 512, 1152 * 512),
+                (5, 129 * 512, 384 * 512),
+                (6, 385 * 512, 640 * 512),
+            ]
+        );
+    }
+}
+
+#[test]
+fn mbr_invalid_boot_indicator() {
+    let mut mbr = common::mbr();
+    mbr.put_u8(446, 0x01);
+
+    for offset in OFFSETS {
+        let img = Image::embed(&mbr, offset, 0).write("pt");
+        let mut probe = Probe::open(img.path(), ProbeFlags::empty(), offset).unwrap();
+        assert!(probe.probe_part_table(PtFilter::empty()).is_err());
+    }
+}
+
+#[test]
+fn gpt() {
+    for offset in OFFSETS {
+        let info = probe_pt(&common::gpt(), offset);
+
+        assert_eq!(info.pt_type(), Some(PtType::Gpt));
+        assert_eq!(info.magic_offset(), Some(512));
+        assert_eq!(info.magic_absolute_offset(), Some(offset + 512));
+
+        let parts = info.partitions().unwrap();
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].start, 34 * 512);
+        assert_eq!(parts[0].partition_name.as_deref(), Some("root"));
+    }
+}
+
+#[test]
+fn aix() {
+    for offset in OFFSETS {
+        let img = Image::embed(&common::aix(), offset, 0).write("aix");
+        let mut probe = Probe::open(img.path(), ProbeFlags::empty(), offset).unwrap();
+
+        let info = probe.search_for_part_table(PtType::Aix).unwrap();
+
+        assert_eq!(info.pt_type(), Some(PtType::Aix));
+        assert_eq!(info.magic_absolute_offset(), Some(offset));
+    }
+}
+
+#[test]
+fn bsd() {
+    for offset in OFFSETS {
+        let info = probe_pt(&common::bsd(), offset);
+
+        assert_eq!(info.pt_type(), Some(PtType::Bsd));
+        assert_eq!(info.magic_offset(), Some(512));
+        assert_eq!(info.magic_absolute_offset(), Some(offset + 512));
+
+        let parts: Vec<_> = info
+            .partitions()
+            .unwrap()
+            .iter()
+            .map(|p| (p.part_no, p.partition_id.as_bsd(), p.start, p.end))
+            .collect();
+
+        assert_eq!(
+            parts,
+            [
+                (1, Some('a'), 16 * 512, 1024 * 512),
+                (2, Some('b'), 1024 * 512, 1536 * 512),
+            ]
+        );
+    }
+}
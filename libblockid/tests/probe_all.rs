@@ -0,0 +1,333 @@
+//! Probes whole disk images with `Probe::probe_all`, checking partitions are
+//! probed within their own bounds, and walks malformed chains of MBR logical
+//! partitions.
+
+#![cfg(all(feature = "std", feature = "os_calls"))]
+
+mod common;
+
+use std::io;
+
+use libblockid::{
+    Probe, ProbeFlags, ProbeTree,
+    error::Error,
+    filesystem::FsType,
+    partition::{PtFilter, PtType},
+};
+
+use common::{Image, OFFSETS};
+
+const MIB: u64 = 1 << 20;
+
+fn probe_all(img: &Image, offset: u64) -> ProbeTree<io::Error> {
+    probe_all_with(img, offset, ProbeFlags::empty())
+}
+
+fn probe_all_with(img: &Image, offset: u64, flags: ProbeFlags) -> ProbeTree<io::Error> {
+    let img = img.write("all");
+    let mut probe = Probe::open(img.path(), flags, offset).unwrap();
+
+    probe.probe_all().unwrap()
+}
+
+/// 8 MiB MBR disk: ext4 in partition 1 and an extended partition holding an
+/// exFAT logical partition too small for exFAT, followed by a FAT16 one.
+fn mbr_disk() -> Image {
+    let mut img = Image::new((8 * MIB) as usize);
+
+    common::mbr_table(&mut img, &[(0x83, 2048, 2048), (0x05, 4096, 12288)]);
+    common::ebr(&mut img, 4096, (0x07, 2048, 2048), Some((4096, 8192)));
+    common::ebr(&mut img, 8192, (0x06, 2048, 4096), None);
+
+    img.put(MIB, common::ext("root", 0x0004, 0x0040).bytes());
+    img.put(3 * MIB, &common::exfat("data").bytes()[..MIB as usize]);
+    img.put(
+        5 * MIB,
+        &common::fat16(b"FAT16VOLUME").bytes()[..(2 * MIB) as usize],
+    );
+
+    img
+}
+
+#[test]
+fn mbr_partitions() {
+    for offset in [0].into_iter().chain(OFFSETS) {
+        let img = Image::embed(&mbr_disk(), offset, 0);
+        let tree = probe_all(&img, offset);
+
+        assert_eq!(tree.offset, offset);
+        assert_eq!(tree.size, 8 * MIB);
+        assert!(tree.filesystem.is_none());
+        assert_eq!(
+            tree.part_table.as_ref().and_then(|pt| pt.pt_type()),
+            Some(PtType::Mbr)
+        );
+
+        let parts: Vec<_> = tree
+            .partitions
+            .iter()
+            .map(|p| {
+                (
+                    p.partition.part_no,
+                    p.contents.as_ref().map(|c| c.offset),
+                    p.contents
+                        .as_ref()
+                        .and_then(|c| c.filesystem.as_ref())
+                        .and_then(|fs| fs.fs_type()),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            parts,
+            [
+                (1, Some(offset + MIB), Some(FsType::Ext4)),
+                (2, None, None),
+                (5, Some(offset + 3 * MIB), None),
+                (6, Some(offset + 5 * MIB), Some(FsType::Vfat)),
+            ]
+        );
+    }
+}
+
+#[test]
+fn gpt_partitions() {
+    let mut disk = Image::new((4 * MIB) as usize);
+
+    common::gpt_table(&mut disk, &[(2048, 4095, "xfs"), (4096, 6143, "luks")]);
+    disk.put(MIB, &common::xfs("xfslabel").bytes()[..MIB as usize]);
+    disk.put(2 * MIB, &common::luks1().bytes()[..MIB as usize]);
+
+    for offset in [0].into_iter().chain(OFFSETS) {
+        let img = Image::embed(&disk, offset, 0);
+        let tree = probe_all(&img, offset);
+
+        assert_eq!(
+            tree.part_table.as_ref().and_then(|pt| pt.pt_type()),
+            Some(PtType::Gpt)
+        );
+        assert_eq!(tree.partitions.len(), 2);
+
+        let xfs = &tree.partitions[0];
+        assert_eq!(xfs.partition.start, MIB);
+        assert_eq!(xfs.partition.end, 2 * MIB);
+
+        let contents = xfs.contents.as_ref().unwrap();
+        assert_eq!(contents.size, MIB);
+
+        let fs = contents.filesystem.as_ref().unwrap();
+        assert_eq!(fs.fs_type(), Some(FsType::Xfs));
+        assert_eq!(fs.label().map(String::as_str), Some("xfslabel"));
+        assert_eq!(fs.magic_offset(), Some(0));
+        assert_eq!(fs.magic_absolute_offset(), Some(offset + MIB));
+
+        let luks = tree.partitions[1].contents.as_ref().unwrap();
+        assert_eq!(
+            luks.filesystem.as_ref().and_then(|fs| fs.fs_type()),
+            Some(FsType::LUKS1)
+        );
+    }
+}
+
+#[test]
+fn partition_error_recorded() {
+    // A FAT16 boot sector left behind under ext4 in the first partition
+    // does not stop the second from being probed.
+    let mut stale = common::fat16(b"FAT16VOLUME");
+    stale.put(
+        1024,
+        &common::ext("ext4label", 0x0004, 0x0040).bytes()[1024..2048],
+    );
+
+    let mut disk = Image::new((4 * MIB) as usize);
+    common::gpt_table(&mut disk, &[(2048, 4095, "stale"), (4096, 6143, "xfs")]);
+    disk.put(MIB, &stale.bytes()[..MIB as usize]);
+    disk.put(2 * MIB, &common::xfs("xfslabel").bytes()[..MIB as usize]);
+
+    for offset in [0].into_iter().chain(OFFSETS) {
+        let img = Image::embed(&disk, offset, 0);
+        let tree = probe_all_with(&img, offset, ProbeFlags::FailOnAmbivalent);
+
+        assert!(tree.fs_error.is_none() && tree.pt_error.is_none());
+        assert_eq!(tree.partitions.len(), 2);
+
+        let stale = tree.partitions[0].contents.as_ref().unwrap();
+        assert!(stale.filesystem.is_none());
+        assert!(matches!(
+            &stale.fs_error,
+            Some(Error::Ambivalent(types)) if types == &[FsType::Ext4, FsType::Vfat]
+        ));
+
+        let xfs = tree.partitions[1].contents.as_ref().unwrap();
+        assert_eq!(
+            xfs.filesystem.as_ref().and_then(|fs| fs.fs_type()),
+            Some(FsType::Xfs)
+        );
+    }
+}
+
+#[test]
+fn unpartitioned() {
+    let tree = probe_all(&common::xfs("xfslabel"), 0);
+
+    assert!(tree.part_table.is_none());
+    assert!(tree.partitions.is_empty());
+    assert_eq!(
+        tree.filesystem.as_ref().and_then(|fs| fs.fs_type()),
+        Some(FsType::Xfs)
+    );
+}
+
+#[test]
+fn hybrid_iso9660() {
+    // isohybrid style MBR in the system area, with a partition starting
+    // inside the ISO9660 volume.
+    let mut img = Image::new((2 * MIB) as usize);
+    img.put(0, common::iso9660("ISOLABEL", None).bytes());
+    common::mbr_table(&mut img, &[(0x17, 64, 4032)]);
+
+    for offset in [0].into_iter().chain(OFFSETS) {
+        let img = Image::embed(&img, offset, 0);
+        let tree = probe_all(&img, offset);
+
+        assert_eq!(
+            tree.part_table.as_ref().and_then(|pt| pt.pt_type()),
+            Some(PtType::Mbr)
+        );
+
+        let fs = tree.filesystem.as_ref().unwrap();
+        assert_eq!(fs.fs_type(), Some(FsType::Iso9660));
+        assert_eq!(fs.label().map(String::as_str), Some("ISOLABEL"));
+        assert_eq!(tree.partitions.len(), 1);
+    }
+}
+
+#[test]
+fn bsd_slice() {
+    // FreeBSD before 10 gave partition offsets from the start of the disk,
+    // later releases from the start of the slice.
+    for base in [2048, 0] {
+        let mut disk = Image::new((4 * MIB) as usize);
+        common::mbr_table(&mut disk, &[(0xA5, 2048, 6144)]);
+
+        // The boot block of the slice carries an MBR signature of its own.
+        disk.put(MIB + 510, &[0x55, 0xAA]);
+        common::bsd_label(
+            &mut disk,
+            MIB + 512,
+            &[
+                (7, base + 2048, 2048),
+                (1, base + 16, 2032),
+                (0, base, 6144),
+            ],
+        );
+        disk.put(2 * MIB, common::ufs("root", true, false).bytes());
+
+        for offset in [0].into_iter().chain(OFFSETS) {
+            let img = Image::embed(&disk, offset, 0);
+            let tree = probe_all(&img, offset);
+
+            assert_eq!(
+                tree.part_table.as_ref().and_then(|pt| pt.pt_type()),
+                Some(PtType::Mbr)
+            );
+
+            let slice = tree.partitions[0].contents.as_ref().unwrap();
+            assert_eq!(
+                slice.part_table.as_ref().and_then(|pt| pt.pt_type()),
+                Some(PtType::Bsd)
+            );
+
+            let parts: Vec<_> = slice
+                .partitions
+                .iter()
+                .map(|p| {
+                    (
+                        p.partition.partition_id.as_bsd(),
+                        p.contents.as_ref().map(|c| c.offset),
+                        p.contents
+                            .as_ref()
+                            .and_then(|c| c.filesystem.as_ref())
+                            .and_then(|fs| fs.fs_type()),
+                    )
+                })
+                .collect();
+
+            assert_eq!(
+                parts,
+                [
+                    (Some('a'), Some(offset + 2 * MIB), Some(FsType::Ufs)),
+                    (Some('b'), Some(offset + MIB + 16 * 512), None),
+                ]
+            );
+        }
+    }
+}
+
+/// MBR disk whose extended partition holds a chain of `links` EBRs two
+/// sectors apart, each with a one sector logical partition when `data` is set.
+fn ebr_chain(links: u32, data: impl Fn(u32) -> bool) -> Image {
+    let mut img = Image::new(((128 + 2 * links as usize + 2) * 512).max(MIB as usize));
+
+    common::mbr_table(&mut img, &[(0x83, 1, 127), (0x05, 128, 2 * links + 2)]);
+    for link in 0..links {
+        let logical = match data(link) {
+            true => (0x83, 1, 1),
+            false => (0x83, 1, 0),
+        };
+        let next = (link + 1 < links).then_some((2 * (link + 1), 2));
+
+        common::ebr(&mut img, 128 + 2 * u64::from(link), logical, next);
+    }
+
+    img
+}
+
+fn part_numbers(img: &Image) -> Vec<u64> {
+    let img = img.write("ebr");
+    let mut probe = Probe::open(img.path(), ProbeFlags::empty(), 0).unwrap();
+    let info = probe.probe_part_table(PtFilter::empty()).unwrap();
+
+    info.partitions()
+        .unwrap()
+        .iter()
+        .map(|p| p.part_no)
+        .collect()
+}
+
+#[test]
+fn mbr_ebr_self_loop() {
+    let mut img = ebr_chain(2, |_| true);
+    // Point the second EBR back at itself.
+    common::ebr(&mut img, 130, (0x83, 1, 1), Some((2, 2)));
+
+    assert_eq!(part_numbers(&img), [1, 2, 5, 6]);
+}
+
+#[test]
+fn mbr_ebr_loop() {
+    let mut img = ebr_chain(3, |_| true);
+    // Point the last EBR back at the second one.
+    common::ebr(&mut img, 132, (0x83, 1, 1), Some((2, 2)));
+
+    assert_eq!(part_numbers(&img), [1, 2, 5, 6, 7]);
+}
+
+#[test]
+fn mbr_ebr_chain_limit() {
+    // Only the EBR after 150 empty links holds a partition, the walk gives up
+    // before reaching it.
+    let img = ebr_chain(151, |link| link == 150);
+
+    assert_eq!(part_numbers(&img), [1, 2]);
+}
+
+#[test]
+fn mbr_ebr_part_no_overflow() {
+    // Logical partitions are numbered from 5, so only 251 fit in a u8.
+    let img = ebr_chain(300, |_| true);
+    let parts = part_numbers(&img);
+
+    assert_eq!(parts.len(), 2 + 251);
+    assert_eq!(parts.last(), Some(&255));
+}
@@ -0,0 +1,743 @@
+//! Explains detection with `Probe::probe_report`, checking each handler's
+//! outcome is recorded.
+
+#![cfg(all(feature = "std", feature = "os_calls"))]
+
+mod common;
+
+use std::io;
+
+use libblockid::{
+    Probe, ProbeFlags, ProbeOutcome, ProbeReport, SignatureType,
+    error::{
+        BcachefsError, BitlockerError, BsdError, BtrfsError, CramfsError, ErofsError, Error,
+        ExtError, F2fsError, FwRaidError, HfsError, IntegrityError, Iso9660Error, JfsError,
+        LinuxRaidError, LvmError, MinixError, NilfsError, ReiserfsError, SquashfsError, SwapError,
+        UdfError, UfsError, VerityError, VxfsError, ZfsError,
+    },
+    filesystem::{FS_DETECT_ORDER, FsFilter, FsType},
+    partition::{PT_DETECT_ORDER, PtFilter, PtType},
+};
+
+use common::{Image, OFFSETS};
+
+fn report(inner: &Image, offset: u64, fs_filter: FsFilter) -> ProbeReport<io::Error> {
+    let img = Image::embed(inner, offset, 4096).write("report");
+    let mut probe = Probe::open(img.path(), ProbeFlags::empty(), offset).unwrap();
+
+    probe.probe_report(fs_filter, PtFilter::empty())
+}
+
+/// Like `report`, for formats found from the end of the device, which is
+/// embedded without slack.
+fn report_at_end(inner: &Image, offset: u64) -> ProbeReport<io::Error> {
+    let img = Image::embed(inner, offset, 0).write("report");
+    let mut probe = Probe::open(img.path(), ProbeFlags::empty(), offset).unwrap();
+
+    probe.probe_report(FsFilter::empty(), PtFilter::empty())
+}
+
+fn outcome(report: &ProbeReport<io::Error>, kind: SignatureType) -> &ProbeOutcome<io::Error> {
+    &report
+        .attempts
+        .iter()
+        .find(|a| a.kind == kind)
+        .unwrap_or_else(|| panic!("{kind} not attempted"))
+        .outcome
+}
+
+#[test]
+fn every_handler_attempted() {
+    let report = report(&common::xfs("xfslabel"), 0, FsFilter::empty());
+
+    let kinds: Vec<_> = report.attempts.iter().map(|a| a.kind).collect();
+    let expected: Vec<_> = FS_DETECT_ORDER
+        .iter()
+        .map(|(_, t)| SignatureType::Filesystem(*t))
+        .chain(
+            PT_DETECT_ORDER
+                .iter()
+                .map(|(_, t)| SignatureType::PartTable(*t)),
+        )
+        .collect();
+
+    assert_eq!(kinds, expected);
+    assert_eq!(
+        report.filesystem().and_then(|fs| fs.fs_type()),
+        Some(FsType::Xfs)
+    );
+
+    // The data device is not taken for its own external log.
+    assert!(matches!(
+        outcome(&report, SignatureType::Filesystem(FsType::XfsExternalLog)),
+        ProbeOutcome::MagicNotFound
+    ));
+}
+
+#[test]
+fn rejected_ext4() {
+    // ext4 flagged as a test filesystem is left for ext4dev.
+    let mut img = common::ext("ext4label", 0x0004, 0x0040);
+    img.put_le32(1024 + 0x160, 0x0004);
+
+    for offset in OFFSETS {
+        let report = report(&img, offset, FsFilter::SKIP_XFS);
+
+        let ext4 = report
+            .attempts
+            .iter()
+            .find(|a| a.kind == SignatureType::Filesystem(FsType::Ext4))
+            .unwrap();
+
+        assert_eq!(ext4.magic.map(|m| m.b_offset), Some(0x438));
+        assert!(
+            matches!(
+                ext4.outcome,
+                ProbeOutcome::Rejected(Error::Ext(ExtError::ProbablyExt4Dev))
+            ),
+            "{:?}",
+            ext4.outcome
+        );
+
+        assert!(matches!(
+            outcome(&report, SignatureType::Filesystem(FsType::Xfs)),
+            ProbeOutcome::Filtered
+        ));
+        assert!(matches!(
+            outcome(&report, SignatureType::Filesystem(FsType::Ntfs)),
+            ProbeOutcome::MagicNotFound
+        ));
+        assert!(matches!(
+            outcome(&report, SignatureType::Filesystem(FsType::Exfat)),
+            ProbeOutcome::DeviceTooSmall
+        ));
+        assert!(matches!(
+            outcome(&report, SignatureType::PartTable(PtType::Gpt)),
+            ProbeOutcome::Rejected(Error::Gpt(_))
+        ));
+        assert!(report.filesystem().is_none());
+    }
+}
+
+#[test]
+fn found_filesystem() {
+    for offset in OFFSETS {
+        let report = report(
+            &common::ext("ext4label", 0x0004, 0x0040),
+            offset,
+            FsFilter::empty(),
+        );
+
+        let info = report.filesystem().unwrap();
+        assert_eq!(info.fs_type(), Some(FsType::Ext4));
+        assert_eq!(info.magic_absolute_offset(), Some(offset + 0x438));
+
+        assert!(matches!(
+            outcome(&report, SignatureType::Filesystem(FsType::Ext2)),
+            ProbeOutcome::Rejected(Error::Ext(_))
+        ));
+        assert!(report.part_table().is_none());
+    }
+}
+
+/// A damaged image and the error its handler should reject it with.
+struct Rejection {
+    name: &'static str,
+    image: fn() -> Image,
+    fs_type: FsType,
+    error: fn(&Error<io::Error>) -> bool,
+}
+
+const REJECTIONS: &[Rejection] = &[
+    Rejection {
+        name: "cramfs_checksum",
+        image: || {
+            let mut img = common::cramfs("cramfslabel", true);
+            img.put_u8(0x9000, 0xFF);
+            img
+        },
+        fs_type: FsType::Cramfs,
+        error: |e| matches!(e, Error::Cramfs(CramfsError::InvalidChecksum)),
+    },
+    Rejection {
+        // Ends one byte past the end of the device.
+        name: "cramfs_size_past_end",
+        image: || {
+            let mut img = common::cramfs("cramfslabel", true);
+            img.put_le32(4, (96 << 10) + 4096 + 1);
+            img
+        },
+        fs_type: FsType::Cramfs,
+        error: |e| matches!(e, Error::Cramfs(CramfsError::InvalidSize)),
+    },
+    Rejection {
+        name: "btrfs_checksum",
+        image: || {
+            let mut img = common::btrfs("btrfslabel");
+            img.put_u8(0x10000 + 0x200, 0xFF);
+            img
+        },
+        fs_type: FsType::Btrfs,
+        error: |e| matches!(e, Error::Btrfs(BtrfsError::InvalidChecksum)),
+    },
+    Rejection {
+        // A superblock copied to the wrong place.
+        name: "btrfs_bytenr",
+        image: || {
+            let mut img = common::btrfs("btrfslabel");
+            img.put_le64(0x10000 + 0x30, 0x4000000);
+            img
+        },
+        fs_type: FsType::Btrfs,
+        error: |e| matches!(e, Error::Btrfs(BtrfsError::InvalidBytenr)),
+    },
+    Rejection {
+        name: "vxfs_block_size",
+        image: || {
+            let mut img = common::vxfs(true);
+            img.put_le32(1024 + 32, 3000);
+            img
+        },
+        fs_type: FsType::Vxfs,
+        error: |e| matches!(e, Error::Vxfs(VxfsError::InvalidBlockSize(3000))),
+    },
+    Rejection {
+        name: "vxfs_version",
+        image: || {
+            let mut img = common::vxfs(false);
+            img.put_be32(8192 + 4, 1);
+            img
+        },
+        fs_type: FsType::Vxfs,
+        error: |e| matches!(e, Error::Vxfs(VxfsError::UnsupportedVersion(1))),
+    },
+    Rejection {
+        // Data size larger than the filesystem.
+        name: "vxfs_size",
+        image: || {
+            let mut img = common::vxfs(true);
+            img.put_le32(1024 + 40, 4096);
+            img
+        },
+        fs_type: FsType::Vxfs,
+        error: |e| matches!(e, Error::Vxfs(VxfsError::InvalidSize)),
+    },
+    Rejection {
+        // block_size disagreeing with block_log.
+        name: "squashfs_block_size",
+        image: || {
+            let mut img = common::squashfs();
+            img.put_le16(22, 18);
+            img
+        },
+        fs_type: FsType::Squashfs,
+        error: |e| matches!(e, Error::Squashfs(SquashfsError::InvalidBlockSize(0x20000))),
+    },
+    Rejection {
+        name: "squashfs_version",
+        image: || {
+            let mut img = common::squashfs();
+            img.put_le16(28, 5);
+            img
+        },
+        fs_type: FsType::Squashfs,
+        error: |e| matches!(e, Error::Squashfs(SquashfsError::UnsupportedVersion(5, 0))),
+    },
+    Rejection {
+        name: "erofs_block_size",
+        image: || {
+            let mut img = common::erofs("erofslabel");
+            img.put_u8(1024 + 12, 30);
+            img
+        },
+        fs_type: FsType::Erofs,
+        error: |e| matches!(e, Error::Erofs(ErofsError::InvalidBlockSize(30))),
+    },
+    Rejection {
+        name: "erofs_checksum",
+        image: || {
+            let mut img = common::erofs("erofslabel");
+            img.put_u8(2048, 0xFF);
+            img
+        },
+        fs_type: FsType::Erofs,
+        error: |e| matches!(e, Error::Erofs(ErofsError::InvalidChecksum)),
+    },
+    Rejection {
+        name: "iso9660_block_size",
+        image: || {
+            let mut img = common::iso9660("ISOLABEL", None);
+            img.put_le16(0x8000 + 128, 1000);
+            img
+        },
+        fs_type: FsType::Iso9660,
+        error: |e| matches!(e, Error::Iso9660(Iso9660Error::InvalidBlockSize(1000))),
+    },
+    Rejection {
+        // The only descriptor before the terminator is a partition one.
+        name: "iso9660_no_primary_descriptor",
+        image: || {
+            let mut img = common::iso9660("ISOLABEL", None);
+            img.put_u8(0x8000, 3);
+            img
+        },
+        fs_type: FsType::Iso9660,
+        error: |e| matches!(e, Error::Iso9660(Iso9660Error::MissingPrimaryDescriptor)),
+    },
+    Rejection {
+        name: "udf_anchor_checksum",
+        image: || {
+            let mut img = common::udf("UDF Label", false);
+            img.put_u8(256 * 2048 + 4, 0);
+            img
+        },
+        fs_type: FsType::Udf,
+        error: |e| matches!(e, Error::Udf(UdfError::MissingAnchor)),
+    },
+    Rejection {
+        name: "udf_volume_descriptor_checksum",
+        image: || {
+            let mut img = common::udf("UDF Label", false);
+            img.put_u8(32 * 2048 + 4, 0);
+            img
+        },
+        fs_type: FsType::Udf,
+        error: |e| matches!(e, Error::Udf(UdfError::MissingVolumeDescriptor)),
+    },
+    Rejection {
+        name: "f2fs_checksum",
+        image: || {
+            let mut img = common::f2fs("f2fslabel");
+            img.put_u8(1024 + 2048, 0xFF);
+            img
+        },
+        fs_type: FsType::F2fs,
+        error: |e| matches!(e, Error::F2fs(F2fsError::InvalidChecksum)),
+    },
+    Rejection {
+        // 2^9 byte sectors and 2^4 sectors per block disagree with the
+        // 2^12 byte block size.
+        name: "f2fs_block_size",
+        image: || {
+            let mut img = common::f2fs("f2fslabel");
+            img.put_le32(1024 + 12, 4);
+            img
+        },
+        fs_type: FsType::F2fs,
+        error: |e| matches!(e, Error::F2fs(F2fsError::InvalidBlockSize(12))),
+    },
+    Rejection {
+        name: "bcachefs_version",
+        image: || {
+            let mut img = common::bcachefs("bcachefslabel");
+            img.put_le16(4096 + 16, 8);
+            img
+        },
+        fs_type: FsType::Bcachefs,
+        error: |e| matches!(e, Error::Bcachefs(BcachefsError::UnsupportedVersion(8))),
+    },
+    Rejection {
+        name: "bcachefs_checksum",
+        image: || {
+            let mut img = common::bcachefs("bcachefslabel");
+            img.put_u8(4096 + 72, b'B');
+            img
+        },
+        fs_type: FsType::Bcachefs,
+        error: |e| matches!(e, Error::Bcachefs(BcachefsError::InvalidChecksum)),
+    },
+    Rejection {
+        name: "bcachefs_zero_seeded_checksum",
+        image: || {
+            let mut img = common::bcachefs_csum("bcachefslabel", 6);
+            let nonzero = common::bcachefs_csum("bcachefslabel", 2);
+            img.put(4096, &nonzero.bytes()[4096..4104]);
+            img
+        },
+        fs_type: FsType::Bcachefs,
+        error: |e| matches!(e, Error::Bcachefs(BcachefsError::InvalidChecksum)),
+    },
+    Rejection {
+        name: "swap_version",
+        image: || {
+            let mut img = common::swap("swaplabel", 4096, false);
+            img.put_le32(1024, 2);
+            img
+        },
+        fs_type: FsType::Swap,
+        error: |e| matches!(e, Error::Swap(SwapError::UnsupportedVersion(2))),
+    },
+    Rejection {
+        name: "swap_last_page",
+        image: || {
+            let mut img = common::swap("swaplabel", 4096, true);
+            img.put_be32(1028, 0);
+            img
+        },
+        fs_type: FsType::Swap,
+        error: |e| matches!(e, Error::Swap(SwapError::InvalidLastPage)),
+    },
+    Rejection {
+        name: "hfs_block_size",
+        image: || {
+            let mut img = common::hfs("hfslabel");
+            img.put_be32(1024 + 20, 1000);
+            img
+        },
+        fs_type: FsType::Hfs,
+        error: |e| matches!(e, Error::Hfs(HfsError::InvalidBlockSize(1000))),
+    },
+    Rejection {
+        // An HFS+ signature with the HFSX version.
+        name: "hfsplus_version",
+        image: || {
+            let mut img = common::hfsplus("hfspluslabel", false);
+            img.put_be16(1024 + 2, 5);
+            img
+        },
+        fs_type: FsType::HfsPlus,
+        error: |e| matches!(e, Error::Hfs(HfsError::UnsupportedVersion(5))),
+    },
+    Rejection {
+        // A wrapper whose embedded volume has lost its header.
+        name: "hfs_embedded_signature",
+        image: || {
+            let mut img = common::hfs_wrapper("hfspluslabel");
+            img.put(4 * 512 + 4096 + 1024, b"XX");
+            img
+        },
+        fs_type: FsType::Hfs,
+        error: |e| matches!(e, Error::Hfs(HfsError::InvalidEmbeddedSignature)),
+    },
+    Rejection {
+        // Uberblocks from a newer pool version than any known.
+        name: "zfs_uberblock_version",
+        image: || {
+            let mut img = common::zfs("tank", true);
+            for l in [0, 256 << 10, 512 << 10, 768 << 10] {
+                img.put_le64(l + (130 << 10) + 8, 5001);
+            }
+            img
+        },
+        fs_type: FsType::Zfs,
+        error: |e| matches!(e, Error::Zfs(ZfsError::MissingUberblock)),
+    },
+    Rejection {
+        name: "reiserfs_block_size",
+        image: || {
+            let mut img = common::reiserfs("reiserlabel", b"ReIsEr2Fs");
+            img.put_le16((64 << 10) + 44, 1000);
+            img
+        },
+        fs_type: FsType::Reiserfs,
+        error: |e| matches!(e, Error::Reiserfs(ReiserfsError::InvalidBlockSize(1000))),
+    },
+    Rejection {
+        // A copy of the superblock logged past the start of the journal.
+        name: "reiserfs_superblock_in_journal",
+        image: || {
+            let mut img = common::reiserfs("reiserlabel", b"ReIsEr2Fs");
+            img.put_le32((64 << 10) + 12, 2);
+            img
+        },
+        fs_type: FsType::Reiserfs,
+        error: |e| matches!(e, Error::Reiserfs(ReiserfsError::SuperblockInJournal)),
+    },
+    Rejection {
+        name: "jfs_block_factor",
+        image: || {
+            let mut img = common::jfs("jfslabel");
+            img.put_le16((32 << 10) + 22, 4);
+            img
+        },
+        fs_type: FsType::Jfs,
+        error: |e| matches!(e, Error::Jfs(JfsError::InvalidBlockFactor(4))),
+    },
+    Rejection {
+        name: "nilfs2_checksum",
+        image: || {
+            let mut img = common::nilfs2("nilfslabel");
+            img.put_u8(1024 + 168, b'N');
+            img.put_u8((124 << 10) + 168, b'N');
+            img
+        },
+        fs_type: FsType::Nilfs2,
+        error: |e| matches!(e, Error::Nilfs(NilfsError::InvalidChecksum)),
+    },
+    Rejection {
+        name: "minix_state",
+        image: || {
+            let mut img = common::minix(1, false);
+            img.put_le16(1024 + 18, 4);
+            img
+        },
+        fs_type: FsType::Minix,
+        error: |e| matches!(e, Error::Minix(MinixError::InvalidState(4))),
+    },
+    Rejection {
+        // No inode bitmap blocks for the inodes.
+        name: "minix_bitmaps",
+        image: || {
+            let mut img = common::minix(3, true);
+            img.put_be16(1024 + 6, 0);
+            img
+        },
+        fs_type: FsType::Minix,
+        error: |e| matches!(e, Error::Minix(MinixError::InvalidBitmaps)),
+    },
+    Rejection {
+        name: "ufs_block_size",
+        image: || {
+            let mut img = common::ufs("ufslabel", false, false);
+            img.put_le32((8 << 10) + 48, 3000);
+            img
+        },
+        fs_type: FsType::Ufs,
+        error: |e| matches!(e, Error::Ufs(UfsError::InvalidBlockSize(3000))),
+    },
+    Rejection {
+        // Fragments larger than the blocks they divide.
+        name: "ufs_fragment_size",
+        image: || {
+            let mut img = common::ufs("ufslabel", true, true);
+            img.put_be32((64 << 10) + 52, 32768);
+            img
+        },
+        fs_type: FsType::Ufs,
+        error: |e| matches!(e, Error::Ufs(UfsError::InvalidFragmentSize(32768))),
+    },
+    Rejection {
+        name: "md0_checksum",
+        image: || {
+            let mut img = common::md0(true);
+            img.put_be32(img.len() - (64 << 10) + 13 * 4, 0);
+            img
+        },
+        fs_type: FsType::LinuxRaidMember,
+        error: |e| matches!(e, Error::LinuxRaid(LinuxRaidError::InvalidChecksum)),
+    },
+    Rejection {
+        name: "md1_version",
+        image: || {
+            let mut img = common::md1(1, "mdlabel");
+            img.put_le32(4, 2);
+            img
+        },
+        fs_type: FsType::LinuxRaidMember,
+        error: |e| {
+            matches!(
+                e,
+                Error::LinuxRaid(LinuxRaidError::UnsupportedVersion(2, 1))
+            )
+        },
+    },
+    Rejection {
+        // A 1.2 superblock recording that it belongs at the start of the
+        // device.
+        name: "md1_super_offset",
+        image: || {
+            let mut img = common::md1(2, "mdlabel");
+            img.put_le64(4096 + 144, 0);
+            img
+        },
+        fs_type: FsType::LinuxRaidMember,
+        error: |e| {
+            matches!(
+                e,
+                Error::LinuxRaid(LinuxRaidError::SuperblockOffsetMismatch(0))
+            )
+        },
+    },
+    Rejection {
+        name: "lvm2_checksum",
+        image: || {
+            let mut img = common::lvm2("vg_data");
+            img.put_u8(512 + 32, b'A');
+            img
+        },
+        fs_type: FsType::Lvm2Member,
+        error: |e| matches!(e, Error::Lvm(LvmError::InvalidChecksum)),
+    },
+    Rejection {
+        // A label copied from the first sector.
+        name: "lvm2_sector",
+        image: || {
+            let mut img = common::lvm2("vg_data");
+            img.put_le64(512 + 8, 0);
+            img
+        },
+        fs_type: FsType::Lvm2Member,
+        error: |e| matches!(e, Error::Lvm(LvmError::SectorMismatch(0))),
+    },
+    Rejection {
+        name: "verity_block_size",
+        image: || {
+            let mut img = common::verity();
+            img.put_le32(64, 1000);
+            img
+        },
+        fs_type: FsType::DmVerityHash,
+        error: |e| matches!(e, Error::Verity(VerityError::InvalidBlockSize(1000))),
+    },
+    Rejection {
+        // More salt than fits in the superblock.
+        name: "verity_salt_size",
+        image: || {
+            let mut img = common::verity();
+            img.put_le16(80, 257);
+            img
+        },
+        fs_type: FsType::DmVerityHash,
+        error: |e| matches!(e, Error::Verity(VerityError::InvalidSaltSize(257))),
+    },
+    Rejection {
+        name: "integrity_version",
+        image: || {
+            let mut img = common::integrity();
+            img.put_u8(8, 7);
+            img
+        },
+        fs_type: FsType::DmIntegrity,
+        error: |e| matches!(e, Error::Integrity(IntegrityError::UnsupportedVersion(7))),
+    },
+    Rejection {
+        name: "integrity_tag_size",
+        image: || {
+            let mut img = common::integrity();
+            img.put_le16(10, 0);
+            img
+        },
+        fs_type: FsType::DmIntegrity,
+        error: |e| matches!(e, Error::Integrity(IntegrityError::InvalidTagSize)),
+    },
+    Rejection {
+        // A Vista header is not enough without its FVE metadata block.
+        name: "bitlocker_vista_metadata",
+        image: || {
+            let mut img = common::bitlocker_vista();
+            img.put(0x10000, &[0; 8]);
+            img
+        },
+        fs_type: FsType::Bitlocker,
+        error: |e| matches!(e, Error::Bitlocker(BitlockerError::MissingMetadata)),
+    },
+    Rejection {
+        // The cluster of the FVE metadata block is past the end.
+        name: "bitlocker_vista_cluster",
+        image: || {
+            let mut img = common::bitlocker_vista();
+            img.put_le64(0x38, 1 << 20);
+            img
+        },
+        fs_type: FsType::Bitlocker,
+        error: |e| matches!(e, Error::Bitlocker(BitlockerError::MissingMetadata)),
+    },
+];
+
+#[test]
+fn rejected() {
+    for case in REJECTIONS {
+        let img = (case.image)();
+
+        for offset in OFFSETS {
+            let report = report(&img, offset, FsFilter::empty());
+
+            match outcome(&report, SignatureType::Filesystem(case.fs_type)) {
+                ProbeOutcome::Rejected(e) if (case.error)(e) => {}
+                outcome => panic!("{} at offset {offset:#x}: {outcome:?}", case.name),
+            }
+            assert!(
+                report.filesystem().is_none(),
+                "{} at offset {offset:#x}: {:?}",
+                case.name,
+                report.filesystem()
+            );
+        }
+    }
+}
+
+#[test]
+fn invalid_xfs_log_record() {
+    // A log record header with unknown version bits is not taken for a log.
+    let mut img = common::xfs_log();
+    img.put_be32(1024 + 8, 0x10);
+
+    for offset in OFFSETS {
+        let report = report(&img, offset, FsFilter::empty());
+
+        assert!(matches!(
+            outcome(&report, SignatureType::Filesystem(FsType::XfsExternalLog)),
+            ProbeOutcome::MagicNotFound
+        ));
+        assert!(report.filesystem().is_none());
+    }
+}
+
+#[test]
+fn invalid_bsd_checksum() {
+    let mut img = common::bsd();
+    img.put_le32(512 + 148, 1009);
+
+    for offset in OFFSETS {
+        let report = report(&img, offset, FsFilter::empty());
+
+        assert!(matches!(
+            outcome(&report, SignatureType::PartTable(PtType::Bsd)),
+            ProbeOutcome::Rejected(Error::Bsd(BsdError::InvalidChecksum))
+        ));
+        assert!(report.part_table().is_none());
+    }
+}
+
+#[test]
+fn invalid_isw_metadata() {
+    let mut bad_csum = common::isw();
+    bad_csum.put(bad_csum.len() - 3 * 512 + 16, b"Volume1");
+    let mut bad_size = common::isw();
+    bad_size.put_le32(bad_size.len() - 2 * 512 + 36, 2 << 20);
+    // Larger than mdadm reads, though it would fit on the device.
+    let mut too_large = common::isw();
+    too_large.put_le32(too_large.len() - 2 * 512 + 36, (32 << 10) + 4);
+
+    for offset in OFFSETS {
+        let report = report_at_end(&bad_csum, offset);
+        assert!(matches!(
+            outcome(&report, SignatureType::Filesystem(FsType::IswRaidMember)),
+            ProbeOutcome::Rejected(Error::FwRaid(FwRaidError::InvalidChecksum))
+        ));
+        assert!(report.filesystem().is_none());
+
+        let report = report_at_end(&bad_size, offset);
+        assert!(matches!(
+            outcome(&report, SignatureType::Filesystem(FsType::IswRaidMember)),
+            ProbeOutcome::Rejected(Error::FwRaid(FwRaidError::InvalidSize(0x200000)))
+        ));
+        assert!(report.filesystem().is_none());
+
+        let report = report_at_end(&too_large, offset);
+        assert!(matches!(
+            outcome(&report, SignatureType::Filesystem(FsType::IswRaidMember)),
+            ProbeOutcome::Rejected(Error::FwRaid(FwRaidError::InvalidSize(0x8004)))
+        ));
+    }
+}
+
+#[test]
+fn bitlocker_identifier() {
+    // FAT32 has the boot code and OEM name of a BitLocker To Go header, and
+    // a Windows 7 header needs its identifier too.
+    let fat32 = common::fat32(b"FAT32VOLUME");
+    let mut win7 = common::bitlocker_win7();
+    win7.put(160, &[0; 16]);
+
+    for offset in OFFSETS {
+        for img in [&fat32, &win7] {
+            let report = report(img, offset, FsFilter::empty());
+
+            assert!(matches!(
+                outcome(&report, SignatureType::Filesystem(FsType::Bitlocker)),
+                ProbeOutcome::MagicNotFound
+            ));
+        }
+    }
+}
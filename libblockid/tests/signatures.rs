@@ -0,0 +1,89 @@
+//! Lists every signature on an image with `Probe::probe_all_signatures`.
+
+#![cfg(all(feature = "std", feature = "os_calls"))]
+
+mod common;
+
+use libblockid::{
+    Probe, ProbeFlags, Signature, SignatureType, filesystem::FsType, partition::PtType,
+};
+
+use common::{Image, OFFSETS};
+
+fn signatures(inner: &Image, offset: u64) -> Vec<(SignatureType, Option<u64>)> {
+    let img = Image::embed(inner, offset, 4096).write("signatures");
+    let mut probe = Probe::open(img.path(), ProbeFlags::empty(), offset).unwrap();
+
+    probe
+        .probe_all_signatures()
+        .map(|(kind, result)| {
+            let magic = result.ok().map(|sig| match sig {
+                Signature::Filesystem(info) => {
+                    assert_eq!(SignatureType::Filesystem(info.fs_type().unwrap()), kind);
+                    info.magic_absolute_offset().unwrap()
+                }
+                Signature::PartTable(info) => {
+                    assert_eq!(SignatureType::PartTable(info.pt_type().unwrap()), kind);
+                    info.magic_absolute_offset().unwrap()
+                }
+            });
+            (kind, magic)
+        })
+        .collect()
+}
+
+#[test]
+fn stale_vfat_under_ext4() {
+    let mut img = common::fat16(b"FAT16VOLUME");
+    let ext = common::ext("ext4label", 0x0004, 0x0040);
+    img.put(1024, &ext.bytes()[1024..2048]);
+
+    for offset in OFFSETS {
+        assert_eq!(
+            signatures(&img, offset),
+            [
+                (SignatureType::Filesystem(FsType::Jbd), None),
+                (SignatureType::Filesystem(FsType::Ext2), None),
+                (SignatureType::Filesystem(FsType::Ext3), None),
+                (
+                    SignatureType::Filesystem(FsType::Ext4),
+                    Some(offset + 0x438)
+                ),
+                (SignatureType::Filesystem(FsType::Vfat), Some(offset + 0x36)),
+                (SignatureType::PartTable(PtType::Mbr), None),
+            ]
+        );
+    }
+}
+
+#[test]
+fn gpt_with_protective_mbr() {
+    for offset in OFFSETS {
+        assert_eq!(
+            signatures(&common::gpt(), offset),
+            [
+                (SignatureType::PartTable(PtType::Gpt), Some(offset + 512)),
+                (SignatureType::PartTable(PtType::Mbr), None),
+            ]
+        );
+    }
+}
+
+#[test]
+fn empty_device() {
+    assert!(signatures(&Image::new(1 << 20), 0).is_empty());
+}
+
+#[test]
+fn fat32_not_bitlocker() {
+    // FAT32 starts like a BitLocker To Go header, it has no identifier.
+    for offset in OFFSETS {
+        assert_eq!(
+            signatures(&common::fat32(b"FAT32VOLUME"), offset),
+            [
+                (SignatureType::Filesystem(FsType::Vfat), Some(offset + 0x52)),
+                (SignatureType::PartTable(PtType::Mbr), None),
+            ]
+        );
+    }
+}
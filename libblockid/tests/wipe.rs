@@ -0,0 +1,226 @@
+//! Erases signatures with `Wiper`, checking that every copy of a signature is
+//! erased and nothing else on the device is touched.
+
+#![cfg(all(feature = "std", feature = "os_calls"))]
+
+mod common;
+
+use std::fs;
+
+use libblockid::{
+    ProbeFlags, SignatureType, Wiper,
+    filesystem::{FsFilter, FsType},
+    partition::{PtFilter, PtType},
+};
+
+use common::{Image, OFFSETS, TempImage};
+
+fn wiper(img: &TempImage, offset: u64) -> Wiper {
+    Wiper::open(img.path(), ProbeFlags::empty(), offset).unwrap()
+}
+
+fn found(img: &TempImage, offset: u64) -> Vec<SignatureType> {
+    wiper(img, offset)
+        .probe_all_signatures()
+        .filter(|(_, result)| result.is_ok())
+        .map(|(kind, _)| kind)
+        .collect()
+}
+
+/// Returns the offsets of bytes that differ between `img` and `expected`,
+/// other than those at `erased`.
+fn changed(img: &TempImage, expected: &Image, erased: &[(u64, usize)]) -> Vec<u64> {
+    let bytes = fs::read(img.path()).unwrap();
+
+    for &(offset, len) in erased {
+        let range = offset as usize..offset as usize + len;
+        assert!(
+            bytes[range].iter().all(|b| *b == 0),
+            "{offset:#x} not erased"
+        );
+    }
+
+    bytes
+        .iter()
+        .zip(expected.bytes())
+        .enumerate()
+        .filter(|(i, (a, b))| {
+            a != b
+                && !erased
+                    .iter()
+                    .any(|&(o, l)| (o as usize..o as usize + l).contains(i))
+        })
+        .map(|(i, _)| i as u64)
+        .collect()
+}
+
+#[test]
+fn luks2_secondary_headers() {
+    for offset in OFFSETS {
+        let expected = Image::embed(&common::luks2(b""), offset, 4096);
+        let img = expected.write("wipe");
+
+        let regions = wiper(&img, offset)
+            .wipe_all(FsFilter::empty(), PtFilter::empty())
+            .unwrap();
+
+        let erased: Vec<_> = regions
+            .iter()
+            .map(|r| (r.kind, r.offset, r.backup.clone()))
+            .collect();
+        let luks2 = SignatureType::Filesystem(FsType::LUKS2);
+
+        assert_eq!(
+            erased,
+            [
+                (luks2, offset, b"LUKS\xba\xbe".to_vec()),
+                (luks2, offset + 0x4000, b"SKUL\xba\xbe".to_vec()),
+            ]
+        );
+        assert!(found(&img, offset).is_empty());
+        assert!(changed(&img, &expected, &[(offset, 6), (offset + 0x4000, 6)]).is_empty());
+    }
+}
+
+#[test]
+fn gpt_backup_header() {
+    let mut disk = common::gpt();
+    let last = disk.len() - 512;
+    let header = disk.bytes()[512..1024].to_vec();
+    disk.put(last, &header);
+
+    for offset in OFFSETS {
+        let expected = Image::embed(&disk, offset, 0);
+        let img = expected.write("wipe");
+
+        let regions = wiper(&img, offset)
+            .wipe_all(FsFilter::all(), PtFilter::empty())
+            .unwrap();
+
+        let gpt: Vec<_> = regions
+            .iter()
+            .filter(|r| r.kind == SignatureType::PartTable(PtType::Gpt))
+            .map(|r| r.offset)
+            .collect();
+
+        assert_eq!(gpt, [offset + 512, offset + 510, offset + last]);
+        assert!(found(&img, offset).is_empty());
+        assert_eq!(
+            fs::read(img.path()).unwrap()[offset as usize + 510..offset as usize + 512],
+            [0, 0]
+        );
+
+        let erased: Vec<_> = regions.iter().map(|r| (r.offset, r.backup.len())).collect();
+        assert!(changed(&img, &expected, &erased).is_empty());
+    }
+}
+
+#[test]
+fn btrfs_mirrors() {
+    let mut fs = Image::new(0x4001000);
+    common::btrfs_super(&mut fs, 0x10000, "btrfslabel");
+    common::btrfs_super(&mut fs, 0x4000000, "btrfslabel");
+
+    let expected = Image::embed(&fs, 0x10000, 4096);
+    let img = expected.write("wipe");
+
+    let regions = wiper(&img, 0x10000)
+        .wipe_all(FsFilter::empty(), PtFilter::empty())
+        .unwrap();
+
+    let erased: Vec<_> = regions.iter().map(|r| (r.offset, r.backup.len())).collect();
+
+    assert_eq!(erased, [(0x20040, 8), (0x4010040, 8)]);
+    assert!(found(&img, 0x10000).is_empty());
+    assert!(changed(&img, &expected, &erased).is_empty());
+}
+
+#[test]
+fn dry_run() {
+    let expected = Image::embed(&common::luks2(b""), 0x10000, 4096);
+    let img = expected.write("wipe");
+
+    let mut wiper = wiper(&img, 0x10000);
+    wiper.set_dry_run(true);
+
+    let regions = wiper
+        .wipe_all(FsFilter::empty(), PtFilter::empty())
+        .unwrap();
+
+    assert_eq!(regions.len(), 2);
+    assert_eq!(fs::read(img.path()).unwrap(), expected.bytes());
+}
+
+#[test]
+fn backup_before_wipe() {
+    let expected = Image::embed(&common::luks2(b""), 0x10000, 4096);
+    let img = expected.write("wipe");
+
+    let mut seen = Vec::new();
+    let regions = wiper(&img, 0x10000)
+        .wipe_all_with(FsFilter::empty(), PtFilter::empty(), |region| {
+            let bytes = fs::read(img.path()).unwrap();
+            let start = region.offset as usize;
+
+            assert_eq!(bytes[start..start + region.backup.len()], region.backup);
+            seen.push(region.offset);
+
+            Ok(())
+        })
+        .unwrap();
+
+    assert_eq!(seen, regions.iter().map(|r| r.offset).collect::<Vec<_>>());
+    assert!(found(&img, 0x10000).is_empty());
+}
+
+#[test]
+fn filtered_types() {
+    let mut stale = common::fat16(b"FAT16VOLUME");
+    let ext = common::ext("ext4label", 0x0004, 0x0040);
+    stale.put(1024, &ext.bytes()[1024..2048]);
+
+    for offset in OFFSETS {
+        let img = Image::embed(&stale, offset, 4096).write("wipe");
+
+        let regions = wiper(&img, offset)
+            .wipe_all(!FsFilter::SKIP_VFAT, PtFilter::all())
+            .unwrap();
+
+        let erased: Vec<_> = regions
+            .iter()
+            .map(|r| (r.kind, r.offset, r.backup.clone()))
+            .collect();
+        let vfat = SignatureType::Filesystem(FsType::Vfat);
+
+        assert_eq!(
+            erased,
+            [
+                (vfat, offset + 0x36, b"FAT16   ".to_vec()),
+                (vfat, offset + 510, vec![0x55, 0xAA]),
+            ]
+        );
+
+        assert_eq!(
+            found(&img, offset),
+            [SignatureType::Filesystem(FsType::Ext4)]
+        );
+    }
+}
+
+#[test]
+fn single_filesystem() {
+    for offset in OFFSETS {
+        let img = Image::embed(&common::xfs("xfslabel"), offset, 4096).write("wipe");
+        let mut wiper = wiper(&img, offset);
+
+        let info = match wiper.probe_all_signatures().next() {
+            Some((_, Ok(libblockid::Signature::Filesystem(info)))) => info,
+            other => panic!("expected xfs, got {other:?}"),
+        };
+
+        let regions = wiper.wipe_filesystem(&info).unwrap();
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].offset, offset);
+        assert!(found(&img, offset).is_empty());
+    }
+}
@@ -0,0 +1,64 @@
+use serde::Serialize;
+use serde_dotenv::{Error, to_string};
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Kind {
+    Ext4,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+struct Inner {
+    label: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+struct Outer {
+    fs_type: Kind,
+    size: u64,
+    magic: Vec<u8>,
+    uuid: Option<&'static str>,
+    #[serde(flatten)]
+    inner: Inner,
+}
+
+#[test]
+fn flat_struct() {
+    let out = to_string(&Outer {
+        fs_type: Kind::Ext4,
+        size: 4096,
+        magic: vec![0x53, 0xef],
+        uuid: None,
+        inner: Inner {
+            label: "a \"b\" $c",
+        },
+    })
+    .unwrap();
+
+    assert_eq!(
+        out,
+        "FS_TYPE=\"ext4\"\nSIZE=4096\nMAGIC=\"83 239\"\nLABEL=\"a \\\"b\\\" \\$c\"\n"
+    );
+}
+
+#[test]
+fn rejects_nested_struct() {
+    #[derive(Serialize)]
+    struct Nested {
+        inner: Inner,
+    }
+
+    let err = to_string(&Nested {
+        inner: Inner { label: "x" },
+    })
+    .unwrap_err();
+
+    assert!(matches!(err, Error::Unsupported(_)));
+}
+
+#[test]
+fn rejects_scalar() {
+    assert!(matches!(to_string(&1u8).unwrap_err(), Error::NotAMap));
+}